@@ -3,7 +3,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use flapigen::{JavaConfig, JavaReachabilityFence, LanguageConfig};
+use flapigen::{JavaConfig, JavaException, JavaReachabilityFence, LanguageConfig};
 
 fn main() {
     env_logger::init();
@@ -23,7 +23,11 @@ fn main() {
         JavaReachabilityFence::Std
     } else {
         JavaReachabilityFence::GenerateFence(8)
-    });
+    })
+    .register_exception(
+        JavaException::new("StorageError".into(), "StorageException".into())
+            .variant("NotFound".into(), "StorageNotFoundException".into()),
+    );
 
     let in_src = Path::new("src").join("java_glue.rs.in");
     let test_opt_rsc = Path::new("src").join("test_optional.rs.in");
@@ -44,6 +44,12 @@ import com.example.rust.DropCounter;
 import com.example.rust.LongOperation;
 import com.example.rust.TestReturnInCallback;
 import com.example.rust.ReturnInCallbackTester;
+import com.example.rust.TestExceptionInCallback;
+import com.example.rust.ExceptionInCallbackTester;
+import com.example.rust.TestU8Slices;
+import com.example.rust.TestStorageError;
+import com.example.rust.StorageException;
+import com.example.rust.StorageNotFoundException;
 
 class Main {
     public static void main(String[] args) {
@@ -111,6 +117,8 @@ class Main {
             assert testInner.name.equals("Boo Boo");
 
 	    testResult();
+	    testResultWithErrorType();
+	    testU8Slices();
 	    testNumberInputOutput();
             testDoubleOverload();
 	    testContainers();
@@ -135,6 +143,7 @@ class Main {
 	    testPrematureGc();
             testPartialEq();
             testReturnInCallback();
+            testExceptionInCallback();
         } catch (Throwable ex) {
             ex.printStackTrace();
             System.exit(-1);
@@ -513,9 +522,14 @@ class Main {
 
     private static void testNullString() {
 	System.out.println("check null handling for String");
-	Foo foo = new Foo(17, null);
-	assert foo.calcF(0, 0) == 17;
-	assert foo.getName().equals("");
+	boolean have_npe = false;
+	try {
+	    Foo foo = new Foo(17, null);
+	} catch (NullPointerException ex) {
+	    assert ex.getMessage().equals("name must not be null");
+	    have_npe = true;
+	}
+	assert have_npe;
     }
 
     private static void testGetIDOverloading() {
@@ -746,6 +760,46 @@ class Main {
 	}
     }
 
+    private static void testResultWithErrorType() {
+	try {
+	    assert TestStorageError.lookup("answer") == 42;
+	} catch (StorageException ex) {
+	    assert false;
+	}
+	boolean have_exception = false;
+	try {
+	    TestStorageError.lookup("foo");
+	} catch (StorageNotFoundException ex) {
+	    assert ex.getMessage().equals("key foo not found");
+	    assert ex.getCause() == null;
+	    have_exception = true;
+	} catch (StorageException ex) {
+	    assert false;
+	}
+	assert have_exception;
+	have_exception = false;
+	try {
+	    TestStorageError.lookup("io");
+	} catch (StorageNotFoundException ex) {
+	    assert false;
+	} catch (StorageException ex) {
+	    assert ex.getMessage().equals("i/o error");
+	    assert ex.getCause() != null;
+	    assert ex.getCause().getMessage().equals("disk failure");
+	    have_exception = true;
+	}
+	assert have_exception;
+    }
+
+    private static void testU8Slices() {
+        byte[] data = new byte[] { 1, 2, (byte) 255 };
+        assert TestU8Slices.sum(data) == 258;
+        byte[] rev = TestU8Slices.reversed(data);
+        assert java.util.Arrays.equals(rev, new byte[] { (byte) 255, 2, 1 });
+        TestU8Slices.increment(data);
+        assert java.util.Arrays.equals(data, new byte[] { 2, 3, 0 });
+    }
+
     private static void testPartialEq() throws Exception {
         Boo a = new Boo();
         Boo b = new Boo();
@@ -776,4 +830,20 @@ class Main {
         TestReturnInCallback cb = new JavaTestReturnInCallback();
         ReturnInCallbackTester.run(cb);
     }
+
+    private static class JavaTestExceptionInCallback implements TestExceptionInCallback {
+        @Override
+        public int parse(String s) throws Exception { return Integer.parseInt(s); }
+        @Override
+        public void check(int x) throws Exception {
+            if (x < 0) {
+                throw new IllegalArgumentException("negative: " + x);
+            }
+        }
+    }
+
+    private static void testExceptionInCallback() throws Exception {
+        TestExceptionInCallback cb = new JavaTestExceptionInCallback();
+        ExceptionInCallbackTester.run(cb);
+    }
 }
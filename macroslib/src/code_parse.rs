@@ -19,9 +19,9 @@ use crate::{
     error::{DiagnosticError, Result},
     namegen::new_unique_name,
     source_registry::SourceId,
-    typemap::ast::{normalize_type, DisplayToTokens},
+    typemap::ast::{normalize_type, DisplayToTokens, SpannedSmolStr},
     types::{
-        FnArg, ForeignClassInfo, ForeignEnumInfo, ForeignEnumItem, ForeignInterface,
+        FnArg, ForeignClassInfo, ForeignCode, ForeignEnumInfo, ForeignEnumItem, ForeignInterface,
         ForeignInterfaceMethod, ForeignMethod, MethodAccess, MethodVariant, NamedArg, SelfTypeDesc,
         SelfTypeVariant,
     },
@@ -116,12 +116,14 @@ mod kw {
     custom_keyword!(empty);
     custom_keyword!(interface);
     custom_keyword!(callback);
+    custom_keyword!(option);
 }
 
 struct Attrs {
     doc_comments: Vec<String>,
     derive_list: Vec<String>,
     unknown_attrs: Vec<String>,
+    namespace: Option<String>,
 }
 
 bitflags! {
@@ -129,6 +131,7 @@ bitflags! {
         const DOC = 1;
         const DERIVE = 2;
         const UNKNOWN = 4;
+        const NAMESPACE = 8;
     }
 }
 
@@ -136,6 +139,7 @@ fn parse_attrs(input: ParseStream, flags: ParseAttrsFlags) -> syn::Result<Attrs>
     let mut doc_comments = vec![];
     let mut derive_list = vec![];
     let mut unknown_attrs = vec![];
+    let mut namespace = None;
 
     if input.fork().call(syn::Attribute::parse_outer).is_ok() {
         let attr: Vec<syn::Attribute> = input.call(syn::Attribute::parse_outer)?;
@@ -162,6 +166,22 @@ fn parse_attrs(input: ParseStream, flags: ParseAttrsFlags) -> syn::Result<Attrs>
                         }
                     }
                 }
+                syn::Meta::NameValue(syn::MetaNameValue {
+                    ref path,
+                    lit: syn::Lit::Str(ref lit_str),
+                    ..
+                }) if path.is_ident("namespace")
+                    && flags.contains(ParseAttrsFlags::NAMESPACE) =>
+                {
+                    let ns = lit_str.value();
+                    if ns.split("::").any(|x| syn::parse_str::<Ident>(x).is_err()) {
+                        return Err(syn::Error::new(
+                            lit_str.span(),
+                            "namespace should be in form of `a::b::c`",
+                        ));
+                    }
+                    namespace = Some(ns);
+                }
                 _ if flags.contains(ParseAttrsFlags::UNKNOWN) => {
                     unknown_attrs.push(DisplayToTokens(&meta).to_string());
                 }
@@ -181,6 +201,7 @@ fn parse_attrs(input: ParseStream, flags: ParseAttrsFlags) -> syn::Result<Attrs>
         doc_comments,
         derive_list,
         unknown_attrs,
+        namespace,
     })
 }
 
@@ -194,7 +215,8 @@ fn do_parse_foreigner_class(_lang: Language, input: ParseStream) -> syn::Result<
         doc_comments: class_doc_comments,
         mut derive_list,
         unknown_attrs,
-    } = parse_attrs(&input, ParseAttrsFlags::DERIVE)?;
+        namespace,
+    } = parse_attrs(&input, ParseAttrsFlags::DERIVE | ParseAttrsFlags::NAMESPACE)?;
     assert!(unknown_attrs.is_empty());
 
     debug!(
@@ -209,7 +231,7 @@ fn do_parse_foreigner_class(_lang: Language, input: ParseStream) -> syn::Result<
     braced!(content in input);
 
     let mut rust_self_type = None;
-    let mut foreigner_code = String::new();
+    let mut foreigner_code = Vec::new();
     let mut has_dummy_constructor = false;
     let mut constructor_ret_type: Option<Type> = None;
     let mut methods = Vec::with_capacity(10);
@@ -224,6 +246,7 @@ fn do_parse_foreigner_class(_lang: Language, input: ParseStream) -> syn::Result<
             doc_comments: method_doc_comments,
             derive_list: method_derive_list,
             unknown_attrs: method_unknown_attrs,
+            ..
         } = parse_attrs(&&content, ParseAttrsFlags::UNKNOWN | ParseAttrsFlags::DOC)?;
         assert!(method_derive_list.is_empty());
         let mut access = if content.peek(kw::private) {
@@ -260,9 +283,25 @@ fn do_parse_foreigner_class(_lang: Language, input: ParseStream) -> syn::Result<
                     FOREIGNER_CODE_DEPRECATED, FOREIGN_CODE
                 );
             }
+            let cfg_option = if content.peek(syn::token::Paren) {
+                let params;
+                parenthesized!(params in content);
+                params.parse::<kw::option>()?;
+                params.parse::<Token![=]>()?;
+                let opt: syn::LitStr = params.parse()?;
+                Some(SpannedSmolStr {
+                    sp: opt.span(),
+                    value: opt.value().into(),
+                })
+            } else {
+                None
+            };
             let lit: syn::LitStr = content.parse()?;
-            debug!("foreigner_code {:?}", lit);
-            foreigner_code.push_str(&lit.value());
+            debug!("foreigner_code {:?}, option {:?}", lit, cfg_option);
+            foreigner_code.push(ForeignCode {
+                cfg_option,
+                code: lit.value(),
+            });
             content.parse::<Token![;]>()?;
             continue;
         }
@@ -572,6 +611,7 @@ fn do_parse_foreigner_class(_lang: Language, input: ParseStream) -> syn::Result<
         foreign_code: foreigner_code,
         doc_comments: class_doc_comments,
         derive_list,
+        namespace,
     })
 }
 
@@ -644,10 +684,15 @@ pub(crate) fn parse_fn_args(
                     }
                     args_names.insert(name.clone());
                 }
+                let mut unknown_attrs = Vec::with_capacity(typed_arg.attrs.len());
+                for a in &typed_arg.attrs {
+                    unknown_attrs.push(DisplayToTokens(&a.parse_meta()?).to_string());
+                }
                 FnArg::Default(NamedArg {
                     name,
                     ty: *typed_arg.ty,
                     span,
+                    unknown_attrs,
                 })
             }
         };
@@ -681,7 +726,8 @@ impl Parse for ForeignEnumInfoParser {
             doc_comments: enum_doc_comments,
             derive_list,
             unknown_attrs,
-        } = parse_attrs(&input, ParseAttrsFlags::DERIVE)?;
+            namespace,
+        } = parse_attrs(&input, ParseAttrsFlags::DERIVE | ParseAttrsFlags::NAMESPACE)?;
         assert!(unknown_attrs.is_empty());
         input.parse::<Token![enum]>()?;
         let enum_name = input.parse::<Ident>()?;
@@ -709,6 +755,7 @@ impl Parse for ForeignEnumInfoParser {
             items,
             doc_comments: enum_doc_comments,
             derive_list,
+            namespace,
         }))
     }
 }
@@ -784,6 +831,15 @@ impl Parse for ForeignInterfaceParser {
                 ));
             }
             let fn_args = parse_fn_args(args_in)?.0;
+            if let Some(arg) = fn_args.iter().find_map(|x| match x {
+                FnArg::Default(ref arg) if !arg.unknown_attrs.is_empty() => Some(arg),
+                _ => None,
+            }) {
+                return Err(syn::Error::new(
+                    arg.span,
+                    "attributes for arguments of callback methods are not supported",
+                ));
+            }
             let out_type: syn::ReturnType = item_parser.parse()?;
             item_parser.parse::<Token![;]>()?;
             let span = rust_func_name.span();
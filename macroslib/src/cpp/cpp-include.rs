@@ -389,6 +389,11 @@ foreign_typemap!(
         "std::string_view{ $p.data, $p.len }";
     ($p:f_type, option = "CppStrView::Std17", req_modules = ["\"rust_str.h\"", "<string_view>"]) <= "std::string_view"
         "CRustStrView{ $p.data(), $p.size() }";
+
+    ($p:f_type, option = "CppStrView::Bundled", req_modules = ["\"rust_str.h\"", "\"rust_string_view_impl.hpp\""]) => "RustStringView"
+        "RustStringView{ $p.data, $p.len }";
+    ($p:f_type, option = "CppStrView::Bundled", req_modules = ["\"rust_str.h\"", "\"rust_string_view_impl.hpp\""]) <= "RustStringView"
+        "CRustStrView{ $p.data(), $p.size() }";
 );
 
 foreign_typemap!(
@@ -402,13 +407,13 @@ foreign_typemap!(
         }
 
         #[no_mangle]
-        pub extern "C" fn crust_string_free(x: CRustString) {
+        pub extern "C" fn CRustString_free(x: CRustString) {
             let s = unsafe { String::from_raw_parts(x.data as *mut u8, x.len, x.capacity) };
             drop(s);
         }
 
         #[no_mangle]
-        pub extern "C" fn crust_string_clone(x: CRustString) -> CRustString {
+        pub extern "C" fn CRustString_clone(x: CRustString) -> CRustString {
             let s = unsafe { String::from_raw_parts(x.data as *mut u8, x.len, x.capacity) };
             let ret = CRustString::from_string(s.clone());
             ::std::mem::forget(s);
@@ -417,6 +422,13 @@ foreign_typemap!(
     );
     foreign_code!(module = "rust_str.h";
                     r##"
+// names of CRustString_free and CRustString_clone in previous versions
+#define crust_string_free CRustString_free
+#define crust_string_clone CRustString_clone
+"##
+    );
+    foreign_code!(module = "rust_str.h";
+                    r##"
 #ifdef __cplusplus
 
 #include <string>
@@ -431,6 +443,11 @@ foreign_typemap!(
                     option = "CppStrView::Boost";
                     r##"
 #include <boost/utility/string_view.hpp>
+"##);
+    foreign_code!(module = "rust_str.h";
+                    option = "CppStrView::Bundled";
+                    r##"
+#include "rust_string_view_impl.hpp"
 "##);
     foreign_code!(module = "rust_str.h";
                     r##"
@@ -445,14 +462,14 @@ public:
     }
     RustString() noexcept { reset(*this); }
     RustString(const RustString &o) noexcept
-        : RustString(crust_string_clone(o))
+        : RustString(CRustString_clone(o))
     {
     }
     RustString &operator=(const RustString &o) noexcept
     {
         if (this != &o) {
             free_mem();
-            auto copy = crust_string_clone(o);
+            auto copy = CRustString_clone(o);
             data = copy.data;
             len = copy.len;
             capacity = copy.capacity;
@@ -492,13 +509,18 @@ public:
                     r#"
     boost::string_view to_boost_string_view() const { return boost::string_view{ data, len }; }
 "#);
+    foreign_code!(module = "rust_str.h";
+                    option = "CppStrView::Bundled";
+                    r##"
+    RustStringView to_string_view() const { return RustStringView(data, len); }
+"##);
     foreign_code!(module = "rust_str.h";
                     r##"
 private:
     void free_mem() noexcept
     {
         if (data != nullptr) {
-            crust_string_free(*this);
+            CRustString_free(*this);
             reset(*this);
         }
     }
@@ -538,7 +560,7 @@ foreign_typemap!(
         };
     };
     ($p:f_type, unique_prefix = "/*opt ref*/", req_modules = ["\"rust_option.h\""]) <= "/*opt ref*/const swig_f_type!(T) *" r#"
-        $out = CRustClassOpt!() { ($p != nullptr) ? static_cast<swig_f_type!(T)Opaque *>(* $p) : nullptr };
+        $out = CRustClassOpt!() { ($p != nullptr) ? static_cast<swig_f_type!(T)::CForeignType *>(* $p) : nullptr };
 "#;
 );
 
@@ -561,7 +583,7 @@ foreign_typemap!(
         };
     };
     ($p:f_type, unique_prefix = "/*opt mut ref*/", req_modules = ["\"rust_option.h\""]) <= "/*opt mut ref*/swig_f_type!(T) *" r#"
-        $out = CRustClassOptMut!() { ($p != nullptr) ? static_cast<swig_f_type!(T)Opaque *>(* $p) : nullptr };
+        $out = CRustClassOptMut!() { ($p != nullptr) ? static_cast<swig_f_type!(T)::CForeignType *>(* $p) : nullptr };
 "#;
 );
 
@@ -629,6 +651,17 @@ foreign_typemap!(
         } else {
             $out.is_some = 0;
         }"#;
+
+    ($p:f_type, option = "CppOptional::Bundled", req_modules = ["\"rust_option.h\"", "\"rust_optional_impl.hpp\""]) => "RustOptional<swig_f_type!(T)>"
+        "($p.is_some != 0) ? RustOptional<swig_f_type!(T)>(swig_foreign_from_i_type!(T, $p.val.data)) : RustOptional<swig_f_type!(T)>()";
+    ($p:f_type, option = "CppOptional::Bundled", req_modules = ["\"rust_option.h\"", "\"rust_optional_impl.hpp\""]) <= "RustOptional<swig_f_type!(T)>"
+        r#"        $out;
+        if (!!$p) {
+            $out.val.data = swig_foreign_to_i_type!(T, (*$p));
+            $out.is_some = 1;
+        } else {
+            $out.is_some = 0;
+        }"#;
 );
 
 #[allow(dead_code)]
@@ -1036,6 +1069,13 @@ foreign_typemap!(
             std::nullopt :
             std::optional<swig_f_type!(T)> { swig_foreign_from_i_type!(T, $p.data.err) }"#;
 
+    ($p:f_type, option = "CppVariant::Bundled",
+     req_modules = ["\"CRustResModule!().h\"", "\"rust_optional_impl.hpp\""],
+     unique_prefix = "/*res_empty*/") => "/*res_empty*/RustOptional<swig_f_type!(T)>"
+       r#"$p.is_ok != 0 ?
+            RustOptional<swig_f_type!(T)>() :
+            RustOptional<swig_f_type!(T)> { swig_foreign_from_i_type!(T, $p.data.err) }"#;
+
     ($p:r_type) <T> Result<(), T> <= CRustRes!() {
        $out = unsafe {
            if $p.is_ok != 0 {
@@ -1079,6 +1119,22 @@ foreign_typemap!(
             }
             return out;
           }(std::move($p))"#;
+
+   ($p:f_type, option = "CppVariant::Bundled",
+    req_modules = ["\"CRustResModule!().h\"", "\"rust_optional_impl.hpp\"", "<utility>"],
+    unique_prefix = "/*res_empty*/")
+       <= "/*res_empty*/RustOptional<swig_f_type!(T)>"
+       r#"[](RustOptional<swig_f_type!(T)> p) -> CRustRes!() {
+            CRustRes!() out;
+            if (!p) {
+                out.is_ok = 1;
+            } else {
+                swig_f_type!(T) tmp = std::move(*p);
+                out.data.err = swig_foreign_to_i_type!(T, tmp);
+                out.is_ok = 0;
+            }
+            return out;
+          }(std::move($p))"#;
 );
 
 foreign_typemap!(
@@ -1130,6 +1186,11 @@ foreign_typemap!(
               std::variant<swig_f_type!(T1), swig_f_type!(T2)> { swig_foreign_from_i_type!(T1, $p.data.ok) } :
               std::variant<swig_f_type!(T1), swig_f_type!(T2)> { swig_foreign_from_i_type!(T2, $p.data.err) }"#;
 
+   ($p:f_type, option = "CppVariant::Bundled", req_modules = ["\"CRustResModule!().h\"", "\"rust_variant_impl.hpp\""]) => "RustVariant<swig_f_type!(T1), swig_f_type!(T2)>"
+       r#"$p.is_ok != 0 ?
+              RustVariant<swig_f_type!(T1), swig_f_type!(T2)>::ok(swig_foreign_from_i_type!(T1, $p.data.ok)) :
+              RustVariant<swig_f_type!(T1), swig_f_type!(T2)>::err(swig_foreign_from_i_type!(T2, $p.data.err))"#;
+
    ($p:r_type) <T1, T2> Result<T1, T2> <= CRustRes!() {
        $out = unsafe {
            if $p.is_ok != 0 {
@@ -1177,4 +1238,21 @@ foreign_typemap!(
             }
             return out;
           }(std::move($p))"#;
+
+   ($p:f_type, option = "CppVariant::Bundled",
+    req_modules = ["\"CRustResModule!().h\"", "\"rust_variant_impl.hpp\"", "<utility>"])
+       <= "RustVariant<swig_f_type!(T1), swig_f_type!(T2)>"
+       r#"[](RustVariant<swig_f_type!(T1), swig_f_type!(T2)> p) -> CRustRes!() {
+            CRustRes!() out;
+            if (p.is_ok()) {
+                swig_f_type!(T1) tmp = std::move(p.ok_value());
+                out.data.ok = swig_foreign_to_i_type!(T1, tmp);
+                out.is_ok = 1;
+            } else {
+                swig_f_type!(T2) tmp = std::move(p.err_value());
+                out.data.err = swig_foreign_to_i_type!(T2, tmp);
+                out.is_ok = 0;
+            }
+            return out;
+          }(std::move($p))"#;
 );
@@ -1,14 +1,15 @@
 use std::{borrow::Cow, fmt::Write, mem};
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, TokenStream};
 use quote::ToTokens;
 use rustc_hash::FxHashSet;
 use smol_str::SmolStr;
-use syn::spanned::Spanned;
+use syn::{parse_quote, spanned::Spanned};
 
 use crate::{
     code_parse::parse_fn_args,
     cpp::{map_type::map_repr_c_type, CppContext, CppForeignMethodSignature, MergeCItemsFlags},
+    doc_comments::{convert_doc_comments, find_link_target, DocMarkup},
     error::{panic_on_syn_error, DiagnosticError},
     file_cache::FileWriteCache,
     namegen::new_unique_name,
@@ -18,22 +19,34 @@ use crate::{
         TO_VAR_TEMPLATE, TO_VAR_TYPE_TEMPLATE,
     },
     types::{FnArg, ForeignClassInfo, ForeignEnumInfo},
-    WRITE_TO_MEM_FAILED_MSG,
+    CppConfig, TypeMap, WRITE_TO_MEM_FAILED_MSG,
 };
 
+/// Doxygen comments, intra-doc links are resolved to C++ names of classes and methods
 pub(in crate::cpp) fn doc_comments_to_c_comments(
+    conv_map: &TypeMap,
     doc_comments: &[String],
     class_comments: bool,
 ) -> String {
+    let resolve_link = |link: &str| {
+        find_link_target(conv_map.foreign_classes(), link).map(|(class, member)| match member {
+            Some(member) => format!("{}::{}", cpp_class_name(class), member),
+            None => cpp_class_name(class),
+        })
+    };
+    let lines = convert_doc_comments(doc_comments, DocMarkup::Doxygen, &resolve_link);
     let mut comments = String::new();
-    for (i, comment) in doc_comments.iter().enumerate() {
+    for (i, line) in lines.iter().enumerate() {
         if i != 0 {
             comments.push('\n');
         }
         if !class_comments {
             comments.push_str("    ");
         }
-        write!(&mut comments, "//{}", comment.trim()).unwrap();
+        comments.push_str("///");
+        if !line.is_empty() {
+            write!(&mut comments, " {}", line).expect(WRITE_TO_MEM_FAILED_MSG);
+        }
     }
     comments
 }
@@ -54,14 +67,221 @@ where
         write!(&mut buf, "{} {}", f_type_info.as_ref().name, arg_name)
             .expect(WRITE_TO_MEM_FAILED_MSG);
     }
+    if let Some(res) = f_method.c_result_out.as_ref() {
+        let out_args = res
+            .ok
+            .as_ref()
+            .map(|(_, fti)| (fti, &res.ok_out_name))
+            .into_iter()
+            .chain(Some((&res.err.1, &res.err_out_name)));
+        for (f_type_info, arg_name) in out_args {
+            if !buf.is_empty() {
+                buf.push_str(", ");
+            }
+            write!(&mut buf, "{} *{}", f_type_info.as_ref().name, arg_name)
+                .expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+    }
     if !buf.is_empty() && append_comma_if_not_empty {
         buf.push_str(", ");
     }
     buf
 }
 
-pub(in crate::cpp) fn c_class_type(class: &ForeignClassInfo) -> String {
-    format!("{}Opaque", class.name)
+pub(in crate::cpp) fn c_class_type(cfg: &CppConfig, class: &ForeignClassInfo) -> String {
+    format!(
+        "{}{}Opaque",
+        c_name_prefix(cfg, class.namespace.as_deref()),
+        class.name
+    )
+}
+
+/// Prefix for C names of items inside `#[namespace]`,
+/// C has no namespaces, so `a::b` becomes `a_b_`,
+/// plus `CppConfig::symbol_prefix` and `CppConfig::symbol_version`
+pub(in crate::cpp) fn c_name_prefix(cfg: &CppConfig, namespace: Option<&str>) -> String {
+    let mut ret = c_symbol_prefix(cfg);
+    ret.push_str(&c_namespace_prefix(namespace));
+    ret
+}
+
+/// `a_b_` for `#[namespace = "a::b"]`, without `CppConfig::symbol_prefix`
+pub(in crate::cpp) fn c_namespace_prefix(namespace: Option<&str>) -> String {
+    match namespace {
+        Some(ns) => format!("{}_", ns.replace("::", "_")),
+        None => String::new(),
+    }
+}
+
+/// Prefix from `CppConfig::symbol_prefix` and `CppConfig::symbol_version`
+/// for all exported C functions and C types
+pub(in crate::cpp) fn c_symbol_prefix(cfg: &CppConfig) -> String {
+    let mut ret = String::new();
+    for part in cfg.symbol_prefix.iter().chain(cfg.symbol_version.iter()) {
+        write!(&mut ret, "{}_", part).expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    ret
+}
+
+/// C++ name of class, relative to `CppConfig::namespace_name`
+pub(in crate::cpp) fn cpp_class_name(class: &ForeignClassInfo) -> String {
+    qualified_name(class.namespace.as_deref(), &class.name)
+}
+
+/// C++ name of enum, relative to `CppConfig::namespace_name`
+pub(in crate::cpp) fn cpp_enum_name(enum_info: &ForeignEnumInfo) -> String {
+    qualified_name(enum_info.namespace.as_deref(), &enum_info.name)
+}
+
+fn qualified_name(namespace: Option<&str>, name: &Ident) -> String {
+    match namespace {
+        Some(ns) => format!("{}::{}", ns, name),
+        None => name.to_string(),
+    }
+}
+
+/// Directory for headers of items inside `#[namespace]`,
+/// relative to output directory, `a::b` becomes `a/b/`
+pub(in crate::cpp) fn header_dir(namespace: Option<&str>) -> String {
+    match namespace {
+        Some(ns) => format!("{}/", ns.replace("::", "/")),
+        None => String::new(),
+    }
+}
+
+/// Open `CppConfig::namespace_name` and nested `#[namespace]`,
+/// C++11 has no `namespace a::b {` syntax
+pub(in crate::cpp) fn namespace_begin(namespace_name: &str, namespace: Option<&str>) -> String {
+    let mut ret = format!("namespace {} {{", namespace_name);
+    for part in namespace.into_iter().flat_map(|ns| ns.split("::")) {
+        write!(&mut ret, "\nnamespace {} {{", part).expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    ret
+}
+
+/// Close namespaces opened by `namespace_begin`
+pub(in crate::cpp) fn namespace_end(namespace_name: &str, namespace: Option<&str>) -> String {
+    let mut ret = String::new();
+    for part in namespace.into_iter().flat_map(|ns| ns.rsplit("::")) {
+        writeln!(&mut ret, "}} // namespace {}", part).expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    write!(&mut ret, "}} // namespace {}", namespace_name).expect(WRITE_TO_MEM_FAILED_MSG);
+    ret
+}
+
+/// Add `c_symbol_prefix` to names of C types and C functions defined in typemaps,
+/// names of C items for classes, enums and callbacks already have it, see `c_name_prefix`.
+/// String literals (names of headers) and comments are not changed.
+pub(in crate::cpp) fn prefix_c_items(
+    cfg: &CppConfig,
+    c_items: &FxHashSet<SmolStr>,
+    file: &mut FileWriteCache,
+) {
+    let symbol_prefix = c_symbol_prefix(cfg);
+    if symbol_prefix.is_empty() || c_items.is_empty() {
+        return;
+    }
+    let cnt = file.take_content();
+    let code = String::from_utf8(cnt).expect("generated code should be valid utf-8");
+    let bytes = code.as_bytes();
+    let is_ident_char = |ch: u8| ch.is_ascii_alphanumeric() || ch == b'_';
+    let mut ret = String::with_capacity(code.len());
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let start = pos;
+        match bytes[pos] {
+            b'"' | b'\'' => {
+                let quote = bytes[pos];
+                pos += 1;
+                while pos < bytes.len() && bytes[pos] != quote && bytes[pos] != b'\n' {
+                    if bytes[pos] == b'\\' {
+                        pos += 1;
+                    }
+                    pos += 1;
+                }
+                pos = (pos + 1).min(bytes.len());
+            }
+            b'/' if bytes.get(pos + 1) == Some(&b'/') => {
+                pos = code[pos..].find('\n').map_or(bytes.len(), |n| pos + n);
+            }
+            b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+                pos = code[pos + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |n| pos + 2 + n + 2);
+            }
+            ch if is_ident_char(ch) => {
+                while pos < bytes.len() && is_ident_char(bytes[pos]) {
+                    pos += 1;
+                }
+                if c_items.contains(&code[start..pos]) {
+                    ret.push_str(&symbol_prefix);
+                }
+            }
+            _ => {
+                pos += code[pos..].chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        ret.push_str(&code[start..pos]);
+    }
+    file.replace_content(ret.into_bytes());
+}
+
+/// For pure C API: remove `#ifdef __cplusplus` sections with C++ code
+/// (classes, includes of `.hpp`), only `extern "C"` guards and `static_assert` stay
+pub(in crate::cpp) fn strip_cpp_only_code(file: &mut FileWriteCache) {
+    let cnt = file.take_content();
+    let code = String::from_utf8(cnt).expect("generated code should be valid utf-8");
+    let mut ret = String::with_capacity(code.len());
+    let mut lines = code.split_inclusive('\n');
+    while let Some(line) = lines.next() {
+        if line.trim() != "#ifdef __cplusplus" {
+            ret.push_str(line);
+            continue;
+        }
+        let mut section = String::new();
+        let mut else_part = String::new();
+        let mut in_else = false;
+        let mut depth = 0;
+        for line in lines.by_ref() {
+            let directive = line.trim();
+            if directive.starts_with("#if") {
+                depth += 1;
+            } else if directive.starts_with("#endif") {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            } else if directive.starts_with("#else") && depth == 0 {
+                in_else = true;
+                continue;
+            }
+            if in_else {
+                else_part.push_str(line);
+            } else {
+                section.push_str(line);
+            }
+        }
+        let only_c_compatible = section.lines().map(str::trim).all(|l| {
+            l.is_empty()
+                || l == "extern \"C\" {"
+                || l == "}"
+                || l == "} // extern \"C\" {"
+                || l.starts_with("static_assert(")
+                || l.starts_with('"')
+        });
+        if only_c_compatible {
+            ret.push_str("#ifdef __cplusplus\n");
+            ret.push_str(&section);
+            if in_else {
+                ret.push_str("#else\n");
+                ret.push_str(&else_part);
+            }
+            ret.push_str("#endif\n");
+        } else {
+            ret.push_str(&else_part);
+        }
+    }
+    file.replace_content(ret.into_bytes());
 }
 
 pub(in crate::cpp) fn cpp_generate_args_with_types<'a, NI: Iterator<Item = &'a str>>(
@@ -156,15 +376,44 @@ pub(in crate::cpp) fn convert_args<'a, NI: Iterator<Item = &'a str>>(
 }
 
 pub(in crate::cpp) fn cpp_header_name(class: &ForeignClassInfo) -> String {
-    format!("{}.hpp", class.name)
+    format!(
+        "{}{}.hpp",
+        header_dir(class.namespace.as_deref()),
+        class.name
+    )
 }
 
 pub(in crate::cpp) fn c_header_name(class: &ForeignClassInfo) -> String {
-    format!("c_{}.h", class.name)
+    format!(
+        "{}c_{}.h",
+        header_dir(class.namespace.as_deref()),
+        class.name
+    )
 }
 
 pub(in crate::cpp) fn cpp_header_name_for_enum(enum_info: &ForeignEnumInfo) -> String {
-    format!("{}.hpp", enum_info.name)
+    format!(
+        "{}{}.hpp",
+        header_dir(enum_info.namespace.as_deref()),
+        enum_info.name
+    )
+}
+
+pub(in crate::cpp) fn c_header_name_for_enum(enum_info: &ForeignEnumInfo) -> String {
+    format!(
+        "{}c_{}.h",
+        header_dir(enum_info.namespace.as_deref()),
+        enum_info.name
+    )
+}
+
+/// Only C headers from list of includes, for pure C API
+pub(in crate::cpp) fn c_includes_only(includes: &[SmolStr]) -> Vec<SmolStr> {
+    includes
+        .iter()
+        .filter(|inc| *inc != "<stdint.h>" && (inc.ends_with(".h\"") || inc.ends_with(".h>")))
+        .cloned()
+        .collect()
 }
 
 pub(in crate::cpp) fn cpp_list_required_includes(
@@ -176,6 +425,11 @@ pub(in crate::cpp) fn cpp_list_required_includes(
             includes.extend(mem::replace(&mut p.provides_by_module, Vec::new()).into_iter());
         }
         includes.extend(mem::replace(&mut m.output.provides_by_module, Vec::new()).into_iter());
+        if let Some(res) = m.c_result_out.as_mut() {
+            for (_, fti) in res.ok.iter_mut().chain(Some(&mut res.err)) {
+                includes.append(&mut fti.provides_by_module);
+            }
+        }
     }
 
     // prerserve order of includes
@@ -208,7 +462,17 @@ pub(in crate::cpp) fn generate_c_type(
                     }
                 }
                 add_func_forward_decl(ctx, f, src_id, module_name)?;
-                ctx.rust_code.push(f.into_token_stream());
+                let symbol_prefix = c_symbol_prefix(ctx.cfg);
+                if symbol_prefix.is_empty() {
+                    ctx.rust_code.push(f.into_token_stream());
+                } else {
+                    let mut f = f.clone();
+                    let export_name = format!("{}{}", symbol_prefix, f.sig.ident);
+                    f.attrs.retain(|a| !a.path.is_ident("no_mangle"));
+                    f.attrs.push(parse_quote!(#[export_name = #export_name]));
+                    ctx.prefixed_c_items.insert(f.sig.ident.to_string().into());
+                    ctx.rust_code.push(f.into_token_stream());
+                }
                 {
                     let common_files = &mut ctx.common_files;
                     let out: &mut FileWriteCache = file_for_module!(ctx, common_files, module_name);
@@ -277,6 +541,9 @@ fn do_generate_c_type(
             return Ok(());
         }
     }
+    if !c_symbol_prefix(ctx.cfg).is_empty() {
+        ctx.prefixed_c_items.insert(ctype.name().to_string().into());
+    }
     let mut rust_layout_test = format!(
         r#"
 #[allow(non_snake_case)]
@@ -469,6 +736,11 @@ extern "C" {
         }
     }
 
+    if fn_args.is_empty() {
+        fn_decl_out
+            .write_all(b"void")
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
     fn_decl_out.write_all(b");").expect(WRITE_TO_MEM_FAILED_MSG);
 
     let common_files = &mut ctx.common_files;
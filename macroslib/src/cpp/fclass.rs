@@ -1,4 +1,4 @@
-use std::{borrow::Cow, io::Write};
+use std::{borrow::Cow, io::Write, path::Path};
 
 use log::debug;
 use petgraph::Direction;
@@ -10,15 +10,19 @@ use syn::{spanned::Spanned, Ident, Type};
 
 use crate::{
     cpp::{
-        c_func_name, cpp_code, do_c_func_name, map_type::map_type, CppContext,
-        CppForeignMethodSignature, CppForeignTypeInfo, MethodContext,
+        all_cfg_options, c_func_name, cfg_options, cpp_code, do_c_func_name, map_type::map_type,
+        CResultOut, CppContext, CppForeignMethodSignature, CppForeignTypeInfo, MethodContext,
+        RUST_RESULT_EXCEPTION_HEADER,
     },
     error::{panic_on_syn_error, DiagnosticError, Result},
     extension::extend_foreign_class,
-    file_cache::FileWriteCache,
+    file_cache::{FileWriteCache, NoNeedFsOpsRegistration},
     namegen::new_unique_name,
     typemap::{
-        ast::{list_lifetimes, strip_lifetimes},
+        ast::{
+            check_if_smart_pointer_return_inner_type, if_result_return_ok_err_types,
+            list_lifetimes, strip_lifetimes,
+        },
         ty::RustType,
         utils::{
             convert_to_heap_pointer, create_suitable_types_for_constructor_and_self,
@@ -28,8 +32,9 @@ use crate::{
         ForeignTypeInfo, TypeConvCodeSubstParam, TypeMap, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
         TO_VAR_TYPE_TEMPLATE,
     },
-    types::{ForeignClassInfo, MethodAccess, MethodVariant, SelfTypeVariant},
-    KNOWN_CLASS_DERIVES, PLAIN_CLASS, SMART_PTR_COPY_TRAIT, WRITE_TO_MEM_FAILED_MSG,
+    types::{ForeignClassInfo, ForeignMethod, MethodAccess, MethodVariant, SelfTypeVariant},
+    KNOWN_CLASS_DERIVES, KNOWN_METHOD_ATTRS, PLAIN_CLASS, RESULT_AS_VARIANT, SHARED_PTR_CLASS,
+    SMART_PTR_COPY_TRAIT, WRITE_TO_MEM_FAILED_MSG,
 };
 
 pub(in crate::cpp) fn generate(ctx: &mut CppContext, class: &ForeignClassInfo) -> Result<()> {
@@ -58,8 +63,17 @@ May be you need to use `private constructor = empty;` syntax?",
         ));
     }
 
+    let mut throws_exceptions = false;
+    for method in &class.methods {
+        if result_ok_type_if_throws(ctx, class, method)?.is_some() {
+            throws_exceptions = true;
+        }
+    }
     let mut m_sigs = find_suitable_foreign_types_for_methods(ctx, class)?;
     let mut req_includes = cpp_code::cpp_list_required_includes(&mut m_sigs);
+    if throws_exceptions {
+        req_includes.push(format!("\"{}\"", RUST_RESULT_EXCEPTION_HEADER).into());
+    }
     let my_self_cpp = format!("\"{}\"", cpp_code::cpp_header_name(class));
     let my_self_c = format!("\"{}\"", cpp_code::c_header_name(class));
     req_includes.retain(|el| *el != my_self_cpp && *el != my_self_c);
@@ -67,6 +81,43 @@ May be you need to use `private constructor = empty;` syntax?",
     Ok(())
 }
 
+/// If method should throw exception instead of returning variant for `Result`,
+/// return type of `Ok` value
+fn result_ok_type_if_throws(
+    ctx: &mut CppContext,
+    class: &ForeignClassInfo,
+    method: &ForeignMethod,
+) -> Result<Option<Type>> {
+    if !ctx.cfg.use_exceptions {
+        return Ok(None);
+    }
+    let ret_ty = match method.fn_decl.output {
+        syn::ReturnType::Default => return Ok(None),
+        syn::ReturnType::Type(_, ref ret_ty) => ret_ty,
+    };
+    let ret_rty = ctx.conv_map.find_or_alloc_rust_type(ret_ty, class.src_id);
+    let ok_ty = match if_result_return_ok_err_types(&ret_rty) {
+        Some((ok_ty, _err_ty)) => ok_ty,
+        None => return Ok(None),
+    };
+    if method.variant == MethodVariant::Constructor {
+        return Err(DiagnosticError::new(
+            class.src_id,
+            ret_ty.span(),
+            format!(
+                "constructor of class {} returns Result, this is not supported by C++ \
+                 in exception mode (CppConfig::use_exceptions), \
+                 use static method that returns Result<{}, Error> instead",
+                class.name, class.name
+            ),
+        ));
+    }
+    if method.unknown_attrs.iter().any(|x| x == RESULT_AS_VARIANT) {
+        return Ok(None);
+    }
+    Ok(Some(ok_ty))
+}
+
 fn do_generate(
     ctx: &mut CppContext,
     class: &ForeignClassInfo,
@@ -78,9 +129,13 @@ fn do_generate(
     let c_path = ctx.cfg.output_dir.join(cpp_code::c_header_name(class));
     let mut c_include_f = FileWriteCache::new(&c_path, ctx.generated_foreign_files);
     let cpp_path = ctx.cfg.output_dir.join(cpp_code::cpp_header_name(class));
-    let mut cpp_include_f = FileWriteCache::new(&cpp_path, ctx.generated_foreign_files);
-    let cpp_fwd_path = ctx.cfg.output_dir.join(format!("{}_fwd.hpp", class.name));
-    let mut cpp_fwd_f = FileWriteCache::new(&cpp_fwd_path, ctx.generated_foreign_files);
+    let mut cpp_include_f = cpp_file_write_cache(ctx, &cpp_path);
+    let cpp_fwd_path = ctx.cfg.output_dir.join(format!(
+        "{}{}_fwd.hpp",
+        cpp_code::header_dir(class.namespace.as_deref()),
+        class.name
+    ));
+    let mut cpp_fwd_f = cpp_file_write_cache(ctx, &cpp_fwd_path);
 
     macro_rules! map_write_err {
         ($file_path:ident) => {
@@ -94,16 +149,32 @@ fn do_generate(
         };
     }
 
-    let c_class_type = cpp_code::c_class_type(class);
-    let class_doc_comments = cpp_code::doc_comments_to_c_comments(&class.doc_comments, true);
+    let c_class_type = cpp_code::c_class_type(ctx.cfg, class);
+    let class_doc_comments =
+        cpp_code::doc_comments_to_c_comments(ctx.conv_map, &class.doc_comments, true);
 
-    generte_c_header_preamble(ctx, &class_doc_comments, &c_class_type, &mut c_include_f);
+    let c_includes = if ctx.cfg.pure_c_api {
+        cpp_code::c_includes_only(req_includes)
+    } else {
+        vec![]
+    };
+    generte_c_header_preamble(
+        ctx,
+        &class_doc_comments,
+        &c_class_type,
+        &c_includes,
+        &mut c_include_f,
+    );
     let plain_class = need_plain_class(class);
     let class_name = if !plain_class {
         format!("{}Wrapper", class.name)
     } else {
         class.name.to_string()
     };
+    let namespace_begin =
+        cpp_code::namespace_begin(&ctx.cfg.namespace_name, class.namespace.as_deref());
+    let namespace_end =
+        cpp_code::namespace_end(&ctx.cfg.namespace_name, class.namespace.as_deref());
 
     let static_only = class
         .methods
@@ -180,7 +251,10 @@ May be you need to use `private constructor = empty;` syntax?",
 
     for (method, f_method) in class.methods.iter().zip(methods_sign) {
         c_include_f
-            .write_all(cpp_code::doc_comments_to_c_comments(&method.doc_comments, false).as_bytes())
+            .write_all(
+                cpp_code::doc_comments_to_c_comments(ctx.conv_map, &method.doc_comments, false)
+                    .as_bytes(),
+            )
             .expect(WRITE_TO_MEM_FAILED_MSG);
 
         let method_access = match method.access {
@@ -196,11 +270,20 @@ May be you need to use `private constructor = empty;` syntax?",
         }
         last_cpp_access = Some(method_access);
         cpp_include_f
-            .write_all(cpp_code::doc_comments_to_c_comments(&method.doc_comments, false).as_bytes())
+            .write_all(
+                cpp_code::doc_comments_to_c_comments(ctx.conv_map, &method.doc_comments, false)
+                    .as_bytes(),
+            )
             .expect(WRITE_TO_MEM_FAILED_MSG);
-        let c_func_name = c_func_name(class, method);
+        let c_func_name = c_func_name(ctx.cfg, class, method);
         let c_args_with_types =
             cpp_code::c_generate_args_with_types(f_method, method.arg_names_without_self(), false);
+        // `f()` in C means "unspecified arguments"
+        let c_decl_args: &str = if c_args_with_types.is_empty() {
+            "void"
+        } else {
+            &c_args_with_types
+        };
         let comma_c_args_with_types = if c_args_with_types.is_empty() {
             String::new()
         } else {
@@ -279,6 +362,24 @@ May be you need to use `private constructor = empty;` syntax?",
             )
             .expect(WRITE_TO_MEM_FAILED_MSG);
         }
+        if let Some(res) = f_method.c_result_out.as_ref() {
+            if let Some((_, ref ok_fti)) = res.ok {
+                write!(
+                    &mut rust_args_with_types,
+                    "{}: *mut {}, ",
+                    res.ok_out_name,
+                    ok_fti.base.correspoding_rust_type.typename(),
+                )
+                .expect(WRITE_TO_MEM_FAILED_MSG);
+            }
+            write!(
+                &mut rust_args_with_types,
+                "{}: *mut {}, ",
+                res.err_out_name,
+                res.err.1.base.correspoding_rust_type.typename(),
+            )
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+        }
 
         let method_ctx = MethodContext {
             class,
@@ -296,6 +397,7 @@ May be you need to use `private constructor = empty;` syntax?",
         } else {
             &f_method.output
         };
+        let mut conv_ret_decl = None;
         let (cpp_ret_type, convert_ret_for_cpp) = match f_output_type.cpp_converter.as_ref() {
             Some(cpp_converter) => {
                 let cpp_ret_type = cpp_converter.typename.clone();
@@ -323,11 +425,16 @@ May be you need to use `private constructor = empty;` syntax?",
                                 }
                             })?;
                     if cpp_converter.converter.has_param(TO_VAR_TYPE_TEMPLATE) {
+                        conv_ret_decl = Some(conv_code.clone());
                         (
                             cpp_ret_type,
                             format!("{}\n        return {};", conv_code, conv_ret),
                         )
                     } else {
+                        conv_ret_decl = Some(format!(
+                            "        {} {} = {};",
+                            cpp_ret_type, conv_ret, conv_code
+                        ));
                         (cpp_ret_type, format!("        return {};", conv_code))
                     }
                 } else {
@@ -339,6 +446,48 @@ May be you need to use `private constructor = empty;` syntax?",
                 format!("        return {};", ret_name),
             ),
         };
+        let (cpp_ret_type, convert_ret_for_cpp, noexcept) =
+            match (result_ok_type_if_throws(ctx, class, method)?, conv_ret_decl) {
+                (Some(_), None) => {
+                    return Err(DiagnosticError::new(
+                        class.src_id,
+                        method.span(),
+                        format!(
+                            "method returns Result, but C++ type {} has no conversion \
+                             to variant, so exception can not be thrown, \
+                             mark method with #[{}]",
+                            f_output_type.as_ref().name,
+                            RESULT_AS_VARIANT
+                        ),
+                    ));
+                }
+                (Some(ok_ty), Some(conv_ret_decl)) => {
+                    let ok_cpp_type = if ok_ty == parse_type! { () } {
+                        "void".into()
+                    } else {
+                        let ok_rty = ctx.conv_map.find_or_alloc_rust_type(&ok_ty, class.src_id);
+                        let ok_ftype = map_type(
+                            ctx,
+                            &ok_rty,
+                            Direction::Outgoing,
+                            (class.src_id, method.span()),
+                        )?;
+                        match ok_ftype.cpp_converter {
+                            Some(cpp_converter) => cpp_converter.typename,
+                            None => ok_ftype.base.name,
+                        }
+                    };
+                    (
+                        ok_cpp_type,
+                        format!(
+                            "{}\n        return rust_result_unwrap(std::move({}));",
+                            conv_ret_decl, conv_ret
+                        ),
+                        "",
+                    )
+                }
+                _ => (cpp_ret_type, convert_ret_for_cpp, " noexcept"),
+            };
         //rename types like "struct Foo" to "Foo" to make VC++ compiler happy
         let cpp_ret_type = cpp_ret_type.display().replace("struct", "");
 
@@ -361,17 +510,18 @@ May be you need to use `private constructor = empty;` syntax?",
     {ret_type} {c_func_name}({args_with_types});"#,
                     ret_type = f_method.output.as_ref().name,
                     c_func_name = c_func_name,
-                    args_with_types = c_args_with_types,
+                    args_with_types = c_decl_args,
                 )
                 .expect(WRITE_TO_MEM_FAILED_MSG);
 
                 writeln!(
                     cpp_include_f,
                     r#"
-    static {cpp_ret_type} {method_name}({cpp_args_with_types}) noexcept;"#,
+    static {cpp_ret_type} {method_name}({cpp_args_with_types}){noexcept};"#,
                     method_name = method_name,
                     cpp_ret_type = cpp_ret_type,
                     cpp_args_with_types = cpp_args_with_types,
+                    noexcept = noexcept,
                 )
                 .expect(WRITE_TO_MEM_FAILED_MSG);
 
@@ -380,7 +530,7 @@ May be you need to use `private constructor = empty;` syntax?",
                         &mut inline_impl,
                         r#"
     template<bool OWN_DATA>
-    inline {cpp_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}) noexcept
+    inline {cpp_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}){noexcept}
     {{
 {conv_args_code}"#,
                         cpp_ret_type = cpp_ret_type,
@@ -388,12 +538,13 @@ May be you need to use `private constructor = empty;` syntax?",
                         method_name = method_name,
                         cpp_args_with_types = cpp_args_with_types,
                         conv_args_code = conv_args_code,
+                        noexcept = noexcept,
                     )
                 } else {
                     write!(
                         &mut inline_impl,
                         r#"
-    inline {cpp_ret_type} {class_name}::{method_name}({cpp_args_with_types}) noexcept
+    inline {cpp_ret_type} {class_name}::{method_name}({cpp_args_with_types}){noexcept}
     {{
 {conv_args_code}"#,
                         cpp_ret_type = cpp_ret_type,
@@ -401,6 +552,7 @@ May be you need to use `private constructor = empty;` syntax?",
                         method_name = method_name,
                         cpp_args_with_types = cpp_args_with_types,
                         conv_args_code = conv_args_code,
+                        noexcept = noexcept,
                     )
                 }
                 .expect(WRITE_TO_MEM_FAILED_MSG);
@@ -440,6 +592,15 @@ May be you need to use `private constructor = empty;` syntax?",
                 } else {
                     ""
                 };
+                let qualifiers = format!(
+                    "{}{}",
+                    if self_variant.is_read_only() {
+                        " const"
+                    } else {
+                        ""
+                    },
+                    noexcept
+                );
                 writeln!(
                     c_include_f,
                     r#"
@@ -455,38 +616,42 @@ May be you need to use `private constructor = empty;` syntax?",
                 writeln!(
                     cpp_include_f,
                     r#"
-    {cpp_ret_type} {method_name}({cpp_args_with_types}) {const_if_readonly}noexcept;"#,
+    {cpp_ret_type} {method_name}({cpp_args_with_types}){qualifiers};"#,
                     method_name = method_name,
                     cpp_ret_type = cpp_ret_type,
                     cpp_args_with_types = cpp_args_with_types,
-                    const_if_readonly = const_if_readonly,
+                    qualifiers = qualifiers,
                 )
                 .expect(WRITE_TO_MEM_FAILED_MSG);
 
                 if !plain_class {
-                    write!(&mut inline_impl, r#"
+                    write!(
+                        &mut inline_impl,
+                        r#"
     template<bool OWN_DATA>
-    inline {cpp_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}) {const_if_readonly}noexcept
+    inline {cpp_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}){qualifiers}
     {{
 {conv_args_code}"#,
-                           cpp_args_with_types = cpp_args_with_types,
-                           method_name = method_name,
-                           class_name = class_name,
-                           cpp_ret_type = cpp_ret_type,
-                           const_if_readonly = const_if_readonly,
-                           conv_args_code = conv_args_code,
+                        cpp_args_with_types = cpp_args_with_types,
+                        method_name = method_name,
+                        class_name = class_name,
+                        cpp_ret_type = cpp_ret_type,
+                        qualifiers = qualifiers,
+                        conv_args_code = conv_args_code,
                     )
                 } else {
-                    write!(&mut inline_impl, r#"
-    inline {cpp_ret_type} {class_name}::{method_name}({cpp_args_with_types}) {const_if_readonly}noexcept
+                    write!(
+                        &mut inline_impl,
+                        r#"
+    inline {cpp_ret_type} {class_name}::{method_name}({cpp_args_with_types}){qualifiers}
     {{
 {conv_args_code}"#,
-                           cpp_args_with_types = cpp_args_with_types,
-                           method_name = method_name,
-                           class_name = class_name,
-                           cpp_ret_type = cpp_ret_type,
-                           const_if_readonly = const_if_readonly,
-                           conv_args_code = conv_args_code,
+                        cpp_args_with_types = cpp_args_with_types,
+                        method_name = method_name,
+                        class_name = class_name,
+                        cpp_ret_type = cpp_ret_type,
+                        qualifiers = qualifiers,
+                        conv_args_code = conv_args_code,
                     )
                 }
                 .expect(WRITE_TO_MEM_FAILED_MSG);
@@ -551,7 +716,7 @@ May be you need to use `private constructor = empty;` syntax?",
     {c_class_type} *{func_name}({args_with_types});"#,
                         c_class_type = c_class_type,
                         func_name = c_func_name,
-                        args_with_types = c_args_with_types,
+                        args_with_types = c_decl_args,
                     )
                     .expect(WRITE_TO_MEM_FAILED_MSG);
 
@@ -603,7 +768,12 @@ May be you need to use `private constructor = empty;` syntax?",
         );
 
         let unpack_code = unpack_from_heap_pointer(&this_type, "this", false);
-        let c_destructor_name = format!("{}_delete", class.name);
+        let c_name_prefix = cpp_code::c_name_prefix(ctx.cfg, class.namespace.as_deref());
+        let c_destructor_name = if ctx.cfg.pure_c_api {
+            format!("{}{}_free", c_name_prefix, class.name)
+        } else {
+            format!("{}{}_delete", c_name_prefix, class.name)
+        };
         let code = format!(
             r#"
 #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
@@ -676,8 +846,9 @@ private:
     )
     .expect(WRITE_TO_MEM_FAILED_MSG);
 
-    if !class.foreign_code.is_empty() {
-        writeln!(cpp_include_f, "\n{}", class.foreign_code).expect(WRITE_TO_MEM_FAILED_MSG);
+    let foreign_code = class.foreign_code_for(&all_cfg_options(), &cfg_options(ctx.cfg))?;
+    if !foreign_code.is_empty() {
+        writeln!(cpp_include_f, "\n{}", foreign_code).expect(WRITE_TO_MEM_FAILED_MSG);
     }
     if !static_only {
         cpp_include_f.write_all(
@@ -701,12 +872,16 @@ private:
             cpp_include_f,
             r#"
 
-}} // namespace {namespace}"#,
-            namespace = ctx.cfg.namespace_name
+{namespace_end}"#,
+            namespace_end = namespace_end,
         )
         .expect(WRITE_TO_MEM_FAILED_MSG);
-        let cpp_impl_path = ctx.cfg.output_dir.join(format!("{}_impl.hpp", class.name));
-        let mut cpp_impl_f = FileWriteCache::new(&cpp_impl_path, ctx.generated_foreign_files);
+        let cpp_impl_path = ctx.cfg.output_dir.join(format!(
+            "{}{}_impl.hpp",
+            cpp_code::header_dir(class.namespace.as_deref()),
+            class.name
+        ));
+        let mut cpp_impl_f = cpp_file_write_cache(ctx, &cpp_impl_path);
         writeln!(
             cpp_impl_f,
             r#"// Automatically generated by flapigen
@@ -714,19 +889,22 @@ private:
 
 #include "{class_name}.hpp"
 
-namespace {namespace} {{"#,
+{namespace_begin}"#,
             class_name = class.name,
-            namespace = ctx.cfg.namespace_name,
+            namespace_begin = namespace_begin,
         )
         .expect(WRITE_TO_MEM_FAILED_MSG);
 
-        write_methods_impls(&mut cpp_impl_f, &ctx.cfg.namespace_name, &inline_impl)
-            .map_err(map_write_err!(cpp_impl_path))?;
-        cpp_impl_f
-            .update_file_if_necessary()
+        write_methods_impls(&mut cpp_impl_f, &namespace_end, &inline_impl)
             .map_err(map_write_err!(cpp_impl_path))?;
+        if !ctx.cfg.pure_c_api {
+            cpp_code::prefix_c_items(ctx.cfg, ctx.prefixed_c_items, &mut cpp_impl_f);
+            cpp_impl_f
+                .update_file_if_necessary()
+                .map_err(map_write_err!(cpp_impl_path))?;
+        }
     } else {
-        write_methods_impls(&mut cpp_include_f, &ctx.cfg.namespace_name, &inline_impl)
+        write_methods_impls(&mut cpp_include_f, &namespace_end, &inline_impl)
             .map_err(map_write_err!(cpp_path))?;
     }
 
@@ -736,13 +914,14 @@ namespace {namespace} {{"#,
             r#"// Automatically generated by flapigen
 #pragma once
 
-namespace {namespace} {{
+{namespace_begin}
 template<bool>
 class {base_class_name};
 using {class_name} = {base_class_name}<true>;
 using {class_name}Ref = {base_class_name}<false>;
-}} // namespace {namespace}"#,
-            namespace = ctx.cfg.namespace_name,
+{namespace_end}"#,
+            namespace_begin = namespace_begin,
+            namespace_end = namespace_end,
             class_name = class.name,
             base_class_name = class_name
         )
@@ -752,38 +931,60 @@ using {class_name}Ref = {base_class_name}<false>;
             r#"// Automatically generated by flapigen
 #pragma once
 
-namespace {namespace} {{
+{namespace_begin}
 class {class_name};
-}} // namespace {namespace}"#,
-            namespace = ctx.cfg.namespace_name,
+{namespace_end}"#,
+            namespace_begin = namespace_begin,
+            namespace_end = namespace_end,
             class_name = class.name,
         )
     }
     .expect(WRITE_TO_MEM_FAILED_MSG);
 
-    cpp_fwd_f
-        .update_file_if_necessary()
-        .map_err(map_write_err!(cpp_fwd_path))?;
-    c_include_f
-        .update_file_if_necessary()
-        .map_err(map_write_err!(c_path))?;
-
-    let mut cnt = cpp_include_f.take_content();
+    // In pure C API mode class extensions are applied to C header
+    let main_f = if ctx.cfg.pure_c_api {
+        &mut c_include_f
+    } else {
+        &mut cpp_include_f
+    };
+    let mut cnt = main_f.take_content();
     extend_foreign_class(
         class,
         &mut cnt,
         &KNOWN_CLASS_DERIVES,
+        &KNOWN_METHOD_ATTRS,
+        &[],
         ctx.class_ext_handlers,
         ctx.method_ext_handlers,
     )?;
-    cpp_include_f.replace_content(cnt);
+    main_f.replace_content(cnt);
 
-    cpp_include_f
+    cpp_code::prefix_c_items(ctx.cfg, ctx.prefixed_c_items, &mut c_include_f);
+    c_include_f
         .update_file_if_necessary()
-        .map_err(map_write_err!(cpp_path))?;
+        .map_err(map_write_err!(c_path))?;
+    if !ctx.cfg.pure_c_api {
+        cpp_code::prefix_c_items(ctx.cfg, ctx.prefixed_c_items, &mut cpp_include_f);
+        cpp_fwd_f
+            .update_file_if_necessary()
+            .map_err(map_write_err!(cpp_fwd_path))?;
+        cpp_include_f
+            .update_file_if_necessary()
+            .map_err(map_write_err!(cpp_path))?;
+    }
     Ok(())
 }
 
+/// C++ headers are generated only to be thrown away in pure C API mode,
+/// so they are not registered as generated files
+fn cpp_file_write_cache(ctx: &mut CppContext, path: &Path) -> FileWriteCache {
+    if ctx.cfg.pure_c_api {
+        FileWriteCache::new(path, &mut NoNeedFsOpsRegistration)
+    } else {
+        FileWriteCache::new(path, ctx.generated_foreign_files)
+    }
+}
+
 fn generate_static_method(conv_map: &mut TypeMap, mc: &MethodContext) -> Result<Vec<TokenStream>> {
     let c_ret_type = mc
         .f_method
@@ -791,14 +992,7 @@ fn generate_static_method(conv_map: &mut TypeMap, mc: &MethodContext) -> Result<
         .as_ref()
         .correspoding_rust_type
         .typename();
-    let (mut deps_code_out, convert_output_code) = foreign_from_rust_convert_method_output(
-        conv_map,
-        mc.class.src_id,
-        &mc.method.fn_decl.output,
-        mc.f_method.output.base.correspoding_rust_type.to_idx(),
-        mc.ret_name,
-        &c_ret_type,
-    )?;
+    let (mut deps_code_out, convert_output_code) = convert_rust_output(conv_map, mc, &c_ret_type)?;
     let (deps_code_in, convert_input_code) = foreign_to_rust_convert_method_inputs(
         conv_map,
         mc.class.src_id,
@@ -857,14 +1051,7 @@ fn generate_method(
         mc.method.arg_names_without_self(),
         &c_ret_type,
     )?;
-    let (mut deps_code_out, convert_output_code) = foreign_from_rust_convert_method_output(
-        conv_map,
-        mc.class.src_id,
-        &mc.method.fn_decl.output,
-        mc.f_method.output.base.correspoding_rust_type.to_idx(),
-        mc.ret_name,
-        &c_ret_type,
-    )?;
+    let (mut deps_code_out, convert_output_code) = convert_rust_output(conv_map, mc, &c_ret_type)?;
     //&mut constructor_real_type -> &mut class.self_type
     let (from_ty, to_ty): (Type, Type) = create_suitable_types_for_constructor_and_self(
         self_variant,
@@ -921,6 +1108,81 @@ pub extern "C" fn {func_name}(this: *mut {this_type}, {decl_func_args}) -> {c_re
     Ok(gen_code)
 }
 
+fn convert_rust_output(
+    conv_map: &mut TypeMap,
+    mc: &MethodContext,
+    c_ret_type: &str,
+) -> Result<(Vec<TokenStream>, String)> {
+    let res = match mc.f_method.c_result_out.as_ref() {
+        Some(res) => res,
+        None => {
+            return foreign_from_rust_convert_method_output(
+                conv_map,
+                mc.class.src_id,
+                &mc.method.fn_decl.output,
+                mc.f_method.output.base.correspoding_rust_type.to_idx(),
+                mc.ret_name,
+                c_ret_type,
+            );
+        }
+    };
+    let span = (mc.class.src_id, mc.method.span());
+    let mut deps = Vec::new();
+    let ok_arm = match res.ok {
+        Some((ref ok_rty, ref ok_fti)) => {
+            let (mut ok_deps, ok_conv) = conv_map.convert_rust_types(
+                ok_rty.to_idx(),
+                ok_fti.base.correspoding_rust_type.to_idx(),
+                mc.ret_name,
+                mc.ret_name,
+                c_ret_type,
+                span,
+            )?;
+            deps.append(&mut ok_deps);
+            format!(
+                r#"Ok({ret}) => {{
+            if !{out}.is_null() {{
+{conv}
+                unsafe {{ {out}.write({ret}) }};
+            }}
+            1
+        }}"#,
+                ret = mc.ret_name,
+                out = res.ok_out_name,
+                conv = ok_conv,
+            )
+        }
+        None => "Ok(_) => 1,".to_string(),
+    };
+    let (err_rty, err_fti) = &res.err;
+    let (mut err_deps, err_conv) = conv_map.convert_rust_types(
+        err_rty.to_idx(),
+        err_fti.base.correspoding_rust_type.to_idx(),
+        mc.ret_name,
+        mc.ret_name,
+        c_ret_type,
+        span,
+    )?;
+    deps.append(&mut err_deps);
+    let code = format!(
+        r#"    let mut {ret}: u8 = match {ret} {{
+        {ok_arm}
+        Err({ret}) => {{
+            if !{err_out}.is_null() {{
+{err_conv}
+                unsafe {{ {err_out}.write({ret}) }};
+            }}
+            0
+        }}
+    }};"#,
+        ret = mc.ret_name,
+        ok_arm = ok_arm,
+        err_out = res.err_out_name,
+        err_conv = err_conv,
+    );
+    Ok((deps, code))
+}
+
 fn generate_constructor(
     conv_map: &mut TypeMap,
     mc: &MethodContext,
@@ -979,15 +1241,15 @@ pub extern "C" fn {func_name}({decl_func_args}) -> *const ::std::os::raw::c_void
 
 fn write_methods_impls(
     file: &mut FileWriteCache,
-    namespace_name: &str,
+    namespace_end: &str,
     inline_impl: &str,
 ) -> std::io::Result<()> {
     writeln!(
         file,
         r#"
 {inline_impl}
-}} // namespace {namespace}"#,
-        namespace = namespace_name,
+{namespace_end}"#,
+        namespace_end = namespace_end,
         inline_impl = inline_impl,
     )
 }
@@ -1023,7 +1285,15 @@ fn find_suitable_foreign_types_for_methods(
                 (class.src_id, named_arg.ty.span()),
             )?);
         }
+        let c_result_out = c_result_out_types(ctx, class, method)?;
         let output: CppForeignTypeInfo = match method.variant {
+            _ if c_result_out.is_some() => ForeignTypeInfo {
+                name: "uint8_t".into(),
+                correspoding_rust_type: ctx
+                    .conv_map
+                    .find_or_alloc_rust_type_no_src_id(&parse_type! { u8 }),
+            }
+            .into(),
             MethodVariant::Constructor => ForeignTypeInfo {
                 name: "".into(),
                 correspoding_rust_type: dummy_rust_ty.clone(),
@@ -1046,17 +1316,71 @@ fn find_suitable_foreign_types_for_methods(
                 }
             },
         };
-        ret.push(CppForeignMethodSignature { output, input });
+        ret.push(CppForeignMethodSignature {
+            output,
+            input,
+            c_result_out,
+        });
     }
     Ok(ret)
 }
 
+/// In pure C API mode method that returns `Result<T, E>`
+/// returns `T` and `E` via out parameters
+fn c_result_out_types(
+    ctx: &mut CppContext,
+    class: &ForeignClassInfo,
+    method: &ForeignMethod,
+) -> Result<Option<CResultOut>> {
+    if !ctx.cfg.pure_c_api || method.variant == MethodVariant::Constructor {
+        return Ok(None);
+    }
+    let ret_ty = match method.fn_decl.output {
+        syn::ReturnType::Default => return Ok(None),
+        syn::ReturnType::Type(_, ref ret_ty) => ret_ty,
+    };
+    let ret_rty = ctx.conv_map.find_or_alloc_rust_type(ret_ty, class.src_id);
+    let (ok_ty, err_ty) = match if_result_return_ok_err_types(&ret_rty) {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let span = (class.src_id, ret_ty.span());
+    let ok = if ok_ty == parse_type! { () } {
+        None
+    } else {
+        let ok_rty = ctx.conv_map.find_or_alloc_rust_type(&ok_ty, class.src_id);
+        let ok_ftype = map_type(ctx, &ok_rty, Direction::Outgoing, span)?;
+        Some((ok_rty, ok_ftype))
+    };
+    let err_rty = ctx.conv_map.find_or_alloc_rust_type(&err_ty, class.src_id);
+    let err_ftype = map_type(ctx, &err_rty, Direction::Outgoing, span)?;
+
+    let mut known_names: FxHashSet<SmolStr> =
+        method.arg_names_without_self().map(|x| x.into()).collect();
+    known_names.insert("this".into());
+    let ok_out_name = new_unique_name(&known_names, "ret_out");
+    known_names.insert(ok_out_name.clone());
+    let err_out_name = new_unique_name(&known_names, "err_out");
+    Ok(Some(CResultOut {
+        ok,
+        err: (err_rty, err_ftype),
+        ok_out_name,
+        err_out_name,
+    }))
+}
+
 fn generte_c_header_preamble(
     ctx: &CppContext,
     class_doc_comments: &str,
     c_class_type: &str,
+    c_includes: &[SmolStr],
     c_include_f: &mut FileWriteCache,
 ) {
+    use std::fmt::Write;
+    let mut includes = String::new();
+    for inc in c_includes {
+        writeln!(&mut includes, "#include {}", inc).expect(WRITE_TO_MEM_FAILED_MSG);
+    }
     writeln!(
         c_include_f,
         r##"// Automatically generated by flapigen
@@ -1065,7 +1389,7 @@ fn generte_c_header_preamble(
 
 //for (u)intX_t types
 #include <stdint.h>
-
+{includes}
 #ifdef __cplusplus
 static_assert(sizeof(uintptr_t) == sizeof(uint8_t) * {sizeof_usize},
    "our conversation usize <-> uintptr_t is wrong");
@@ -1076,6 +1400,7 @@ extern "C" {{
 "##,
         doc_comments = class_doc_comments,
         c_class_type = c_class_type,
+        includes = includes,
         sizeof_usize = ctx.target_pointer_width / 8,
     )
     .expect(WRITE_TO_MEM_FAILED_MSG);
@@ -1098,6 +1423,8 @@ fn generate_cpp_header_preamble(
         writeln!(&mut includes, "#include {}", inc).unwrap();
     }
     let plain_class = need_plain_class(class);
+    let namespace_begin =
+        cpp_code::namespace_begin(&ctx.cfg.namespace_name, class.namespace.as_deref());
     if !plain_class {
         writeln!(
             cpp_include_f,
@@ -1116,7 +1443,7 @@ fn generate_cpp_header_preamble(
 {includes}
 #include "c_{class_dot_name}.h"
 
-namespace {namespace} {{
+{namespace_begin}
 
 template<bool>
 class {class_name};
@@ -1133,7 +1460,7 @@ public:
             includes = includes,
             class_name = tmp_class_name,
             class_dot_name = class.name,
-            namespace = ctx.cfg.namespace_name,
+            namespace_begin = namespace_begin,
             doc_comments = class_doc_comments,
         )
     } else {
@@ -1152,14 +1479,14 @@ public:
 {includes}
 #include "c_{class_name}.h"
 
-namespace {namespace} {{
+{namespace_begin}
 
 {doc_comments}
 class {class_name} {{
 public:"#,
             includes = includes,
             class_name = class.name,
-            namespace = ctx.cfg.namespace_name,
+            namespace_begin = namespace_begin,
             doc_comments = class_doc_comments,
         )
     }
@@ -1278,7 +1605,7 @@ fn genearte_copy_stuff(
                     ),
                 )
             })?;
-        let c_clone_func = c_func_name(class, &class.methods[pos]);
+        let c_clone_func = c_func_name(ctx.cfg, class, &class.methods[pos]);
 
         writeln!(
             cpp_include_f,
@@ -1312,7 +1639,7 @@ fn genearte_copy_stuff(
             class_name = tmp_class_name
         )
         .expect(WRITE_TO_MEM_FAILED_MSG);
-    } else if class.smart_ptr_copy_derived() {
+    } else if class.smart_ptr_copy_derived() || need_shared_ptr_class(class) {
         let this_type = class
             .self_desc
             .as_ref()
@@ -1335,7 +1662,13 @@ fn genearte_copy_stuff(
             panic_on_syn_error("clone method for smart_ptr_derived class", unpack_code, err)
         });
 
-        let clone_fn_name = do_c_func_name(class, MethodAccess::Private, "clone");
+        // in pure C API there is no C++ wrapper, so C code calls these helpers directly
+        let helper_access = if ctx.cfg.pure_c_api {
+            MethodAccess::Public
+        } else {
+            MethodAccess::Private
+        };
+        let clone_fn_name = do_c_func_name(ctx.cfg, class, helper_access, "clone");
         let clone_fn_name = Ident::new(&clone_fn_name, Span::call_site());
         let this_type_ty = this_type.to_type_without_lifetimes();
         let this_type_for_method_ty = this_type_for_method.to_type_without_lifetimes();
@@ -1390,6 +1723,53 @@ fn genearte_copy_stuff(
             class_name = tmp_class_name
         )
         .expect(WRITE_TO_MEM_FAILED_MSG);
+
+        if need_shared_ptr_class(class) {
+            let smart_ptr: TokenStream =
+                if check_if_smart_pointer_return_inner_type(&this_type, "Arc").is_some() {
+                    quote!(::std::sync::Arc)
+                } else {
+                    quote!(::std::rc::Rc)
+                };
+            let use_count_fn_name = do_c_func_name(ctx.cfg, class, helper_access, "use_count");
+            let use_count_fn_name = Ident::new(&use_count_fn_name, Span::call_site());
+            ctx.rust_code.push(quote! {
+                #[allow(non_snake_case, unused_variables, unused_mut, unused_unsafe)]
+                #[no_mangle]
+                pub extern "C" fn #use_count_fn_name(this: *const #this_type_for_method_ty) -> usize {
+                    #unpack_code
+                    let ret: usize = #smart_ptr::strong_count(&this);
+                    ::std::mem::forget(this);
+                    ret
+                }
+            });
+            writeln!(
+                c_include_f,
+                r#"
+    uintptr_t {func_name}(const {c_class_type} *);"#,
+                c_class_type = c_class_type,
+                func_name = use_count_fn_name,
+            )
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+            writeln!(
+                cpp_include_f,
+                r#"
+    /// Number of references to Rust object, as std::shared_ptr::use_count
+    long use_count() const noexcept
+    {{
+        return self_ != nullptr ? static_cast<long>({use_count_func}(self_)) : 0;
+    }}
+    explicit operator bool() const noexcept {{ return self_ != nullptr; }}
+    void reset() noexcept {{ free_mem(this->self_); }}
+    {class_name} *operator->() noexcept {{ return this; }}
+    const {class_name} *operator->() const noexcept {{ return this; }}
+    {class_name} &operator*() noexcept {{ return *this; }}
+    const {class_name} &operator*() const noexcept {{ return *this; }}"#,
+                use_count_func = use_count_fn_name,
+                class_name = tmp_class_name
+            )
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+        }
     } else {
         writeln!(
             cpp_include_f,
@@ -1407,3 +1787,8 @@ fn genearte_copy_stuff(
 pub(in crate::cpp) fn need_plain_class(class: &ForeignClassInfo) -> bool {
     class.derive_list.iter().any(|x| *x == PLAIN_CLASS)
 }
+
+#[inline]
+pub(in crate::cpp) fn need_shared_ptr_class(class: &ForeignClassInfo) -> bool {
+    class.derive_list.iter().any(|x| *x == SHARED_PTR_CLASS)
+}
@@ -45,10 +45,11 @@ pub(in crate::cpp) fn generate_enum(ctx: &mut CppContext, fenum: &ForeignEnumInf
         .find_or_alloc_rust_type_no_src_id(&parse_type! { u32 });
 
     let enum_ftype = ForeignTypeS {
-        name: ForeignTypeName::new(fenum.name.to_string(), (fenum.src_id, fenum.name.span())),
-        provides_by_module: vec![
-            format!("\"{}\"", cpp_code::cpp_header_name_for_enum(fenum)).into()
-        ],
+        name: ForeignTypeName::new(
+            enum_type_name(ctx, fenum),
+            (fenum.src_id, fenum.name.span()),
+        ),
+        provides_by_module: vec![format!("\"{}\"", enum_header_name(ctx, fenum)).into()],
         into_from_rust: Some(ForeignConversationRule {
             rust_ty: enum_rty.to_idx(),
             intermediate: Some(ForeignConversationIntermediate {
@@ -57,7 +58,7 @@ pub(in crate::cpp) fn generate_enum(ctx: &mut CppContext, fenum: &ForeignEnumInf
                 conv_code: Rc::new(TypeConvCode::new(
                     format!(
                         "static_cast<{enum_name}>({var})",
-                        enum_name = fenum.name,
+                        enum_name = enum_type_name(ctx, fenum),
                         var = FROM_VAR_TEMPLATE
                     ),
                     invalid_src_id_span(),
@@ -80,34 +81,122 @@ pub(in crate::cpp) fn generate_enum(ctx: &mut CppContext, fenum: &ForeignEnumInf
     Ok(())
 }
 
+/// Name of enum type to use outside of enum's header
+fn enum_type_name(ctx: &CppContext, enum_info: &ForeignEnumInfo) -> String {
+    if ctx.cfg.pure_c_api {
+        c_enum_name(ctx, enum_info)
+    } else {
+        cpp_code::cpp_enum_name(enum_info)
+    }
+}
+
+fn c_enum_name(ctx: &CppContext, enum_info: &ForeignEnumInfo) -> String {
+    format!(
+        "{}{}",
+        cpp_code::c_name_prefix(ctx.cfg, enum_info.namespace.as_deref()),
+        enum_info.name
+    )
+}
+
+fn enum_header_name(ctx: &CppContext, enum_info: &ForeignEnumInfo) -> String {
+    if ctx.cfg.pure_c_api {
+        cpp_code::c_header_name_for_enum(enum_info)
+    } else {
+        cpp_code::cpp_header_name_for_enum(enum_info)
+    }
+}
+
 fn generate_c_code_for_enum(
     ctx: &mut CppContext,
     enum_info: &ForeignEnumInfo,
 ) -> std::result::Result<(), DiagnosticError> {
-    let c_path = ctx
-        .cfg
-        .output_dir
-        .join(cpp_code::cpp_header_name_for_enum(enum_info));
+    let c_path = ctx.cfg.output_dir.join(enum_header_name(ctx, enum_info));
     let mut file = FileWriteCache::new(&c_path, ctx.generated_foreign_files);
-    let enum_doc_comments = cpp_code::doc_comments_to_c_comments(&enum_info.doc_comments, true);
+    let enum_doc_comments =
+        cpp_code::doc_comments_to_c_comments(ctx.conv_map, &enum_info.doc_comments, true);
+
+    if ctx.cfg.pure_c_api {
+        write_pure_c_enum(ctx, &mut file, enum_info, &enum_doc_comments);
+    } else {
+        write_cpp_enum(ctx, &mut file, enum_info, &enum_doc_comments);
+    }
+    let mut cnt = file.take_content();
+    extend_foreign_enum(enum_info, &mut cnt, ctx.enum_ext_handlers)?;
+    file.replace_content(cnt);
+    cpp_code::prefix_c_items(ctx.cfg, ctx.prefixed_c_items, &mut file);
+    file.update_file_if_necessary()
+        .map_err(DiagnosticError::map_any_err_to_our_err)?;
+    Ok(())
+}
+
+/// Values of enum are passed as `uint32_t`, items are prefixed with enum name
+/// to not pollute global namespace
+fn write_pure_c_enum(
+    ctx: &CppContext,
+    file: &mut FileWriteCache,
+    enum_info: &ForeignEnumInfo,
+    enum_doc_comments: &str,
+) {
+    writeln!(
+        file,
+        r#"// Automatically generated by flapigen
+#pragma once
+
+{doc_comments}
+enum {enum_name} {{"#,
+        enum_name = c_enum_name(ctx, enum_info),
+        doc_comments = enum_doc_comments,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+
+    for (i, item) in enum_info.items.iter().enumerate() {
+        let doc_comments =
+            cpp_code::doc_comments_to_c_comments(ctx.conv_map, &item.doc_comments, false);
+        if !doc_comments.is_empty() {
+            writeln!(file, "{}", doc_comments).expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+        writeln!(
+            file,
+            "    {enum_name}_{item_name} = {index}{separator}",
+            enum_name = c_enum_name(ctx, enum_info),
+            item_name = item.name,
+            index = i,
+            separator = if i == enum_info.items.len() - 1 {
+                ""
+            } else {
+                ","
+            },
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    writeln!(file, "}};").expect(WRITE_TO_MEM_FAILED_MSG);
+}
 
+fn write_cpp_enum(
+    ctx: &CppContext,
+    file: &mut FileWriteCache,
+    enum_info: &ForeignEnumInfo,
+    enum_doc_comments: &str,
+) {
     writeln!(
         file,
         r#"// Automatically generated by flapigen
 #pragma once
 
-namespace {namespace} {{
+{namespace_begin}
 {doc_comments}
 enum {enum_name} {{"#,
         enum_name = enum_info.name,
         doc_comments = enum_doc_comments,
-        namespace = ctx.cfg.namespace_name,
+        namespace_begin =
+            cpp_code::namespace_begin(&ctx.cfg.namespace_name, enum_info.namespace.as_deref()),
     )
     .expect(WRITE_TO_MEM_FAILED_MSG);
 
     for (i, item) in enum_info.items.iter().enumerate() {
         //Enums are aligned left, so we pass true to get left aligned comments.
-        let mut doc_comments = cpp_code::doc_comments_to_c_comments(&item.doc_comments, true);
+        let mut doc_comments =
+            cpp_code::doc_comments_to_c_comments(ctx.conv_map, &item.doc_comments, true);
         if !doc_comments.is_empty() && !doc_comments.ends_with('\n') {
             doc_comments.push('\n');
         }
@@ -129,17 +218,12 @@ enum {enum_name} {{"#,
     writeln!(
         file,
         r#"}};
-}} // namespace {namespace}
+{namespace_end}
 "#,
-        namespace = ctx.cfg.namespace_name
+        namespace_end =
+            cpp_code::namespace_end(&ctx.cfg.namespace_name, enum_info.namespace.as_deref()),
     )
     .expect(WRITE_TO_MEM_FAILED_MSG);
-    let mut cnt = file.take_content();
-    extend_foreign_enum(enum_info, &mut cnt, ctx.enum_ext_handlers)?;
-    file.replace_content(cnt);
-    file.update_file_if_necessary()
-        .map_err(DiagnosticError::map_any_err_to_our_err)?;
-    Ok(())
 }
 
 fn generate_rust_trait_for_enum(ctx: &mut CppContext, enum_info: &ForeignEnumInfo) -> Result<()> {
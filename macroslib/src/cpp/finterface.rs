@@ -1,4 +1,4 @@
-use std::{io::Write, rc::Rc};
+use std::{fmt::Display, io::Write, rc::Rc};
 
 use petgraph::Direction;
 use rustc_hash::FxHashSet;
@@ -11,7 +11,7 @@ use crate::{
         CppForeignTypeInfo,
     },
     error::{invalid_src_id_span, panic_on_syn_error, DiagnosticError, Result},
-    file_cache::FileWriteCache,
+    file_cache::{FileWriteCache, NoNeedFsOpsRegistration},
     namegen::new_unique_name,
     typemap::{
         ast::{parse_ty_with_given_span, DisplayToTokens, ForeignTypeName},
@@ -19,7 +19,7 @@ use crate::{
         utils::rust_to_foreign_convert_method_inputs,
         ForeignTypeInfo, TypeConvCode, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE, TO_VAR_TYPE_TEMPLATE,
     },
-    types::ForeignInterface,
+    types::{ForeignInterface, ForeignInterfaceMethod, SelfTypeVariant},
     WRITE_TO_MEM_FAILED_MSG,
 };
 
@@ -28,12 +28,29 @@ pub(in crate::cpp) fn generate_interface(
     interface: &ForeignInterface,
 ) -> Result<()> {
     let mut f_methods = find_suitable_ftypes_for_interace_methods(ctx, interface)?;
-    let req_includes = cpp_code::cpp_list_required_includes(&mut f_methods);
-    cpp_code_generate_interface(ctx, interface, &req_includes, &f_methods)
-        .map_err(|err| DiagnosticError::new(interface.src_id, interface.span(), err))?;
-    rust_code_generate_interface(ctx, interface, &f_methods)?;
+    let mut rust_impl_f_methods = find_suitable_ftypes_for_rust_impl(ctx, interface, &f_methods)?;
+    if rust_impl_f_methods.is_some() && !rust_code_generate_rust_impl(ctx, interface, &f_methods)? {
+        rust_impl_f_methods = None;
+    }
+    let mut req_includes = cpp_code::cpp_list_required_includes(&mut f_methods);
+    if let Some(rust_impl_f_methods) = rust_impl_f_methods.as_mut() {
+        for inc in cpp_code::cpp_list_required_includes(rust_impl_f_methods) {
+            if !req_includes.contains(&inc) {
+                req_includes.push(inc);
+            }
+        }
+    }
+    cpp_code_generate_interface(
+        ctx,
+        interface,
+        &req_includes,
+        &f_methods,
+        rust_impl_f_methods.as_deref(),
+    )
+    .map_err(|err| DiagnosticError::new(interface.src_id, interface.span(), err))?;
+    rust_code_generate_interface(ctx, interface, &f_methods, rust_impl_f_methods.is_some())?;
 
-    let c_struct_name = format!("C_{}", interface.name);
+    let c_struct_name = c_interface_struct_name(ctx, interface);
     let rust_struct_pointer = format!("*const {}", c_struct_name);
     let rust_ty: Type = parse_ty_with_given_span(&rust_struct_pointer, interface.name.span())
         .map_err(|err| DiagnosticError::from_syn_err(interface.src_id, err))?;
@@ -45,7 +62,7 @@ pub(in crate::cpp) fn generate_interface(
 
     ctx.conv_map.alloc_foreign_type(ForeignTypeS {
         name: ForeignTypeName::new(c_struct_pointer, interface.src_id_span()),
-        provides_by_module: vec![c_interface_struct_header],
+        provides_by_module: vec![c_interface_struct_header.clone()],
         into_from_rust: Some(ForeignConversationRule {
             rust_ty: rust_ty.to_idx(),
             intermediate: None,
@@ -56,6 +73,26 @@ pub(in crate::cpp) fn generate_interface(
         }),
     })?;
 
+    let c_struct_rust_ty: Type = parse_ty_with_given_span(&c_struct_name, interface.name.span())
+        .map_err(|err| DiagnosticError::from_syn_err(interface.src_id, err))?;
+    let c_struct_rust_ty = ctx
+        .conv_map
+        .find_or_alloc_rust_type_no_src_id(&c_struct_rust_ty);
+    if rust_impl_f_methods.is_some() {
+        ctx.conv_map.alloc_foreign_type(ForeignTypeS {
+            name: ForeignTypeName::new(
+                format!("struct {}", c_struct_name),
+                interface.src_id_span(),
+            ),
+            provides_by_module: vec![c_interface_struct_header.clone()],
+            into_from_rust: Some(ForeignConversationRule {
+                rust_ty: c_struct_rust_ty.to_idx(),
+                intermediate: None,
+            }),
+            from_into_rust: None,
+        })?;
+    }
+
     let cpp_abs_class_header: SmolStr = format!("\"{}\"", cpp_interface_header(interface)).into();
     let boxed_trait_name = format!("Box<dyn {}>", DisplayToTokens(&interface.self_type));
     let boxed_trait_rust_ty: Type =
@@ -65,72 +102,633 @@ pub(in crate::cpp) fn generate_interface(
         .conv_map
         .find_or_alloc_rust_type(&boxed_trait_rust_ty, interface.src_id);
 
-    let mut params = Vec::with_capacity(3);
-    params.push(FROM_VAR_TEMPLATE.into());
-    params.push(TO_VAR_TYPE_TEMPLATE.into());
-    let tmp_name = "$tmp".into();
-    let conv_code = format!(
-        r#"
+    let to_c_interface_code = || {
+        let mut params = Vec::with_capacity(3);
+        params.push(FROM_VAR_TEMPLATE.into());
+        params.push(TO_VAR_TYPE_TEMPLATE.into());
+        let tmp_name = "$tmp".into();
+        let conv_code = format!(
+            r#"
         {c_struct} {tmp_name} = {interface}::to_c_interface(std::move({var}));
         {to} = &{tmp_name};
 "#,
-        var = FROM_VAR_TEMPLATE,
-        interface = interface.name,
-        to = TO_VAR_TYPE_TEMPLATE,
-        tmp_name = tmp_name,
-        c_struct = c_struct_name,
-    );
-    params.push(tmp_name);
-    let conv_code = TypeConvCode::with_params(conv_code, invalid_src_id_span(), params);
+            var = FROM_VAR_TEMPLATE,
+            interface = interface.name,
+            to = TO_VAR_TYPE_TEMPLATE,
+            tmp_name = tmp_name,
+            c_struct = c_struct_name,
+        );
+        params.push(tmp_name);
+        Rc::new(TypeConvCode::with_params(
+            conv_code,
+            invalid_src_id_span(),
+            params,
+        ))
+    };
 
+    let from_rust_conv = |from_c_interface: &str| ForeignConversationRule {
+        rust_ty: boxed_trait_rust_ty.to_idx(),
+        intermediate: Some(ForeignConversationIntermediate {
+            input_to_output: false,
+            intermediate_ty: c_struct_rust_ty.to_idx(),
+            conv_code: Rc::new(TypeConvCode::new(
+                format!(
+                    "{interface}::{from_c_interface}({var})",
+                    from_c_interface = from_c_interface,
+                    interface = interface.name,
+                    var = FROM_VAR_TEMPLATE,
+                ),
+                invalid_src_id_span(),
+            )),
+        }),
+    };
     ctx.conv_map.alloc_foreign_type(ForeignTypeS {
         name: ForeignTypeName::new(
             format!("std::unique_ptr<{}>", interface.name),
             interface.src_id_span(),
         ),
-        provides_by_module: vec![cpp_abs_class_header, "<memory>".into(), "<utility>".into()],
-        into_from_rust: None,
+        provides_by_module: vec![
+            cpp_abs_class_header.clone(),
+            "<memory>".into(),
+            "<utility>".into(),
+        ],
+        into_from_rust: rust_impl_f_methods
+            .as_ref()
+            .map(|_| from_rust_conv("from_c_interface")),
         from_into_rust: Some(ForeignConversationRule {
             rust_ty: boxed_trait_rust_ty.to_idx(),
             intermediate: Some(ForeignConversationIntermediate {
                 input_to_output: false,
                 intermediate_ty: rust_ty.to_idx(),
-                conv_code: Rc::new(conv_code),
+                conv_code: to_c_interface_code(),
             }),
         }),
     })?;
 
-    ctx.conv_map.add_conversation_rule(
-        rust_ty.to_idx(),
-        boxed_trait_rust_ty.to_idx(),
+    let c_struct_to_rust_code = |res_type: &Type, into_rust: &str, wrap: &str| {
         TypeConvCode::new2(
             format!(
                 r#"
             assert!(!{from_var}.is_null());
             let {to_var}: &{struct_with_funcs} = unsafe {{ {from_var}.as_ref().unwrap() }};
-            let {to_var}: {res_type} = Box::new({to_var}.clone());
+            let {to_var}: {res_type} = {converted};
         "#,
                 to_var = TO_VAR_TEMPLATE,
                 from_var = FROM_VAR_TEMPLATE,
                 struct_with_funcs = c_struct_name,
-                res_type = DisplayToTokens(&boxed_trait_rust_ty.ty),
+                res_type = DisplayToTokens(res_type),
+                converted = if rust_impl_f_methods.is_some() {
+                    format!(
+                        "{}::{}({}.clone())",
+                        c_struct_name, into_rust, TO_VAR_TEMPLATE
+                    )
+                } else {
+                    format!("{}::new({}.clone())", wrap, TO_VAR_TEMPLATE)
+                },
+            ),
+            invalid_src_id_span(),
+        )
+        .into()
+    };
+    ctx.conv_map.add_conversation_rule(
+        rust_ty.to_idx(),
+        boxed_trait_rust_ty.to_idx(),
+        c_struct_to_rust_code(&boxed_trait_rust_ty.ty, "into_rust", "Box"),
+    );
+
+    let arc_trait_name = format!("Arc<dyn {}>", DisplayToTokens(&interface.self_type));
+    let arc_trait_rust_ty: Type = parse_ty_with_given_span(&arc_trait_name, interface.name.span())
+        .map_err(|err| DiagnosticError::from_syn_err(interface.src_id, err))?;
+    let arc_trait_rust_ty = ctx
+        .conv_map
+        .find_or_alloc_rust_type(&arc_trait_rust_ty, interface.src_id);
+    ctx.conv_map.add_conversation_rule(
+        rust_ty.to_idx(),
+        arc_trait_rust_ty.to_idx(),
+        c_struct_to_rust_code(&arc_trait_rust_ty.ty, "into_rust_arc", "::std::sync::Arc"),
+    );
+
+    // `Arc` gives only shared access to trait object
+    let rust_impl_shared = rust_impl_f_methods.is_some() && !rust_impl_needs_mut(interface)?;
+    let shared_into_from_rust = if rust_impl_shared {
+        let mut into_from_rust = from_rust_conv("shared_from_c_interface");
+        into_from_rust.rust_ty = arc_trait_rust_ty.to_idx();
+        Some(into_from_rust)
+    } else {
+        None
+    };
+    ctx.conv_map.alloc_foreign_type(ForeignTypeS {
+        name: ForeignTypeName::new(
+            format!("std::shared_ptr<{}>", interface.name),
+            interface.src_id_span(),
+        ),
+        provides_by_module: vec![cpp_abs_class_header, "<memory>".into(), "<utility>".into()],
+        into_from_rust: shared_into_from_rust,
+        from_into_rust: Some(ForeignConversationRule {
+            rust_ty: arc_trait_rust_ty.to_idx(),
+            intermediate: Some(ForeignConversationIntermediate {
+                input_to_output: false,
+                intermediate_ty: rust_ty.to_idx(),
+                conv_code: to_c_interface_code(),
+            }),
+        }),
+    })?;
+
+    if rust_impl_f_methods.is_none() {
+        return Ok(());
+    }
+    let from_rust_code = |from_rust: &str| {
+        TypeConvCode::new2(
+            format!(
+                "let {to_var}: {c_struct} = {c_struct}::{from_rust}({from_var});",
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE,
+                c_struct = c_struct_name,
+                from_rust = from_rust,
             ),
             invalid_src_id_span(),
         )
-        .into(),
+        .into()
+    };
+    ctx.conv_map.add_conversation_rule(
+        boxed_trait_rust_ty.to_idx(),
+        c_struct_rust_ty.to_idx(),
+        from_rust_code("from_rust_box"),
     );
+    if rust_impl_shared {
+        ctx.conv_map.add_conversation_rule(
+            arc_trait_rust_ty.to_idx(),
+            c_struct_rust_ty.to_idx(),
+            from_rust_code("from_rust"),
+        );
+    }
 
     Ok(())
 }
 
+/// Is `&mut self` used by methods, so implementation by Rust object requires `DerefMut`
+fn rust_impl_needs_mut(interface: &ForeignInterface) -> Result<bool> {
+    for method in &interface.items {
+        if let SelfTypeVariant::RptrMut = method.fn_decl.inputs[0].as_self_arg(interface.src_id)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Generate `from_rust`/`into_rust` for C struct of interface,
+/// to wrap Rust implementation of trait into C struct with functions
+/// that call trait object.
+/// Returns `false` if some type can not be converted from C to Rust
+/// for argument, or from Rust to C for result
+fn rust_code_generate_rust_impl(
+    ctx: &mut CppContext,
+    interface: &ForeignInterface,
+    methods_sign: &[CppForeignMethodSignature],
+) -> Result<bool> {
+    use std::fmt::Write;
+
+    let struct_with_funcs = c_interface_struct_name(ctx, interface);
+    let trait_object = format!("dyn {}", DisplayToTokens(&interface.self_type));
+    let needs_mut = rust_impl_needs_mut(interface)?;
+    let bound = format!(
+        "P: ::std::ops::{}<Target = {}> + 'static",
+        if needs_mut { "DerefMut" } else { "Deref" },
+        trait_object
+    );
+    let mut conv_deps = Vec::new();
+    let mut fill_funcs = String::new();
+    let mut funcs = String::new();
+    // `Arc` can be taken back only as `Box` with forwarding implementation of trait
+    let mut arc_impl_funcs = String::new();
+
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let (this_ty, this_cast, this_ref) =
+            match method.fn_decl.inputs[0].as_self_arg(interface.src_id)? {
+                SelfTypeVariant::Rptr => ("&P", "&*(opaque as *const P)", "&**this"),
+                SelfTypeVariant::RptrMut => ("&mut P", "&mut *(opaque as *mut P)", "&mut **this"),
+                SelfTypeVariant::Mut | SelfTypeVariant::Default => return Ok(false),
+            };
+        let c_ret_type =
+            DisplayToTokens(&f_method.output.base.correspoding_rust_type.ty).to_string();
+        let mut args_with_types = String::new();
+        let mut convert_args = String::new();
+        let mut call_args = String::new();
+        for (i, (arg, f_arg)) in method
+            .fn_decl
+            .inputs
+            .iter()
+            .skip(1)
+            .zip(&f_method.input)
+            .enumerate()
+        {
+            let named_arg = arg
+                .as_named_arg()
+                .map_err(|err| DiagnosticError::from_syn_err(interface.src_id, err))?;
+            let arg_name = format!("a{}", i);
+            let arg_rust_ty = ctx
+                .conv_map
+                .find_or_alloc_rust_type(&named_arg.ty, interface.src_id);
+            write!(
+                &mut args_with_types,
+                "{}: {}, ",
+                arg_name,
+                DisplayToTokens(&f_arg.base.correspoding_rust_type.ty)
+            )
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+            let (mut deps, conv_code) = match ctx.conv_map.convert_rust_types(
+                f_arg.base.correspoding_rust_type.to_idx(),
+                arg_rust_ty.to_idx(),
+                &arg_name,
+                &arg_name,
+                &c_ret_type,
+                (interface.src_id, named_arg.ty.span()),
+            ) {
+                Ok(x) => x,
+                Err(_) => return Ok(false),
+            };
+            conv_deps.append(&mut deps);
+            convert_args.push_str(&conv_code);
+            call_args.push_str(", ");
+            call_args.push_str(&arg_name);
+        }
+        let (real_ret_type, output_conv) = match method.fn_decl.output {
+            syn::ReturnType::Default => ("()".to_string(), String::new()),
+            syn::ReturnType::Type(_, ref ret_ty) => {
+                let real_ret_type = ctx
+                    .conv_map
+                    .find_or_alloc_rust_type(ret_ty, interface.src_id);
+                let (mut deps, conv_code) = match ctx.conv_map.convert_rust_types(
+                    real_ret_type.to_idx(),
+                    f_method.output.base.correspoding_rust_type.to_idx(),
+                    "ret",
+                    "ret",
+                    &c_ret_type,
+                    (interface.src_id, ret_ty.span()),
+                ) {
+                    Ok(x) => x,
+                    Err(_) => return Ok(false),
+                };
+                conv_deps.append(&mut deps);
+                (real_ret_type.normalized_name.to_string(), conv_code)
+            }
+        };
+        writeln!(
+            &mut funcs,
+            r#"
+    #[allow(non_snake_case, unused_mut)]
+    extern "C" fn rust_impl_{method_name}<P>({args_with_types}opaque: *const ::std::os::raw::c_void) -> {c_ret_type}
+    where
+        {bound},
+    {{
+        let this: {this_ty} = unsafe {{ {this_cast} }};
+{convert_args}
+        let mut ret: {real_ret_type} = {rust_name}({this_ref}{call_args});
+{output_conv}
+        ret
+    }}"#,
+            method_name = method.name,
+            args_with_types = args_with_types,
+            c_ret_type = c_ret_type,
+            bound = bound,
+            this_ty = this_ty,
+            this_cast = this_cast,
+            convert_args = convert_args,
+            real_ret_type = real_ret_type,
+            rust_name = DisplayToTokens(&method.rust_name),
+            this_ref = this_ref,
+            call_args = call_args,
+            output_conv = output_conv,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        writeln!(
+            &mut fill_funcs,
+            "            {method_name}: Self::rust_impl_{method_name}::<P>,",
+            method_name = method.name,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+
+        let trait_func_name = match method.rust_name.segments.last() {
+            Some(seg) => &seg.ident,
+            None => return Ok(false),
+        };
+        let mut arc_impl_args = String::new();
+        let mut arc_impl_call_args = String::new();
+        for (i, arg) in method.fn_decl.inputs.iter().skip(1).enumerate() {
+            let named_arg = arg
+                .as_named_arg()
+                .map_err(|err| DiagnosticError::from_syn_err(interface.src_id, err))?;
+            write!(
+                &mut arc_impl_args,
+                ", a{}: {}",
+                i,
+                DisplayToTokens(&named_arg.ty)
+            )
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+            write!(&mut arc_impl_call_args, ", a{}", i).expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+        writeln!(
+            &mut arc_impl_funcs,
+            r#"
+    fn {func_name}(&self{args}){ret} {{
+        {rust_name}(&*self.0{call_args})
+    }}"#,
+            func_name = trait_func_name,
+            args = arc_impl_args,
+            ret = DisplayToTokens(&method.fn_decl.output),
+            rust_name = DisplayToTokens(&method.rust_name),
+            call_args = arc_impl_call_args,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+
+    let arc_impl_name = format!("SwigArcImpl{}", struct_with_funcs);
+    // `Box` of `Arc` wrapper may lack `Send`, then `Arc` is wrapped by C struct
+    let arc_can_be_boxed = interface.arc_can_be_boxed();
+    let arc_trait_object = format!("::std::sync::Arc<{}>", trait_object);
+    let (arc_into_rust, arc_impl) = if needs_mut {
+        (
+            format!(
+                r#"
+        self.foreign_into_box()
+    }}
+    /// Take back Rust implementation as `Arc`
+    #[allow(dead_code)]
+    fn into_rust_arc(self) -> {arc_trait_object} {{
+        if Self::rust_impl_forget::<Box<{trait_object}>>(self.opaque) {{
+            let p = unsafe {{ Box::from_raw(self.opaque as *mut Box<{trait_object}>) }};
+            ::std::mem::forget(self);
+            return ::std::sync::Arc::from(*p);
+        }}
+        self.foreign_into_arc()
+    }}"#,
+                arc_trait_object = arc_trait_object,
+                trait_object = trait_object,
+            ),
+            String::new(),
+        )
+    } else {
+        (
+            format!(
+                r#"{box_from_arc}
+        self.foreign_into_box()
+    }}
+    /// Take back Rust implementation as `Arc`, if it was wrapped by `from_rust`,
+    /// instead of wrapping it twice
+    #[allow(dead_code)]
+    fn into_rust_arc(self) -> {arc_trait_object} {{
+        if Self::rust_impl_forget::<{arc_trait_object}>(self.opaque) {{
+            let p = unsafe {{ Box::from_raw(self.opaque as *mut {arc_trait_object}) }};
+            ::std::mem::forget(self);
+            return *p;
+        }}
+        if Self::rust_impl_forget::<Box<{trait_object}>>(self.opaque) {{
+            let p = unsafe {{ Box::from_raw(self.opaque as *mut Box<{trait_object}>) }};
+            ::std::mem::forget(self);
+            return ::std::sync::Arc::from(*p);
+        }}
+        self.foreign_into_arc()
+    }}
+    /// `Arc` wrapped by `from_rust`
+    fn rust_impl_arc(&self) -> Option<&{arc_trait_object}> {{
+        let is_arc = SWIG_RUST_IMPLS_{struct_with_funcs}
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|impls| impls.get(&(self.opaque as usize)).cloned())
+            == Some(::std::any::TypeId::of::<{arc_trait_object}>());
+        if !is_arc {{
+            return None;
+        }}
+        Some(unsafe {{ &*(self.opaque as *const {arc_trait_object}) }})
+    }}
+    /// New C struct that shares `Arc` wrapped by `from_rust`
+    fn rust_impl_share(&self) -> Option<Self> {{
+        self.rust_impl_arc().map(|p| Self::from_rust(p.clone()))
+    }}"#,
+                arc_trait_object = arc_trait_object,
+                trait_object = trait_object,
+                struct_with_funcs = struct_with_funcs,
+                box_from_arc = if arc_can_be_boxed {
+                    format!(
+                        r#"
+        if Self::rust_impl_forget::<{arc_trait_object}>(self.opaque) {{
+            let p = unsafe {{ Box::from_raw(self.opaque as *mut {arc_trait_object}) }};
+            ::std::mem::forget(self);
+            return Box::new({arc_impl_name}(*p));
+        }}"#,
+                        arc_trait_object = arc_trait_object,
+                        arc_impl_name = arc_impl_name,
+                    )
+                } else {
+                    String::new()
+                },
+            ),
+            format!(
+                r#"{arc_impl_struct}
+/// If `this` wraps `Arc` created by Rust, fill `out` with C struct
+/// that shares it and return `1`, otherwise return `0`
+#[no_mangle]
+pub extern "C" fn {struct_with_funcs}_share(
+    this: *const {struct_with_funcs},
+    out: *mut {struct_with_funcs},
+) -> ::std::os::raw::c_char {{
+    assert!(!this.is_null());
+    assert!(!out.is_null());
+    let this: &{struct_with_funcs} = unsafe {{ this.as_ref().unwrap() }};
+    match this.rust_impl_share() {{
+        Some(shared) => {{
+            unsafe {{ ::std::ptr::write(out, shared) }};
+            1
+        }}
+        None => 0,
+    }}
+}}
+
+/// If `this` wraps `Arc` with foreign implementation, fill `out` with copy of
+/// its C struct, that is still owned by `this`, and return `1`, otherwise return `0`
+#[no_mangle]
+pub extern "C" fn {struct_with_funcs}_foreign(
+    this: *const {struct_with_funcs},
+    out: *mut {struct_with_funcs},
+) -> ::std::os::raw::c_char {{
+    assert!(!this.is_null());
+    assert!(!out.is_null());
+    let this: &{struct_with_funcs} = unsafe {{ this.as_ref().unwrap() }};
+    match this.rust_impl_arc().and_then(|p| {struct_with_funcs}::as_foreign(&**p)) {{
+        Some(foreign) => {{
+            unsafe {{ ::std::ptr::write(out, ::std::ptr::read(foreign)) }};
+            1
+        }}
+        None => 0,
+    }}
+}}
+"#,
+                struct_with_funcs = struct_with_funcs,
+                arc_impl_struct = if arc_can_be_boxed {
+                    format!(
+                        r#"
+/// `Arc` implementation taken back from `{struct_with_funcs}`
+#[allow(non_camel_case_types)]
+struct {arc_impl_name}({arc_trait_object});
+
+impl {trait_name} for {arc_impl_name} {{{arc_impl_funcs}}}
+"#,
+                        struct_with_funcs = struct_with_funcs,
+                        arc_impl_name = arc_impl_name,
+                        arc_trait_object = arc_trait_object,
+                        trait_name = DisplayToTokens(&interface.self_type.bounds[0]),
+                        arc_impl_funcs = arc_impl_funcs,
+                    )
+                } else {
+                    String::new()
+                },
+            ),
+        )
+    };
+
+    let code = format!(
+        r#"
+/// `opaque` of `{struct_with_funcs}` created by `from_rust` and type of Rust implementation,
+/// so `into_rust` can distinguish them from foreign implementations
+#[allow(non_upper_case_globals)]
+static SWIG_RUST_IMPLS_{struct_with_funcs}: ::std::sync::Mutex<
+    Option<::std::collections::HashMap<usize, ::std::any::TypeId>>,
+> = ::std::sync::Mutex::new(None);
+/// Addresses of foreign implementations moved to heap by `into_rust` or `into_rust_arc`,
+/// so they can be given back instead of wrapping them twice
+#[allow(non_upper_case_globals)]
+static SWIG_FOREIGN_IMPLS_{struct_with_funcs}: ::std::sync::Mutex<
+    Option<::std::collections::HashSet<usize>>,
+> = ::std::sync::Mutex::new(None);
+{arc_impl}
+impl {struct_with_funcs} {{
+    /// Wrap Rust implementation, so foreign code can use it
+    #[allow(dead_code)]
+    fn from_rust<P>(p: P) -> Self
+    where
+        {bound},
+    {{
+        let opaque = Box::into_raw(Box::new(p)) as *const ::std::os::raw::c_void;
+        SWIG_RUST_IMPLS_{struct_with_funcs}
+            .lock()
+            .unwrap()
+            .get_or_insert_with(Default::default)
+            .insert(opaque as usize, ::std::any::TypeId::of::<P>());
+        {struct_with_funcs} {{
+            opaque,
+            C_{interface_name}_deref: Self::rust_impl_C_{interface_name}_deref::<P>,
+{fill_funcs}
+        }}
+    }}
+    /// Take back Rust implementation, if it was wrapped by `from_rust`,
+    /// instead of wrapping it twice
+    #[allow(dead_code)]
+    fn into_rust(self) -> Box<{trait_object}> {{
+        if Self::rust_impl_forget::<Box<{trait_object}>>(self.opaque) {{
+            let p = unsafe {{ Box::from_raw(self.opaque as *mut Box<{trait_object}>) }};
+            ::std::mem::forget(self);
+            return *p;
+        }}{arc_into_rust}
+    /// Return `true` and forget `opaque` if it was created by `from_rust::<P>`
+    fn rust_impl_forget<P: 'static>(opaque: *const ::std::os::raw::c_void) -> bool {{
+        let mut impls = SWIG_RUST_IMPLS_{struct_with_funcs}.lock().unwrap();
+        match impls.as_mut() {{
+            Some(impls)
+                if impls.get(&(opaque as usize)) == Some(&::std::any::TypeId::of::<P>()) =>
+            {{
+                impls.remove(&(opaque as usize));
+                true
+            }}
+            _ => false,
+        }}
+    }}
+    #[allow(non_snake_case)]
+    extern "C" fn rust_impl_C_{interface_name}_deref<P: 'static>(
+        opaque: *const ::std::os::raw::c_void,
+    ) {{
+        Self::rust_impl_forget::<P>(opaque);
+        unsafe {{ drop(Box::from_raw(opaque as *mut P)) }};
+    }}
+    /// Wrap Rust implementation, or give back foreign implementation
+    /// boxed by `into_rust`
+    #[allow(dead_code)]
+    fn from_rust_box(p: Box<{trait_object}>) -> Self {{
+        if Self::as_foreign(&*p).is_some() {{
+            let p = unsafe {{ Box::from_raw(Box::into_raw(p) as *mut Self) }};
+            p.foreign_forget();
+            return *p;
+        }}
+        Self::from_rust(p)
+    }}
+    fn foreign_into_box(self) -> Box<{trait_object}> {{
+        let p = Box::new(self);
+        Self::foreign_register(&p);
+        p
+    }}
+    #[allow(dead_code)]
+    fn foreign_into_arc(self) -> ::std::sync::Arc<{trait_object}> {{
+        let p = ::std::sync::Arc::new(self);
+        Self::foreign_register(&p);
+        p
+    }}
+    fn foreign_register(p: &Self) {{
+        SWIG_FOREIGN_IMPLS_{struct_with_funcs}
+            .lock()
+            .unwrap()
+            .get_or_insert_with(Default::default)
+            .insert(p as *const Self as usize);
+    }}
+    /// Forget address of foreign implementation, if it was moved to heap
+    fn foreign_forget(&self) {{
+        if let Some(impls) = SWIG_FOREIGN_IMPLS_{struct_with_funcs}.lock().unwrap().as_mut() {{
+            impls.remove(&(self as *const Self as usize));
+        }}
+    }}
+    /// Foreign implementation, if `p` was moved to heap by `into_rust` or `into_rust_arc`
+    fn as_foreign(p: &({trait_object})) -> Option<&Self> {{
+        let addr = p as *const ({trait_object}) as *const Self;
+        let found = SWIG_FOREIGN_IMPLS_{struct_with_funcs}
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|impls| impls.contains(&(addr as usize)))
+            .unwrap_or(false);
+        if found {{
+            Some(unsafe {{ &*addr }})
+        }} else {{
+            None
+        }}
+    }}
+{funcs}
+}}
+"#,
+        struct_with_funcs = struct_with_funcs,
+        bound = bound,
+        interface_name = interface.name,
+        fill_funcs = fill_funcs,
+        trait_object = trait_object,
+        funcs = funcs,
+        arc_impl = arc_impl,
+        arc_into_rust = arc_into_rust,
+    );
+    ctx.rust_code.append(&mut conv_deps);
+    ctx.rust_code.push(
+        syn::parse_str(&code)
+            .unwrap_or_else(|err| panic_on_syn_error("cpp internal code", code, err)),
+    );
+    Ok(true)
+}
+
 fn rust_code_generate_interface(
     ctx: &mut CppContext,
     interface: &ForeignInterface,
     methods_sign: &[CppForeignMethodSignature],
+    rust_impl: bool,
 ) -> Result<()> {
     use std::fmt::Write;
 
-    let struct_with_funcs = format!("C_{}", interface.name);
+    let struct_with_funcs = c_interface_struct_name(ctx, interface);
 
     let mut code = format!(
         r#"
@@ -139,10 +737,11 @@ fn rust_code_generate_interface(
 #[allow(non_snake_case)]
 pub struct {struct_with_funcs} {{
     opaque: *const ::std::os::raw::c_void,
-    {struct_with_funcs}_deref:
+    C_{interface_name}_deref:
         extern "C" fn(_: *const ::std::os::raw::c_void),
 "#,
         struct_with_funcs = struct_with_funcs,
+        interface_name = interface.name,
     );
     for (method, f_method) in interface.items.iter().zip(methods_sign) {
         let args = rust_generate_args_with_types(f_method);
@@ -294,11 +893,17 @@ impl {trait_name} for {struct_with_funcs} {{"#,
         &mut code,
         r#"
 impl Drop for {struct_with_funcs} {{
-    fn drop(&mut self) {{
-       (self.{struct_with_funcs}_deref)(self.opaque);
+    fn drop(&mut self) {{{forget_foreign}
+       (self.C_{interface_name}_deref)(self.opaque);
     }}
 }}"#,
-        struct_with_funcs = struct_with_funcs
+        struct_with_funcs = struct_with_funcs,
+        interface_name = interface.name,
+        forget_foreign = if rust_impl {
+            "\n        self.foreign_forget();"
+        } else {
+            ""
+        },
     )
     .expect(WRITE_TO_MEM_FAILED_MSG);
 
@@ -353,36 +958,123 @@ fn find_suitable_ftypes_for_interace_methods(
                 )?
             }
         };
-        f_methods.push(CppForeignMethodSignature { output, input });
+        f_methods.push(CppForeignMethodSignature {
+            output,
+            input,
+            c_result_out: None,
+        });
     }
     Ok(f_methods)
 }
 
+/// Signatures of methods for implementation of interface by Rust object:
+/// the same C and C++ types as in `f_methods`, but converters in opposite direction.
+/// Returns `None` if some type can not be converted back
+fn find_suitable_ftypes_for_rust_impl(
+    ctx: &mut CppContext,
+    interface: &ForeignInterface,
+    f_methods: &[CppForeignMethodSignature],
+) -> Result<Option<Vec<CppForeignMethodSignature>>> {
+    fn is_same_ftype(a: &CppForeignTypeInfo, b: &CppForeignTypeInfo) -> bool {
+        !a.input_to_output
+            && !b.input_to_output
+            && a.base.name.display() == b.base.name.display()
+            && a.base.correspoding_rust_type.to_idx() == b.base.correspoding_rust_type.to_idx()
+            && a.cpp_converter.as_ref().map(|x| x.typename.display())
+                == b.cpp_converter.as_ref().map(|x| x.typename.display())
+    }
+
+    let mut rust_impl_f_methods = Vec::with_capacity(f_methods.len());
+    for (method, f_method) in interface.items.iter().zip(f_methods) {
+        let mut input = Vec::<CppForeignTypeInfo>::with_capacity(f_method.input.len());
+        for (arg, f_arg) in method.fn_decl.inputs.iter().skip(1).zip(&f_method.input) {
+            let named_arg = arg
+                .as_named_arg()
+                .map_err(|err| DiagnosticError::from_syn_err(interface.src_id, err))?;
+            let arg_rust_ty = ctx
+                .conv_map
+                .find_or_alloc_rust_type(&named_arg.ty, interface.src_id);
+            match map_type(
+                ctx,
+                &arg_rust_ty,
+                Direction::Incoming,
+                (interface.src_id, named_arg.ty.span()),
+            ) {
+                Ok(f_type) if is_same_ftype(&f_type, f_arg) => input.push(f_type),
+                _ => return Ok(None),
+            }
+        }
+        let output = match method.fn_decl.output {
+            syn::ReturnType::Default => ForeignTypeInfo {
+                name: f_method.output.base.name.clone(),
+                correspoding_rust_type: f_method.output.base.correspoding_rust_type.clone(),
+            }
+            .into(),
+            syn::ReturnType::Type(_, ref ret_ty) => {
+                let ret_rust_ty = ctx
+                    .conv_map
+                    .find_or_alloc_rust_type(ret_ty, interface.src_id);
+                match map_type(
+                    ctx,
+                    &ret_rust_ty,
+                    Direction::Outgoing,
+                    (interface.src_id, ret_ty.span()),
+                ) {
+                    Ok(f_type) if is_same_ftype(&f_type, &f_method.output) => f_type,
+                    _ => return Ok(None),
+                }
+            }
+        };
+        rust_impl_f_methods.push(CppForeignMethodSignature {
+            output,
+            input,
+            c_result_out: None,
+        });
+    }
+    Ok(Some(rust_impl_f_methods))
+}
+
 fn cpp_code_generate_interface(
     ctx: &mut CppContext,
     interface: &ForeignInterface,
     req_includes: &[SmolStr],
     f_methods: &[CppForeignMethodSignature],
+    rust_impl_f_methods: Option<&[CppForeignMethodSignature]>,
 ) -> std::result::Result<(), DiagnosticError> {
     use std::fmt::Write;
 
+    let rust_impl_shared = rust_impl_f_methods.is_some() && !rust_impl_needs_mut(interface)?;
     let c_interface_struct_header = c_interface_header(interface);
     let c_path = ctx.cfg.output_dir.join(&c_interface_struct_header);
     let mut file_c = FileWriteCache::new(&c_path, ctx.generated_foreign_files);
     let cpp_path = ctx.cfg.output_dir.join(cpp_interface_header(interface));
-    let mut file_cpp = FileWriteCache::new(&cpp_path, ctx.generated_foreign_files);
-    let interface_comments = cpp_code::doc_comments_to_c_comments(&interface.doc_comments, true);
+    let mut file_cpp = if ctx.cfg.pure_c_api {
+        FileWriteCache::new(&cpp_path, &mut NoNeedFsOpsRegistration)
+    } else {
+        FileWriteCache::new(&cpp_path, ctx.generated_foreign_files)
+    };
+    let interface_comments =
+        cpp_code::doc_comments_to_c_comments(ctx.conv_map, &interface.doc_comments, true);
+    let mut c_includes = String::new();
+    if ctx.cfg.pure_c_api {
+        c_includes.push_str("\n//for (u)intX_t types\n#include <stdint.h>\n");
+        for inc in cpp_code::c_includes_only(req_includes) {
+            writeln!(&mut c_includes, "#include {}", inc).expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+    }
 
     writeln!(
         file_c,
         r#"// Automatically generated by flapigen
 #pragma once
-{doc_comments}
-struct C_{interface_name} {{
+{c_includes}{doc_comments}
+struct {c_struct_name} {{
     void *opaque;
     //! call by Rust side when callback not need anymore
     void (*C_{interface_name}_deref)(void *opaque);"#,
         interface_name = interface.name,
+        c_struct_name = c_interface_struct_name(ctx, interface),
+        c_includes = c_includes,
         doc_comments = interface_comments
     )
     .expect(WRITE_TO_MEM_FAILED_MSG);
@@ -404,8 +1096,15 @@ struct C_{interface_name} {{
 "#,
         interface_name = interface.name
     );
+    let mut functions_args = String::new();
+    let mut functions_members = String::new();
+    let mut functions_init = String::new();
+    let mut functions_methods = String::new();
+    let mut functions_pass_args = String::new();
+    let mut rust_impl_methods = String::new();
+    let mut shared_methods = String::new();
 
-    for (method, f_method) in interface.items.iter().zip(f_methods) {
+    for (i, (method, f_method)) in interface.items.iter().zip(f_methods).enumerate() {
         let c_ret_type = &f_method.output.base.name;
         let mut known_names: FxHashSet<SmolStr> =
             method.arg_names_without_self().map(|x| x.into()).collect();
@@ -424,14 +1123,15 @@ struct C_{interface_name} {{
                     .replace(FROM_VAR_TEMPLATE, &ret_name);
                 (out_conv.typename.clone(), conv_code)
             } else {
-                (c_ret_type.clone(), String::new())
+                (c_ret_type.clone(), ret_name.to_string())
             };
         writeln!(
             file_c,
             r#"{doc_comments}
     {c_ret_type} (*{method_name})({single_args_with_types}void *opaque);"#,
             method_name = method.name,
-            doc_comments = cpp_code::doc_comments_to_c_comments(&method.doc_comments, false),
+            doc_comments =
+                cpp_code::doc_comments_to_c_comments(ctx.conv_map, &method.doc_comments, false),
             single_args_with_types = cpp_code::c_generate_args_with_types(
                 f_method,
                 method.arg_names_without_self(),
@@ -446,13 +1146,40 @@ struct C_{interface_name} {{
             r#"{doc_comments}
     virtual {cpp_ret_type} {method_name}({single_args_with_types}) noexcept = 0;"#,
             method_name = method.name,
-            doc_comments = cpp_code::doc_comments_to_c_comments(&method.doc_comments, false),
+            doc_comments =
+                cpp_code::doc_comments_to_c_comments(ctx.conv_map, &method.doc_comments, false),
             single_args_with_types =
                 cpp_code::cpp_generate_args_with_types(f_method, method.arg_names_without_self()),
             cpp_ret_type = cpp_ret_type,
         )
         .expect(WRITE_TO_MEM_FAILED_MSG);
 
+        generate_function_impl_for_method(
+            method,
+            f_method,
+            &cpp_ret_type,
+            FunctionsImplCode {
+                args: &mut functions_args,
+                members: &mut functions_members,
+                init: &mut functions_init,
+                methods: &mut functions_methods,
+                pass_args: &mut functions_pass_args,
+            },
+        );
+
+        generate_shared_impl_method(method, f_method, &cpp_ret_type, &mut shared_methods);
+
+        if let Some(rust_impl_f_methods) = rust_impl_f_methods {
+            generate_rust_impl_method(
+                method,
+                &rust_impl_f_methods[i],
+                &cpp_ret_type,
+                &ret_name,
+                known_names.clone(),
+                &mut rust_impl_methods,
+            )?;
+        }
+
         let (conv_args_code, call_input_args) =
             cpp_code::convert_args(f_method, &mut known_names, method.arg_names_without_self())?;
 
@@ -518,6 +1245,28 @@ struct C_{interface_name} {{
 "#,
         )
         .expect(WRITE_TO_MEM_FAILED_MSG);
+    if rust_impl_shared {
+        writeln!(
+            file_c,
+            r#"
+#ifdef __cplusplus
+extern "C" {{
+#endif
+
+//! if `self` wraps Rust `Arc`, fill `out` with struct that shares it and return 1,
+//! otherwise return 0
+char {c_struct_name}_share(const struct {c_struct_name} *self, struct {c_struct_name} *out);
+//! if `self` wraps Rust `Arc` with foreign implementation, fill `out` with copy of its struct,
+//! that is still owned by `self`, and return 1, otherwise return 0
+char {c_struct_name}_foreign(const struct {c_struct_name} *self, struct {c_struct_name} *out);
+
+#ifdef __cplusplus
+}}
+#endif"#,
+            c_struct_name = c_interface_struct_name(ctx, interface),
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
 
     let mut includes = String::new();
     for inc in req_includes {
@@ -530,7 +1279,9 @@ struct C_{interface_name} {{
 #pragma once
 
 #include <cassert>
+#include <functional> //for std::function
 #include <memory> //for std::unique_ptr
+#include <utility> //for std::move
 
 {includes}
 #include "{c_interface_struct_header}"
@@ -542,19 +1293,77 @@ public:
     virtual ~{interface_name}() noexcept {{}}
 {virtual_methods}
 
-    static C_{interface_name} to_c_interface(std::unique_ptr<{interface_name}> p)
+    static {c_struct_name} to_c_interface(std::unique_ptr<{interface_name}> p)
     {{
         assert(p != nullptr);
-        C_{interface_name} ret;
+        {c_struct_name} ret;{unwrap_rust_impl}
         ret.opaque = p.release();
 {cpp_fill_c_interface_struct}
         return ret;
     }}
-private:
+    static {c_struct_name} to_c_interface(std::shared_ptr<{interface_name}> p);
+    /// Create implementation that forwards methods calls to callables,
+    /// one for each method
+    static std::unique_ptr<{interface_name}> from_functions({functions_args});{from_c_interface}
+private:{into_c_interface}
 {static_reroute_methods}
 }};
+
+/// Implementation of {interface_name} via callables, see {interface_name}::from_functions
+class {interface_name}Functions final : public {interface_name} {{
+public:
+    {interface_name}Functions({functions_args}){functions_init}
+    {{
+    }}
+{functions_methods}
+private:
+{functions_members}}};
+
+inline std::unique_ptr<{interface_name}> {interface_name}::from_functions({functions_args})
+{{
+    return std::unique_ptr<{interface_name}>(new {interface_name}Functions({functions_pass_args}));
+}}
+
+/// Implementation of {interface_name} that shares ownership of other implementation,
+/// see {interface_name}::to_c_interface
+class {interface_name}Shared final : public {interface_name} {{
+public:
+    explicit {interface_name}Shared(std::shared_ptr<{interface_name}> p) noexcept
+        : p_(std::move(p))
+    {{
+    }}
+{shared_methods}
+private:{shared_origin}
+    std::shared_ptr<{interface_name}> p_;
+}};
+
+inline {c_struct_name} {interface_name}::to_c_interface(std::shared_ptr<{interface_name}> p)
+{{
+    assert(p != nullptr);{share_rust_impl}
+    return to_c_interface(std::unique_ptr<{interface_name}>(new {interface_name}Shared(std::move(p))));
+}}{rust_impl_class}
 }} // namespace {namespace_name}"##,
         interface_name = interface.name,
+        c_struct_name = c_interface_struct_name(ctx, interface),
+        functions_args = functions_args,
+        functions_init = if functions_init.is_empty() {
+            String::new()
+        } else {
+            format!("\n        : {}", functions_init)
+        },
+        functions_methods = functions_methods,
+        functions_members = functions_members,
+        functions_pass_args = functions_pass_args,
+        shared_methods = shared_methods,
+        shared_origin = if rust_impl_shared {
+            format!(
+                r#"
+    std::shared_ptr<{interface_name}> shared_origin() const noexcept override {{ return p_; }}"#,
+                interface_name = interface.name,
+            )
+        } else {
+            String::new()
+        },
         includes = includes,
         doc_comments = interface_comments,
         c_interface_struct_header = c_interface_struct_header,
@@ -562,19 +1371,312 @@ private:
         static_reroute_methods = cpp_static_reroute_methods,
         cpp_fill_c_interface_struct = cpp_fill_c_interface_struct,
         namespace_name = ctx.cfg.namespace_name,
+        unwrap_rust_impl = if rust_impl_f_methods.is_some() {
+            r#"
+        if (p->into_c_interface(ret)) {
+            return ret;
+        }"#
+        } else {
+            ""
+        },
+        share_rust_impl = if rust_impl_shared {
+            format!(
+                r#"
+    {c_struct_name} ret;
+    if (p->share_c_interface(ret)) {{
+        return ret;
+    }}"#,
+                c_struct_name = c_interface_struct_name(ctx, interface),
+            )
+        } else {
+            String::new()
+        },
+        from_c_interface = if rust_impl_f_methods.is_some() {
+            format!(
+                r#"
+    /// Take C struct returned by Rust, C++ implementation given back
+    /// by Rust is not wrapped twice
+    static std::unique_ptr<{interface_name}> from_c_interface({c_struct_name} c);{shared_from_c_interface}"#,
+                interface_name = interface.name,
+                c_struct_name = c_interface_struct_name(ctx, interface),
+                shared_from_c_interface = if rust_impl_shared {
+                    format!(
+                        r#"
+    static std::shared_ptr<{interface_name}> shared_from_c_interface({c_struct_name} c);"#,
+                        interface_name = interface.name,
+                        c_struct_name = c_interface_struct_name(ctx, interface),
+                    )
+                } else {
+                    String::new()
+                },
+            )
+        } else {
+            String::new()
+        },
+        into_c_interface = if rust_impl_f_methods.is_some() {
+            format!(
+                r#"
+    /// Implementation by Rust object gives back its C struct,
+    /// so it is not wrapped twice
+    virtual bool into_c_interface({c_struct_name} &) noexcept {{ return false; }}{share_c_interface}"#,
+                c_struct_name = c_interface_struct_name(ctx, interface),
+                share_c_interface = if rust_impl_shared {
+                    format!(
+                        r#"
+    /// Implementation by Rust `Arc` gives C struct that shares it
+    virtual bool share_c_interface({c_struct_name} &) const noexcept {{ return false; }}
+    /// Implementation that shares other implementation gives it back
+    virtual std::shared_ptr<{interface_name}> shared_origin() const noexcept {{ return nullptr; }}"#,
+                        c_struct_name = c_interface_struct_name(ctx, interface),
+                        interface_name = interface.name,
+                    )
+                } else {
+                    String::new()
+                },
+            )
+        } else {
+            String::new()
+        },
+        rust_impl_class = if rust_impl_f_methods.is_some() {
+            format!(
+                r#"
+
+/// Implementation of {interface_name} by Rust object
+class {interface_name}Rust final : public {interface_name} {{
+public:
+    explicit {interface_name}Rust({c_struct_name} c) noexcept
+        : c_(c)
+    {{
+    }}
+    {interface_name}Rust(const {interface_name}Rust &) = delete;
+    {interface_name}Rust &operator=(const {interface_name}Rust &) = delete;
+    ~{interface_name}Rust() noexcept override
+    {{
+        if (c_.opaque != nullptr) {{
+            c_.C_{interface_name}_deref(c_.opaque);
+        }}
+    }}
+{rust_impl_methods}
+private:
+    bool into_c_interface({c_struct_name} &out) noexcept override
+    {{
+        out = c_;
+        c_.opaque = nullptr;
+        return true;
+    }}{share_c_interface}
+    {c_struct_name} c_;
+}};
+
+inline std::unique_ptr<{interface_name}> {interface_name}::from_c_interface({c_struct_name} c)
+{{
+    if (c.C_{interface_name}_deref == c_{interface_name}_deref) {{
+        return std::unique_ptr<{interface_name}>(static_cast<{interface_name} *>(c.opaque));
+    }}
+    return std::unique_ptr<{interface_name}>(new {interface_name}Rust(c));
+}}{shared_from_c_interface}"#,
+                interface_name = interface.name,
+                c_struct_name = c_interface_struct_name(ctx, interface),
+                shared_from_c_interface = if rust_impl_shared {
+                    format!(
+                        r#"
+
+inline std::shared_ptr<{interface_name}> {interface_name}::shared_from_c_interface({c_struct_name} c)
+{{
+    {c_struct_name} foreign;
+    if ({c_struct_name}_foreign(&c, &foreign) != 0 && foreign.C_{interface_name}_deref == c_{interface_name}_deref) {{
+        std::shared_ptr<{interface_name}> p = static_cast<const {interface_name} *>(foreign.opaque)->shared_origin();
+        if (p != nullptr) {{
+            c.C_{interface_name}_deref(c.opaque);
+            return p;
+        }}
+    }}
+    return std::shared_ptr<{interface_name}>(new {interface_name}Rust(c));
+}}"#,
+                        interface_name = interface.name,
+                        c_struct_name = c_interface_struct_name(ctx, interface),
+                    )
+                } else {
+                    String::new()
+                },
+                rust_impl_methods = rust_impl_methods,
+                share_c_interface = if rust_impl_shared {
+                    format!(
+                        r#"
+    bool share_c_interface({c_struct_name} &out) const noexcept override
+    {{
+        return {c_struct_name}_share(&c_, &out) != 0;
+    }}"#,
+                        c_struct_name = c_interface_struct_name(ctx, interface),
+                    )
+                } else {
+                    String::new()
+                },
+            )
+        } else {
+            String::new()
+        },
     )
     .expect(WRITE_TO_MEM_FAILED_MSG);
 
+    cpp_code::prefix_c_items(ctx.cfg, ctx.prefixed_c_items, &mut file_c);
     file_c
         .update_file_if_necessary()
         .map_err(DiagnosticError::map_any_err_to_our_err)?;
-    file_cpp
-        .update_file_if_necessary()
-        .map_err(DiagnosticError::map_any_err_to_our_err)?;
+    if !ctx.cfg.pure_c_api {
+        cpp_code::prefix_c_items(ctx.cfg, ctx.prefixed_c_items, &mut file_cpp);
+        file_cpp
+            .update_file_if_necessary()
+            .map_err(DiagnosticError::map_any_err_to_our_err)?;
+    }
 
     Ok(())
 }
 
+struct FunctionsImplCode<'a> {
+    args: &'a mut String,
+    members: &'a mut String,
+    init: &'a mut String,
+    methods: &'a mut String,
+    pass_args: &'a mut String,
+}
+
+/// Code for `<Interface>Functions` class, that holds `std::function` for each method
+fn generate_function_impl_for_method(
+    method: &ForeignInterfaceMethod,
+    f_method: &CppForeignMethodSignature,
+    cpp_ret_type: &impl Display,
+    out: FunctionsImplCode,
+) {
+    use std::fmt::Write;
+
+    let mut arg_types = String::new();
+    let mut forward_args = String::new();
+    for (f_type_info, arg_name) in f_method.input.iter().zip(method.arg_names_without_self()) {
+        if f_type_info.input_to_output {
+            continue;
+        }
+        if !arg_types.is_empty() {
+            arg_types.push_str(", ");
+            forward_args.push_str(", ");
+        }
+        match f_type_info.cpp_converter.as_ref() {
+            Some(conv) => write!(&mut arg_types, "{}", conv.typename),
+            None => write!(&mut arg_types, "{}", f_type_info.as_ref().name),
+        }
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        write!(&mut forward_args, "std::move({})", arg_name).expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    let func_type = format!("std::function<{}({})>", cpp_ret_type, arg_types);
+    if !out.args.is_empty() {
+        out.args.push_str(", ");
+        out.init.push_str(", ");
+        out.pass_args.push_str(", ");
+    }
+    write!(out.args, "{} {}", func_type, method.name).expect(WRITE_TO_MEM_FAILED_MSG);
+    write!(out.init, "{name}_(std::move({name}))", name = method.name)
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    write!(out.pass_args, "std::move({})", method.name).expect(WRITE_TO_MEM_FAILED_MSG);
+    writeln!(out.members, "    {} {}_;", func_type, method.name).expect(WRITE_TO_MEM_FAILED_MSG);
+    writeln!(
+        out.methods,
+        r#"    {cpp_ret_type} {method_name}({args_with_types}) noexcept override
+    {{
+        return {method_name}_({forward_args});
+    }}"#,
+        cpp_ret_type = cpp_ret_type,
+        method_name = method.name,
+        args_with_types =
+            cpp_code::cpp_generate_args_with_types(f_method, method.arg_names_without_self()),
+        forward_args = forward_args,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+}
+
+/// Method of `<Interface>Shared` class, that calls shared implementation
+fn generate_shared_impl_method(
+    method: &ForeignInterfaceMethod,
+    f_method: &CppForeignMethodSignature,
+    cpp_ret_type: &impl Display,
+    out: &mut String,
+) {
+    use std::fmt::Write;
+
+    let mut forward_args = String::new();
+    for (f_type_info, arg_name) in f_method.input.iter().zip(method.arg_names_without_self()) {
+        if f_type_info.input_to_output {
+            continue;
+        }
+        if !forward_args.is_empty() {
+            forward_args.push_str(", ");
+        }
+        write!(&mut forward_args, "std::move({})", arg_name).expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    writeln!(
+        out,
+        r#"    {cpp_ret_type} {method_name}({args_with_types}) noexcept override
+    {{
+        return p_->{method_name}({forward_args});
+    }}"#,
+        cpp_ret_type = cpp_ret_type,
+        method_name = method.name,
+        args_with_types =
+            cpp_code::cpp_generate_args_with_types(f_method, method.arg_names_without_self()),
+        forward_args = forward_args,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+}
+
+/// Method of `<Interface>Rust` class, that calls function from C struct
+fn generate_rust_impl_method(
+    method: &ForeignInterfaceMethod,
+    f_method: &CppForeignMethodSignature,
+    cpp_ret_type: &impl Display,
+    ret_name: &str,
+    mut known_names: FxHashSet<SmolStr>,
+    out: &mut String,
+) -> std::result::Result<(), DiagnosticError> {
+    use std::fmt::Write;
+
+    let (conv_args_code, call_input_args) =
+        cpp_code::convert_args(f_method, &mut known_names, method.arg_names_without_self())?;
+    let call = format!(
+        "c_.{method_name}({input_args}{comma}c_.opaque)",
+        method_name = method.name,
+        input_args = call_input_args,
+        comma = if call_input_args.is_empty() { "" } else { ", " },
+    );
+    let call = if f_method.output.base.name.display() == "void" {
+        format!("{};", call)
+    } else if let Some(out_conv) = f_method.output.cpp_converter.as_ref() {
+        format!(
+            "auto {ret} = {call};\n        return {conv};",
+            ret = ret_name,
+            call = call,
+            conv = out_conv
+                .converter
+                .as_str()
+                .replace(FROM_VAR_TEMPLATE, ret_name),
+        )
+    } else {
+        format!("return {};", call)
+    };
+    writeln!(
+        out,
+        r#"    {cpp_ret_type} {method_name}({args_with_types}) noexcept override
+    {{
+{conv_args_code}        {call}
+    }}"#,
+        cpp_ret_type = cpp_ret_type,
+        method_name = method.name,
+        args_with_types =
+            cpp_code::cpp_generate_args_with_types(f_method, method.arg_names_without_self()),
+        conv_args_code = conv_args_code,
+        call = call,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    Ok(())
+}
+
 fn c_interface_header(interface: &ForeignInterface) -> String {
     format!("c_{}.h", interface.name)
 }
@@ -582,3 +1684,12 @@ fn c_interface_header(interface: &ForeignInterface) -> String {
 fn cpp_interface_header(interface: &ForeignInterface) -> String {
     format!("{}.hpp", interface.name)
 }
+
+/// Name of C struct with pointers to callback's methods
+fn c_interface_struct_name(ctx: &CppContext, interface: &ForeignInterface) -> String {
+    format!(
+        "{}C_{}",
+        cpp_code::c_name_prefix(ctx.cfg, None),
+        interface.name
+    )
+}
@@ -12,10 +12,11 @@ use crate::{
         RustTypeIdx, TypeConvCode, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
     },
     types::{ForeignClassInfo, SelfTypeDesc},
-    TypeMap,
+    CppConfig, TypeMap,
 };
 
 pub(in crate::cpp) fn register_typemap_for_self_type(
+    cfg: &CppConfig,
     conv_map: &mut TypeMap,
     class: &ForeignClassInfo,
     this_type: RustType,
@@ -48,6 +49,7 @@ pub(in crate::cpp) fn register_typemap_for_self_type(
     let this_type_mut_ref = conv_map.find_or_alloc_rust_type(&gen_ty, class.src_id);
 
     register_intermidiate_pointer_types(
+        cfg,
         conv_map,
         class,
         void_ptr_rust_ty.to_idx(),
@@ -80,6 +82,7 @@ pub(in crate::cpp) fn register_typemap_for_self_type(
 }
 
 fn register_intermidiate_pointer_types(
+    cfg: &CppConfig,
     conv_map: &mut TypeMap,
     class: &ForeignClassInfo,
     void_ptr_rust_ty: RustTypeIdx,
@@ -87,7 +90,7 @@ fn register_intermidiate_pointer_types(
 ) -> Result<()> {
     let c_ftype = ForeignTypeS {
         name: ForeignTypeName::new(
-            format!("{} *", cpp_code::c_class_type(class)),
+            format!("{} *", cpp_code::c_class_type(cfg, class)),
             (class.src_id, class.name.span()),
         ),
         provides_by_module: vec![format!("\"{}\"", cpp_code::c_header_name(class)).into()],
@@ -104,7 +107,7 @@ fn register_intermidiate_pointer_types(
 
     let c_const_ftype = ForeignTypeS {
         name: ForeignTypeName::new(
-            format!("const {} *", cpp_code::c_class_type(class)),
+            format!("const {} *", cpp_code::c_class_type(cfg, class)),
             (class.src_id, class.name.span()),
         ),
         provides_by_module: vec![format!("\"{}\"", cpp_code::c_header_name(class)).into()],
@@ -254,8 +257,20 @@ fn register_main_foreign_types(
         "register_main_foreign_types: this {}, self {}",
         conv_map[this_type], conv_map[self_type]
     );
+    // C pointer types, see `register_intermidiate_pointer_types`
+    let c_ptr_name = |conv_map: &TypeMap, rust_ty: RustTypeIdx| -> String {
+        let ftype = conv_map
+            .find_foreign_type_related_to_rust_ty(rust_ty)
+            .expect("C pointer type for class should be registered");
+        conv_map[ftype].name.display().to_string()
+    };
+    let c_ptr = c_ptr_name(conv_map, void_ptr_rust_ty);
+    let c_const_ptr = c_ptr_name(conv_map, const_void_ptr_rust_ty);
     let class_ftype = ForeignTypeS {
-        name: ForeignTypeName::new(class.name.to_string(), (class.src_id, class.name.span())),
+        name: ForeignTypeName::new(
+            cpp_code::cpp_class_name(class),
+            (class.src_id, class.name.span()),
+        ),
         provides_by_module: vec![format!("\"{}\"", cpp_code::cpp_header_name(class)).into()],
         into_from_rust: Some(ForeignConversationRule {
             rust_ty: this_type,
@@ -264,9 +279,9 @@ fn register_main_foreign_types(
                 intermediate_ty: void_ptr_rust_ty,
                 conv_code: Rc::new(TypeConvCode::new(
                     format!(
-                        "{class_name}(static_cast<{c_type} *>({var}))",
-                        class_name = class.name,
-                        c_type = cpp_code::c_class_type(class),
+                        "{class_name}(static_cast<{c_ptr}>({var}))",
+                        class_name = cpp_code::cpp_class_name(class),
+                        c_ptr = c_ptr,
                         var = FROM_VAR_TEMPLATE
                     ),
                     invalid_src_id_span(),
@@ -289,7 +304,7 @@ fn register_main_foreign_types(
 
     let class_ftype_ref_in = ForeignTypeS {
         name: ForeignTypeName::new(
-            format!("const {} &", class.name),
+            format!("const {} &", cpp_code::cpp_class_name(class)),
             (class.src_id, class.name.span()),
         ),
         provides_by_module: vec![format!("\"{}\"", cpp_code::cpp_header_name(class)).into()],
@@ -299,11 +314,7 @@ fn register_main_foreign_types(
                 input_to_output: false,
                 intermediate_ty: const_void_ptr_rust_ty,
                 conv_code: Rc::new(TypeConvCode::new(
-                    format!(
-                        "static_cast<const {} *>({})",
-                        cpp_code::c_class_type(class),
-                        FROM_VAR_TEMPLATE
-                    ),
+                    format!("static_cast<{}>({})", c_const_ptr, FROM_VAR_TEMPLATE),
                     invalid_src_id_span(),
                 )),
             }),
@@ -317,7 +328,7 @@ fn register_main_foreign_types(
     if !is_plain_class {
         let class_ftype_ref_out = ForeignTypeS {
             name: ForeignTypeName::new(
-                format!("{}Ref", class.name),
+                format!("{}Ref", cpp_code::cpp_class_name(class)),
                 (class.src_id, class.name.span()),
             ),
             provides_by_module: vec![format!("\"{}\"", cpp_code::cpp_header_name(class)).into()],
@@ -328,9 +339,9 @@ fn register_main_foreign_types(
                     intermediate_ty: const_void_ptr_rust_ty,
                     conv_code: Rc::new(TypeConvCode::new(
                         format!(
-                            "{class}Ref{{ static_cast<const {c_type} *>({var}) }}",
-                            class = class.name,
-                            c_type = cpp_code::c_class_type(class),
+                            "{class}Ref{{ static_cast<{c_ptr}>({var}) }}",
+                            class = cpp_code::cpp_class_name(class),
+                            c_ptr = c_const_ptr,
                             var = FROM_VAR_TEMPLATE
                         ),
                         invalid_src_id_span(),
@@ -344,7 +355,7 @@ fn register_main_foreign_types(
 
     let class_ftype_mut_ref_in = ForeignTypeS {
         name: ForeignTypeName::new(
-            format!("{} &", class.name),
+            format!("{} &", cpp_code::cpp_class_name(class)),
             (class.src_id, class.name.span()),
         ),
         provides_by_module: vec![format!("\"{}\"", cpp_code::cpp_header_name(class)).into()],
@@ -354,11 +365,7 @@ fn register_main_foreign_types(
                 input_to_output: false,
                 intermediate_ty: void_ptr_rust_ty,
                 conv_code: Rc::new(TypeConvCode::new(
-                    format!(
-                        "static_cast<{} *>({})",
-                        cpp_code::c_class_type(class),
-                        FROM_VAR_TEMPLATE
-                    ),
+                    format!("static_cast<{}>({})", c_ptr, FROM_VAR_TEMPLATE),
                     invalid_src_id_span(),
                 )),
             }),
@@ -377,7 +384,7 @@ fn register_main_foreign_types(
 
             let class_ftype_mut_ref_in = ForeignTypeS {
                 name: ForeignTypeName::new_with_unique_prefix(
-                    format!("/**/{} &", class.name),
+                    format!("/**/{} &", cpp_code::cpp_class_name(class)),
                     "/**/",
                     (class.src_id, class.name.span()),
                 ),
@@ -388,11 +395,7 @@ fn register_main_foreign_types(
                         input_to_output: false,
                         intermediate_ty: void_ptr_rust_ty,
                         conv_code: Rc::new(TypeConvCode::new(
-                            format!(
-                                "static_cast<{} *>({})",
-                                cpp_code::c_class_type(class),
-                                FROM_VAR_TEMPLATE
-                            ),
+                            format!("static_cast<{}>({})", c_ptr, FROM_VAR_TEMPLATE),
                             invalid_src_id_span(),
                         )),
                     }),
@@ -409,7 +412,7 @@ fn register_main_foreign_types(
 
             let class_ftype_ref_in = ForeignTypeS {
                 name: ForeignTypeName::new_with_unique_prefix(
-                    format!("/**/const {} &", class.name),
+                    format!("/**/const {} &", cpp_code::cpp_class_name(class)),
                     "/**/",
                     (class.src_id, class.name.span()),
                 ),
@@ -420,11 +423,7 @@ fn register_main_foreign_types(
                         input_to_output: false,
                         intermediate_ty: const_void_ptr_rust_ty,
                         conv_code: Rc::new(TypeConvCode::new(
-                            format!(
-                                "static_cast<const {} *>({})",
-                                cpp_code::c_class_type(class),
-                                FROM_VAR_TEMPLATE
-                            ),
+                            format!("static_cast<{}>({})", c_const_ptr, FROM_VAR_TEMPLATE),
                             invalid_src_id_span(),
                         )),
                     }),
@@ -35,8 +35,15 @@ mod fenum;
 mod finterface;
 mod map_class_self_type;
 mod map_type;
-
-use std::{io::Write, mem, path::PathBuf, rc::Rc};
+mod package;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+    mem,
+    path::PathBuf,
+    rc::Rc,
+};
 
 use log::{debug, trace};
 use proc_macro2::TokenStream;
@@ -58,16 +65,18 @@ use crate::{
         },
         ty::{ForeignConversationRule, ForeignType, ForeignTypeS, RustType},
         utils::{
-            configure_ftype_rule, remove_files_if, validate_cfg_options, ForeignMethodSignature,
-            ForeignTypeInfoT,
+            cfg_option_enabled, configure_ftype_rule, remove_files_if, validate_cfg_options,
+            ForeignMethodSignature, ForeignTypeInfoT,
         },
         CItem, CItems, ForeignTypeInfo, TypeConvCode, TypeMapConvRuleInfo,
     },
     types::{ForeignClassInfo, ForeignMethod, ItemToExpand, MethodAccess, MethodVariant},
     CppConfig, CppOptional, CppStrView, CppVariant, LanguageGenerator, SourceCode, TypeMap,
-    SMART_PTR_COPY_TRAIT, WRITE_TO_MEM_FAILED_MSG,
+    PLAIN_CLASS, SHARED_PTR_CLASS, SMART_PTR_COPY_TRAIT, WRITE_TO_MEM_FAILED_MSG,
 };
 
+const RUST_RESULT_EXCEPTION_HEADER: &str = "rust_result_exception.hpp";
+
 #[derive(Debug)]
 struct CppConverter {
     typename: UniqueName,
@@ -196,6 +205,16 @@ impl AsRef<ForeignTypeInfo> for CppForeignTypeInfo {
 struct CppForeignMethodSignature {
     output: CppForeignTypeInfo,
     input: Vec<CppForeignTypeInfo>,
+    c_result_out: Option<CResultOut>,
+}
+
+/// In pure C API method returns `uint8_t` instead of `Result<T, E>`,
+/// and `T` or `E` returned via out parameters
+struct CResultOut {
+    ok: Option<(RustType, CppForeignTypeInfo)>,
+    err: (RustType, CppForeignTypeInfo),
+    ok_out_name: SmolStr,
+    err_out_name: SmolStr,
 }
 
 impl From<ForeignTypeInfo> for CppForeignTypeInfo {
@@ -251,7 +270,24 @@ impl CppConfig {
                 traits.push("Copy");
             }
 
-            if class.smart_ptr_copy_derived() {
+            let shared_ptr_class = fclass::need_shared_ptr_class(class);
+            if shared_ptr_class && fclass::need_plain_class(class) {
+                return Err(DiagnosticError::new(
+                    class.src_id,
+                    class.span(),
+                    format!(
+                        "class {} marked as {} and {}, they are incompatible",
+                        class.name, SHARED_PTR_CLASS, PLAIN_CLASS
+                    ),
+                ));
+            }
+            let smart_ptr_copy = class.smart_ptr_copy_derived() || shared_ptr_class;
+            let smart_ptr_derive = if shared_ptr_class {
+                SHARED_PTR_CLASS
+            } else {
+                SMART_PTR_COPY_TRAIT
+            };
+            if smart_ptr_copy {
                 traits.push(SMART_PTR_COPY_TRAIT);
             }
 
@@ -261,11 +297,11 @@ impl CppConfig {
                 class.src_id,
             );
 
-            if class.smart_ptr_copy_derived() {
+            if smart_ptr_copy {
                 if class.copy_derived() {
                     println!(
                         "cargo:warning=class {} marked as Copy and {}, ignore Copy",
-                        class.name, SMART_PTR_COPY_TRAIT
+                        class.name, smart_ptr_derive
                     );
                 }
                 if check_if_smart_pointer_return_inner_type(&this_type, "Rc").is_none()
@@ -276,7 +312,7 @@ impl CppConfig {
                         this_type.ty.span(),
                         format!(
                             "class {} marked as {}, but type '{}' is not Arc<> or Rc<>",
-                            class.name, SMART_PTR_COPY_TRAIT, this_type
+                            class.name, smart_ptr_derive, this_type
                         ),
                     ));
                 }
@@ -293,13 +329,13 @@ impl CppConfig {
                         this_type.ty.span(),
                         format!(
                             "class {} marked as {}, but has clone method. Error: can not generate clone method.",
-                            class.name, SMART_PTR_COPY_TRAIT,
+                            class.name, smart_ptr_derive,
                         ),
                     ));
                 }
             }
 
-            register_typemap_for_self_type(conv_map, class, this_type, self_desc)?;
+            register_typemap_for_self_type(self, conv_map, class, this_type, self_desc)?;
         }
         conv_map.find_or_alloc_rust_type(&class.self_type_as_ty(), class.src_id);
         Ok(())
@@ -313,6 +349,8 @@ struct CppContext<'a> {
     rust_code: &'a mut Vec<TokenStream>,
     common_files: &'a mut FxHashMap<SmolStr, FileWriteCache>,
     generated_foreign_files: &'a mut FxHashSet<PathBuf>,
+    /// C items from typemaps, that should get `CppConfig::symbol_prefix`
+    prefixed_c_items: &'a mut FxHashSet<SmolStr>,
     class_ext_handlers: &'a ClassExtHandlers,
     method_ext_handlers: &'a MethodExtHandlers,
     enum_ext_handlers: &'a EnumExtHandlers,
@@ -331,6 +369,7 @@ impl LanguageGenerator for CppConfig {
         let mut ret = Vec::with_capacity(items.len());
         let mut files = FxHashMap::<SmolStr, FileWriteCache>::default();
         let mut generated_foreign_files = FxHashSet::default();
+        let mut prefixed_c_items = FxHashSet::default();
         {
             let mut ctx = CppContext {
                 cfg: self,
@@ -339,11 +378,15 @@ impl LanguageGenerator for CppConfig {
                 rust_code: &mut ret,
                 common_files: &mut files,
                 generated_foreign_files: &mut generated_foreign_files,
+                prefixed_c_items: &mut prefixed_c_items,
                 class_ext_handlers: ext_handlers.class_ext_handlers,
                 method_ext_handlers: ext_handlers.method_ext_handlers,
                 enum_ext_handlers: ext_handlers.enum_ext_handlers,
             };
             init(&mut ctx, code)?;
+            if !self.pure_c_api {
+                generate_module_headers(&mut ctx, &items)?;
+            }
             for item in &items {
                 if let ItemToExpand::Class(ref fclass) = item {
                     self.register_class(ctx.conv_map, fclass)?;
@@ -360,8 +403,12 @@ impl LanguageGenerator for CppConfig {
             }
         }
 
-        for (module_name, c_header_f) in files {
+        for (module_name, mut c_header_f) in files {
             let c_header_path = self.output_dir.join(module_name.as_str());
+            if self.pure_c_api {
+                cpp_code::strip_cpp_only_code(&mut c_header_f);
+            }
+            cpp_code::prefix_c_items(self, &prefixed_c_items, &mut c_header_f);
             c_header_f.update_file_if_necessary().map_err(|err| {
                 DiagnosticError::map_any_err_to_our_err(format!(
                     "write to {} failed: {}",
@@ -371,6 +418,10 @@ impl LanguageGenerator for CppConfig {
             })?;
         }
 
+        if let Some(package) = self.package.as_ref() {
+            package::generate_package(self, package, &generated_foreign_files)?;
+        }
+
         if remove_not_generated_files {
             remove_files_if(&self.output_dir, |path| {
                 if let Some(ext) = path.extension() {
@@ -387,22 +438,87 @@ impl LanguageGenerator for CppConfig {
     }
 }
 
-fn c_func_name(class: &ForeignClassInfo, method: &ForeignMethod) -> String {
-    do_c_func_name(class, method.access, &method.short_name())
+/// For each `#[namespace = "a::b"]` generate `a/b.hpp` with includes
+/// of all classes and enums from this namespace and nested namespaces
+fn generate_module_headers(ctx: &mut CppContext, items: &[ItemToExpand]) -> Result<()> {
+    let mut modules = BTreeMap::<String, BTreeSet<String>>::new();
+    for item in items {
+        let (namespace, header) = match item {
+            ItemToExpand::Class(fclass) => (
+                fclass.namespace.as_deref(),
+                cpp_code::cpp_header_name(fclass),
+            ),
+            ItemToExpand::Enum(fenum) => (
+                fenum.namespace.as_deref(),
+                cpp_code::cpp_header_name_for_enum(fenum),
+            ),
+            ItemToExpand::Interface(_) => continue,
+        };
+        let mut namespace = match namespace {
+            Some(ns) => ns,
+            None => continue,
+        };
+        modules
+            .entry(namespace.to_string())
+            .or_default()
+            .insert(header);
+        while let Some(pos) = namespace.rfind("::") {
+            let parent = &namespace[..pos];
+            modules
+                .entry(parent.to_string())
+                .or_default()
+                .insert(module_header_name(namespace));
+            namespace = parent;
+        }
+    }
+
+    for (namespace, headers) in modules {
+        let path = ctx.cfg.output_dir.join(module_header_name(&namespace));
+        let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+        writeln!(
+            file,
+            r#"// Automatically generated by flapigen
+#pragma once
+"#
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        for header in headers {
+            writeln!(file, "#include \"{}\"", header).expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+        file.update_file_if_necessary().map_err(|err| {
+            DiagnosticError::map_any_err_to_our_err(format!(
+                "write to {} failed: {}",
+                path.display(),
+                err
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+fn module_header_name(namespace: &str) -> String {
+    format!("{}.hpp", namespace.replace("::", "/"))
+}
+
+fn c_func_name(cfg: &CppConfig, class: &ForeignClassInfo, method: &ForeignMethod) -> String {
+    do_c_func_name(cfg, class, method.access, &method.short_name())
 }
 
 fn do_c_func_name(
+    cfg: &CppConfig,
     class: &ForeignClassInfo,
     method_access: MethodAccess,
     method_short_name: &str,
 ) -> String {
     format!(
-        "{access}{class_name}_{func}",
+        "{symbol_prefix}{access}{namespace_prefix}{class_name}_{func}",
+        symbol_prefix = cpp_code::c_symbol_prefix(cfg),
         access = match method_access {
             MethodAccess::Private => "private_",
             MethodAccess::Protected => "protected_",
             MethodAccess::Public => "",
         },
+        namespace_prefix = cpp_code::c_namespace_prefix(class.namespace.as_deref()),
         class_name = class.name,
         func = method_short_name,
     )
@@ -473,6 +589,24 @@ fn register_c_type(
     Ok(something_defined)
 }
 
+/// All options, that can be used in typemaps and `foreign_code`
+fn all_cfg_options() -> FxHashSet<&'static str> {
+    let mut opts = FxHashSet::<&'static str>::default();
+    opts.extend(CppOptional::iter().map(|x| -> &'static str { x.into() }));
+    opts.extend(CppVariant::iter().map(|x| -> &'static str { x.into() }));
+    opts.extend(CppStrView::iter().map(|x| -> &'static str { x.into() }));
+    opts
+}
+
+/// Options enabled for this configuration
+fn cfg_options(cfg: &CppConfig) -> FxHashSet<&'static str> {
+    let mut opts = FxHashSet::<&'static str>::default();
+    opts.insert(cfg.cpp_variant.into());
+    opts.insert(cfg.cpp_optional.into());
+    opts.insert(cfg.cpp_str_view.into());
+    opts
+}
+
 fn merge_rule(ctx: &mut CppContext, mut rule: TypeMapConvRuleInfo) -> Result<()> {
     debug!("merge_rule begin {:?}", rule);
     if rule.is_empty() {
@@ -482,22 +616,9 @@ fn merge_rule(ctx: &mut CppContext, mut rule: TypeMapConvRuleInfo) -> Result<()>
             format!("rule {:?} is empty", rule),
         ));
     }
-    let all_options = {
-        let mut opts = FxHashSet::<&'static str>::default();
-        opts.extend(CppOptional::iter().map(|x| -> &'static str { x.into() }));
-        opts.extend(CppVariant::iter().map(|x| -> &'static str { x.into() }));
-        opts.extend(CppStrView::iter().map(|x| -> &'static str { x.into() }));
-        opts
-    };
-
+    let all_options = all_cfg_options();
     validate_cfg_options(&rule, &all_options)?;
-    let options = {
-        let mut opts = FxHashSet::<&'static str>::default();
-        opts.insert(ctx.cfg.cpp_variant.into());
-        opts.insert(ctx.cfg.cpp_optional.into());
-        opts.insert(ctx.cfg.cpp_str_view.into());
-        opts
-    };
+    let options = cfg_options(ctx.cfg);
 
     if let Some(c_types) = rule.c_types.take() {
         merge_c_types(ctx, c_types, MergeCItemsFlags::DefineOnlyCItem, rule.src_id)?;
@@ -511,7 +632,7 @@ fn merge_rule(ctx: &mut CppContext, mut rule: TypeMapConvRuleInfo) -> Result<()>
         let use_fcode = fcode
             .cfg_option
             .as_ref()
-            .map(|opt| options.contains(opt.as_str()))
+            .map(|opt| cfg_option_enabled(opt.as_str(), &options))
             .unwrap_or(true);
 
         if use_fcode {
@@ -563,11 +684,38 @@ fn init(ctx: &mut CppContext, code: &[SourceCode]) -> Result<()> {
             ctx.cfg.output_dir.display()
         )));
     }
+    for part in ctx
+        .cfg
+        .symbol_prefix
+        .iter()
+        .chain(ctx.cfg.symbol_version.iter())
+    {
+        if part.is_empty()
+            || part.starts_with(|ch: char| ch.is_ascii_digit())
+            || !part.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        {
+            return Err(DiagnosticError::new_without_src_info(format!(
+                "CppConfig: symbol prefix/version '{}' can not be used as part of C identifier",
+                part
+            )));
+        }
+    }
     //for enum
     ctx.conv_map
         .find_or_alloc_rust_type_no_src_id(&parse_type! { u32 });
 
+    let not_merged_data = ctx.conv_map.take_not_merged_not_generic_rules();
+    for rule in not_merged_data {
+        merge_rule(ctx, rule)?;
+    }
+
+    // after merge of rules, to know C items from typemaps
     for cu in code {
+        if (ctx.cfg.pure_c_api && cu.id_of_code.ends_with(".hpp"))
+            || !need_foreign_lang_helper(ctx.cfg, &cu.id_of_code)
+        {
+            continue;
+        }
         let src_path = ctx.cfg.output_dir.join(&cu.id_of_code);
         let mut src_file = FileWriteCache::new(&src_path, ctx.generated_foreign_files);
         src_file
@@ -583,6 +731,7 @@ fn init(ctx: &mut CppContext, code: &[SourceCode]) -> Result<()> {
                     err
                 ))
             })?;
+        cpp_code::prefix_c_items(ctx.cfg, ctx.prefixed_c_items, &mut src_file);
         src_file.update_file_if_necessary().map_err(|err| {
             DiagnosticError::map_any_err_to_our_err(format!(
                 "update of {} failed: {}",
@@ -592,10 +741,131 @@ fn init(ctx: &mut CppContext, code: &[SourceCode]) -> Result<()> {
         })?;
     }
 
-    let not_merged_data = ctx.conv_map.take_not_merged_not_generic_rules();
-    for rule in not_merged_data {
-        merge_rule(ctx, rule)?;
+    if ctx.cfg.use_exceptions {
+        if ctx.cfg.pure_c_api {
+            return Err(DiagnosticError::new_without_src_info(
+                "CppConfig: use_exceptions and pure_c_api can not be used together",
+            ));
+        }
+        generate_result_exception_header(ctx)?;
     }
 
     Ok(())
 }
+
+/// Bundled replacements of C++17 types are written only if they are selected
+fn need_foreign_lang_helper(cfg: &CppConfig, id_of_code: &str) -> bool {
+    match id_of_code {
+        "rust_optional_impl.hpp" => {
+            matches!(cfg.cpp_optional, CppOptional::Bundled)
+                || matches!(cfg.cpp_variant, CppVariant::Bundled)
+        }
+        "rust_variant_impl.hpp" => matches!(cfg.cpp_variant, CppVariant::Bundled),
+        "rust_string_view_impl.hpp" => matches!(cfg.cpp_str_view, CppStrView::Bundled),
+        _ => true,
+    }
+}
+
+fn generate_result_exception_header(ctx: &mut CppContext) -> Result<()> {
+    let (includes, variant, optional, is_ok, get_ok, get_err) = match ctx.cfg.cpp_variant {
+        CppVariant::Std17 => (
+            "#include <optional>\n#include <variant>",
+            "std::variant",
+            "std::optional",
+            "res.index() == 0",
+            "std::get<0>(std::move(res))",
+            "std::get<1>(std::move(res))",
+        ),
+        CppVariant::Boost => (
+            "#include <boost/optional.hpp>\n#include <boost/variant.hpp>",
+            "boost::variant",
+            "boost::optional",
+            "res.which() == 0",
+            "boost::get<T>(std::move(res))",
+            "boost::get<E>(std::move(res))",
+        ),
+        CppVariant::Bundled => (
+            "#include \"rust_optional_impl.hpp\"\n#include \"rust_variant_impl.hpp\"",
+            "RustVariant",
+            "RustOptional",
+            "res.is_ok()",
+            "std::move(res.ok_value())",
+            "std::move(res.err_value())",
+        ),
+    };
+    let src_path = ctx.cfg.output_dir.join(RUST_RESULT_EXCEPTION_HEADER);
+    let mut src_file = FileWriteCache::new(&src_path, ctx.generated_foreign_files);
+    write!(
+        src_file,
+        r#"// Automatically generated by flapigen
+#pragma once
+
+#include <memory>
+#include <stdexcept>
+#include <string>
+#include <utility>
+{includes}
+
+namespace {namespace} {{
+/// Base class for exceptions thrown instead of returning Rust `Err`
+class RustException : public std::runtime_error {{
+public:
+    using std::runtime_error::runtime_error;
+}};
+
+/// Exception that holds error returned from Rust method
+template <typename E> class RustError final : public RustException {{
+public:
+    explicit RustError(E err)
+        : RustException(what_for(err, 0))
+        , err_(std::make_shared<E>(std::move(err)))
+    {{
+    }}
+    const E &error() const noexcept {{ return *err_; }}
+
+private:
+    template <typename T>
+    static auto what_for(const T &err, int) -> decltype(err.to_std_string())
+    {{
+        return err.to_std_string();
+    }}
+    template <typename T> static std::string what_for(const T &, long)
+    {{
+        return "Rust error";
+    }}
+    std::shared_ptr<E> err_;
+}};
+
+template <typename T, typename E> T rust_result_unwrap({variant}<T, E> res)
+{{
+    if (!({is_ok})) {{
+        throw RustError<E>({get_err});
+    }}
+    return {get_ok};
+}}
+
+template <typename E> void rust_result_unwrap({optional}<E> err)
+{{
+    if (err) {{
+        throw RustError<E>(std::move(*err));
+    }}
+}}
+}} // namespace {namespace}
+"#,
+        includes = includes,
+        namespace = ctx.cfg.namespace_name,
+        variant = variant,
+        optional = optional,
+        is_ok = is_ok,
+        get_ok = get_ok,
+        get_err = get_err,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    src_file.update_file_if_necessary().map_err(|err| {
+        DiagnosticError::map_any_err_to_our_err(format!(
+            "update of {} failed: {}",
+            src_path.display(),
+            err
+        ))
+    })
+}
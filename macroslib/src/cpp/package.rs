@@ -0,0 +1,126 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use rustc_hash::FxHashSet;
+
+use crate::{
+    error::{DiagnosticError, Result},
+    file_cache::{FileWriteCache, NoNeedFsOpsRegistration},
+    CppConfig, CppOptional, CppPackage, CppStrView, CppVariant, WRITE_TO_MEM_FAILED_MSG,
+};
+
+pub(in crate::cpp) fn generate_package(
+    cfg: &CppConfig,
+    package: &CppPackage,
+    generated_foreign_files: &FxHashSet<PathBuf>,
+) -> Result<()> {
+    let name = package_name(cfg);
+    let mut headers = generated_foreign_files
+        .iter()
+        .filter_map(|p| p.strip_prefix(&cfg.output_dir).ok())
+        .map(cmake_path)
+        .collect::<Vec<_>>();
+    headers.sort();
+
+    let cmake_path = cfg.output_dir.join(format!("{}Config.cmake", name));
+    let mut cmake_f = FileWriteCache::new(&cmake_path, &mut NoNeedFsOpsRegistration);
+    write_cmake_config(cfg, package, &name, &headers, &mut cmake_f);
+    cmake_f
+        .update_file_if_necessary()
+        .map_err(|err| write_err(&cmake_path, err))?;
+
+    let pc_path = cfg.output_dir.join(format!("{}.pc", name));
+    let mut pc_f = FileWriteCache::new(&pc_path, &mut NoNeedFsOpsRegistration);
+    write_pkg_config(package, &name, &mut pc_f);
+    pc_f.update_file_if_necessary()
+        .map_err(|err| write_err(&pc_path, err))?;
+    Ok(())
+}
+
+fn write_err(path: &Path, err: std::io::Error) -> DiagnosticError {
+    DiagnosticError::map_any_err_to_our_err(format!("write to {} failed: {}", path.display(), err))
+}
+
+/// Namespace name usable as file and CMake target name
+fn package_name(cfg: &CppConfig) -> String {
+    cfg.namespace_name.replace("::", "_")
+}
+
+/// CMake treats backslash as escape symbol even on Windows
+fn cmake_path(p: &Path) -> String {
+    p.display().to_string().replace('\\', "/")
+}
+
+fn write_cmake_config(
+    cfg: &CppConfig,
+    package: &CppPackage,
+    name: &str,
+    headers: &[String],
+    file: &mut FileWriteCache,
+) {
+    let lib_dir = cmake_path(&package.lib_dir);
+    let need_cpp17 = !cfg.pure_c_api
+        && (matches!(cfg.cpp_optional, CppOptional::Std17)
+            || matches!(cfg.cpp_variant, CppVariant::Std17)
+            || matches!(cfg.cpp_str_view, CppStrView::Std17));
+    let compile_features = if need_cpp17 {
+        "\n    INTERFACE_COMPILE_FEATURES cxx_std_17"
+    } else if !cfg.pure_c_api {
+        "\n    INTERFACE_COMPILE_FEATURES cxx_std_11"
+    } else {
+        ""
+    };
+    let mut headers_list = String::new();
+    for h in headers {
+        headers_list.push_str(&format!("\n    \"${{CMAKE_CURRENT_LIST_DIR}}/{}\"", h));
+    }
+    writeln!(
+        file,
+        r#"# Automatically generated by flapigen
+if(TARGET {name}::{name})
+  return()
+endif()
+
+set({name}_VERSION "{version}")
+set({name}_HEADERS{headers_list})
+
+add_library({name}::{name} SHARED IMPORTED)
+set_target_properties({name}::{name} PROPERTIES
+    INTERFACE_INCLUDE_DIRECTORIES "${{CMAKE_CURRENT_LIST_DIR}}"{compile_features}
+    IMPORTED_LOCATION "{lib_dir}/${{CMAKE_SHARED_LIBRARY_PREFIX}}{lib_name}${{CMAKE_SHARED_LIBRARY_SUFFIX}}")
+if(WIN32)
+  set_target_properties({name}::{name} PROPERTIES
+      IMPORTED_IMPLIB "{lib_dir}/{lib_name}.dll.lib")
+endif()"#,
+        name = name,
+        version = package.version,
+        headers_list = headers_list,
+        compile_features = compile_features,
+        lib_dir = lib_dir,
+        lib_name = package.lib_name,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+}
+
+fn write_pkg_config(package: &CppPackage, name: &str, file: &mut FileWriteCache) {
+    writeln!(
+        file,
+        r#"# Automatically generated by flapigen
+includedir=${{pcfiledir}}
+libdir={lib_dir}
+
+Name: {name}
+Description: {description}
+Version: {version}
+Cflags: -I${{includedir}}
+Libs: -L${{libdir}} -l{lib_name}"#,
+        name = name,
+        description = package.description,
+        version = package.version,
+        lib_dir = package.lib_dir.display(),
+        lib_name = package.lib_name,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+}
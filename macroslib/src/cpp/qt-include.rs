@@ -0,0 +1,296 @@
+// Optional typemaps for Qt types, turned on by `CppConfig::qt_typemaps`
+// or `Generator::merge_type_map("qt-include.rs", CPP_QT_TYPEMAPS)`.
+// Use the aliases below in `foreign_class!` to get Qt types on C++ side.
+// Containers of foreign classes require copyable classes,
+// like `#[derive(Copy)]`, `#[derive(SmartPtrCopy)]` or `#[derive(SharedPtr)]`.
+
+#[allow(dead_code)]
+pub type QString = String;
+#[allow(dead_code)]
+pub type QRustStrView<'a> = &'a str;
+#[allow(dead_code)]
+pub type QByteArray = Vec<u8>;
+#[allow(dead_code)]
+pub type QRustByteView<'a> = &'a [u8];
+#[allow(dead_code)]
+pub type QVector<T> = Vec<T>;
+#[allow(dead_code)]
+pub type QList<T> = Vec<T>;
+#[allow(dead_code)]
+pub type QDateTime = ::std::time::SystemTime;
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CRustQStringView {
+    data: *const u16,
+    len: usize,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CRustByteView {
+    data: *const u8,
+    len: usize,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CRustByteVec {
+    data: *const u8,
+    len: usize,
+    capacity: usize,
+}
+
+foreign_typemap!(
+    define_c_type!(module = "rust_qt.h";
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        pub struct CRustQStringView {
+            data: *const u16,
+            len: usize,
+        }
+
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        pub struct CRustByteView {
+            data: *const u8,
+            len: usize,
+        }
+
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        pub struct CRustByteVec {
+            data: *const u8,
+            len: usize,
+            capacity: usize,
+        }
+
+        #[no_mangle]
+        pub extern "C" fn crust_byte_vec_free(x: CRustByteVec) {
+            let v = unsafe { Vec::from_raw_parts(x.data as *mut u8, x.len, x.capacity) };
+            drop(v);
+        }
+    );
+    (r_type) CRustQStringView;
+    (f_type) "CRustQStringView";
+);
+
+foreign_typemap!(
+    ($p:r_type) QString => CRustString {
+        $out = CRustString::from_string($p);
+    };
+    ($p:r_type) QString <= CRustQStringView {
+        $out = if $p.len != 0 {
+            let slice: &[u16] = unsafe { ::std::slice::from_raw_parts($p.data, $p.len) };
+            String::from_utf16_lossy(slice)
+        } else {
+            String::new()
+        };
+    };
+    ($p:f_type, req_modules = ["\"rust_str.h\"", "<QString>"]) => "QString"
+        r#"[](CRustString s) -> QString {
+            QString qs = QString::fromUtf8(s.data, static_cast<int>(s.len));
+            CRustString_free(s);
+            return qs;
+          }($p)"#;
+    ($p:f_type, req_modules = ["\"rust_qt.h\"", "<QString>"]) <= "QString"
+        "CRustQStringView{ reinterpret_cast<const uint16_t *>($p.utf16()), static_cast<uintptr_t>($p.size()) }";
+);
+
+foreign_typemap!(
+    ($p:r_type) QRustStrView => CRustStrView {
+        $out = CRustStrView::from_str($p);
+    };
+    ($p:f_type, req_modules = ["\"rust_str.h\"", "<QString>"], unique_prefix = "/*&str*/")
+        => "/*&str*/QString"
+        "QString::fromUtf8($p.data, static_cast<int>($p.len))";
+);
+
+foreign_typemap!(
+    ($p:r_type) QByteArray => CRustByteVec {
+        let mut v = ::std::mem::ManuallyDrop::new($p);
+        $out = CRustByteVec {
+            data: v.as_mut_ptr(),
+            len: v.len(),
+            capacity: v.capacity(),
+        };
+    };
+    ($p:r_type) QByteArray <= CRustByteView {
+        $out = if $p.len != 0 {
+            unsafe { ::std::slice::from_raw_parts($p.data, $p.len) }.to_vec()
+        } else {
+            Vec::new()
+        };
+    };
+    ($p:f_type, req_modules = ["\"rust_qt.h\"", "<QByteArray>"]) => "QByteArray"
+        r#"[](CRustByteVec v) -> QByteArray {
+            QByteArray bytes(reinterpret_cast<const char *>(v.data), static_cast<int>(v.len));
+            crust_byte_vec_free(v);
+            return bytes;
+          }($p)"#;
+    ($p:f_type, req_modules = ["\"rust_qt.h\"", "<QByteArray>"]) <= "QByteArray"
+        "CRustByteView{ reinterpret_cast<const uint8_t *>($p.constData()), static_cast<uintptr_t>($p.size()) }";
+);
+
+foreign_typemap!(
+    ($p:r_type) QRustByteView => CRustByteView {
+        $out = CRustByteView {
+            data: $p.as_ptr(),
+            len: $p.len(),
+        };
+    };
+    ($p:r_type) QRustByteView <= CRustByteView {
+        $out = if $p.len != 0 {
+            unsafe { ::std::slice::from_raw_parts($p.data, $p.len) }
+        } else {
+            &[]
+        };
+    };
+    ($p:f_type, req_modules = ["\"rust_qt.h\"", "<QByteArray>"], unique_prefix = "/*&[u8]*/")
+        => "/*&[u8]*/QByteArray"
+        "QByteArray(reinterpret_cast<const char *>($p.data), static_cast<int>($p.len))";
+    ($p:f_type, req_modules = ["\"rust_qt.h\"", "<QByteArray>"], unique_prefix = "/*&[u8]*/")
+        <= "/*&[u8]*/QByteArray"
+        "CRustByteView{ reinterpret_cast<const uint8_t *>($p.constData()), static_cast<uintptr_t>($p.size()) }";
+);
+
+#[allow(dead_code)]
+#[inline]
+fn take_foreign_classes_from_vec<T: SwigForeignClass>(
+    v: CRustForeignVec,
+    out: *mut ::std::os::raw::c_void,
+) {
+    assert!(v.len == 0 || ::std::mem::size_of::<T>() == v.step);
+    let out = out as *mut *mut ::std::os::raw::c_void;
+    let v = unsafe { Vec::from_raw_parts(v.data as *mut T, v.len, v.capacity) };
+    for (i, x) in v.into_iter().enumerate() {
+        unsafe { *out.add(i) = T::box_object(x) };
+    }
+}
+
+foreign_typemap!(
+    generic_alias!(CQVectorModule = swig_concat_idents!(rust_qvector_, swig_f_type!(T)));
+    generic_alias!(CQVectorNew = swig_concat_idents!(RustQVector, swig_f_type!(T), _new));
+    generic_alias!(CQVectorPush = swig_concat_idents!(RustQVector, swig_f_type!(T), _push));
+    generic_alias!(CQVectorTake = swig_concat_idents!(RustQVector, swig_f_type!(T), _take));
+    define_c_type!(
+        module = "CQVectorModule!().h";
+        #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
+        #[no_mangle]
+        pub extern "C" fn CQVectorNew!()(capacity: usize) -> CRustForeignVec {
+            CRustForeignVec::from_vec(Vec::<swig_subst_type!(T)>::with_capacity(capacity))
+        }
+
+        #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
+        #[no_mangle]
+        pub extern "C" fn CQVectorPush!()(v: *mut CRustForeignVec, e: *mut ::std::os::raw::c_void) {
+            push_foreign_class_to_vec::<swig_subst_type!(T)>(v, e);
+        }
+
+        #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
+        #[no_mangle]
+        pub extern "C" fn CQVectorTake!()(v: CRustForeignVec, out: *mut ::std::os::raw::c_void) {
+            take_foreign_classes_from_vec::<swig_subst_type!(T)>(v, out);
+        }
+    );
+    ($p:r_type) <T: SwigForeignClass> QVector<T> => CRustForeignVec {
+        $out = CRustForeignVec::from_vec($p);
+    };
+    ($p:r_type) <T: SwigForeignClass> QVector<T> <= CRustForeignVec {
+        $out = unsafe { Vec::from_raw_parts($p.data as *mut swig_subst_type!(T), $p.len, $p.capacity) };
+    };
+    ($p:f_type, req_modules = ["\"CQVectorModule!().h\"", "\"rust_vec.h\"", "<QVector>"]) => "QVector<swig_f_type!(T)>"
+        r#"[](CRustForeignVec v) -> QVector<swig_f_type!(T)> {
+            QVector<void *> ptrs(static_cast<int>(v.len));
+            CQVectorTake!()(v, ptrs.data());
+            QVector<swig_f_type!(T)> items;
+            items.reserve(ptrs.size());
+            for (void *p : ptrs) {
+                items.append(swig_f_type!(T){ static_cast<swig_f_type!(T)::CForeignType *>(p) });
+            }
+            return items;
+          }($p)"#;
+    ($p:f_type, req_modules = ["\"CQVectorModule!().h\"", "\"rust_vec.h\"", "<QVector>"]) <= "QVector<swig_f_type!(T)>"
+        r#"[](QVector<swig_f_type!(T)> v) -> CRustForeignVec {
+            CRustForeignVec out = CQVectorNew!()(static_cast<uintptr_t>(v.size()));
+            for (int i = 0; i < v.size(); ++i) {
+                CQVectorPush!()(&out, v[i].release());
+            }
+            return out;
+          }(std::move($p))"#;
+);
+
+foreign_typemap!(
+    generic_alias!(CQListModule = swig_concat_idents!(rust_qlist_, swig_f_type!(T)));
+    generic_alias!(CQListNew = swig_concat_idents!(RustQList, swig_f_type!(T), _new));
+    generic_alias!(CQListPush = swig_concat_idents!(RustQList, swig_f_type!(T), _push));
+    generic_alias!(CQListTake = swig_concat_idents!(RustQList, swig_f_type!(T), _take));
+    define_c_type!(
+        module = "CQListModule!().h";
+        #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
+        #[no_mangle]
+        pub extern "C" fn CQListNew!()(capacity: usize) -> CRustForeignVec {
+            CRustForeignVec::from_vec(Vec::<swig_subst_type!(T)>::with_capacity(capacity))
+        }
+
+        #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
+        #[no_mangle]
+        pub extern "C" fn CQListPush!()(v: *mut CRustForeignVec, e: *mut ::std::os::raw::c_void) {
+            push_foreign_class_to_vec::<swig_subst_type!(T)>(v, e);
+        }
+
+        #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
+        #[no_mangle]
+        pub extern "C" fn CQListTake!()(v: CRustForeignVec, out: *mut ::std::os::raw::c_void) {
+            take_foreign_classes_from_vec::<swig_subst_type!(T)>(v, out);
+        }
+    );
+    ($p:r_type) <T: SwigForeignClass> QList<T> => CRustForeignVec {
+        $out = CRustForeignVec::from_vec($p);
+    };
+    ($p:r_type) <T: SwigForeignClass> QList<T> <= CRustForeignVec {
+        $out = unsafe { Vec::from_raw_parts($p.data as *mut swig_subst_type!(T), $p.len, $p.capacity) };
+    };
+    ($p:f_type, req_modules = ["\"CQListModule!().h\"", "\"rust_vec.h\"", "<QList>", "<QVector>"]) => "QList<swig_f_type!(T)>"
+        r#"[](CRustForeignVec v) -> QList<swig_f_type!(T)> {
+            QVector<void *> ptrs(static_cast<int>(v.len));
+            CQListTake!()(v, ptrs.data());
+            QList<swig_f_type!(T)> items;
+            items.reserve(ptrs.size());
+            for (void *p : ptrs) {
+                items.append(swig_f_type!(T){ static_cast<swig_f_type!(T)::CForeignType *>(p) });
+            }
+            return items;
+          }($p)"#;
+    ($p:f_type, req_modules = ["\"CQListModule!().h\"", "\"rust_vec.h\"", "<QList>"]) <= "QList<swig_f_type!(T)>"
+        r#"[](QList<swig_f_type!(T)> v) -> CRustForeignVec {
+            CRustForeignVec out = CQListNew!()(static_cast<uintptr_t>(v.size()));
+            for (int i = 0; i < v.size(); ++i) {
+                CQListPush!()(&out, v[i].release());
+            }
+            return out;
+          }(std::move($p))"#;
+);
+
+foreign_typemap!(
+    ($p:r_type) QDateTime => i64 {
+        $out = match $p.duration_since(::std::time::UNIX_EPOCH) {
+            Ok(d) => d.as_millis() as i64,
+            Err(err) => -(err.duration().as_millis() as i64),
+        };
+    };
+    ($p:r_type) QDateTime <= i64 {
+        $out = if $p >= 0 {
+            ::std::time::UNIX_EPOCH + ::std::time::Duration::from_millis($p as u64)
+        } else {
+            ::std::time::UNIX_EPOCH - ::std::time::Duration::from_millis($p.wrapping_neg() as u64)
+        };
+    };
+    ($p:f_type, req_modules = ["<QDateTime>"]) => "QDateTime"
+        "QDateTime::fromMSecsSinceEpoch($p, Qt::UTC)";
+    ($p:f_type, req_modules = ["<QDateTime>"]) <= "QDateTime"
+        "$p.toMSecsSinceEpoch()";
+);
@@ -0,0 +1,87 @@
+#pragma once
+
+#include <cassert>
+#include <new>
+#include <type_traits>
+#include <utility>
+
+namespace RUST_SWIG_USER_NAMESPACE {
+
+/// Minimal replacement of std::optional for C++11
+template <typename T> class RustOptional final {
+public:
+    using value_type = T;
+
+    RustOptional() noexcept : has_value_(false) {}
+    RustOptional(T v) : has_value_(true) { new (&storage_) T(std::move(v)); }
+    RustOptional(const RustOptional &o) : has_value_(o.has_value_)
+    {
+        if (has_value_) {
+            new (&storage_) T(*o);
+        }
+    }
+    RustOptional(RustOptional &&o) : has_value_(o.has_value_)
+    {
+        if (has_value_) {
+            new (&storage_) T(std::move(*o));
+        }
+    }
+    RustOptional &operator=(const RustOptional &o)
+    {
+        if (this != &o) {
+            RustOptional tmp(o);
+            *this = std::move(tmp);
+        }
+        return *this;
+    }
+    RustOptional &operator=(RustOptional &&o)
+    {
+        if (this != &o) {
+            reset();
+            if (o.has_value_) {
+                new (&storage_) T(std::move(*o));
+                has_value_ = true;
+            }
+        }
+        return *this;
+    }
+    ~RustOptional() noexcept { reset(); }
+    explicit operator bool() const noexcept { return has_value_; }
+    bool has_value() const noexcept { return has_value_; }
+    T &operator*() noexcept
+    {
+        assert(has_value_);
+        return *ptr();
+    }
+    const T &operator*() const noexcept
+    {
+        assert(has_value_);
+        return *ptr();
+    }
+    T *operator->() noexcept
+    {
+        assert(has_value_);
+        return ptr();
+    }
+    const T *operator->() const noexcept
+    {
+        assert(has_value_);
+        return ptr();
+    }
+    T value_or(T def) const { return has_value_ ? **this : std::move(def); }
+    void reset() noexcept
+    {
+        if (has_value_) {
+            ptr()->~T();
+            has_value_ = false;
+        }
+    }
+
+private:
+    T *ptr() noexcept { return reinterpret_cast<T *>(&storage_); }
+    const T *ptr() const noexcept { return reinterpret_cast<const T *>(&storage_); }
+
+    typename std::aligned_storage<sizeof(T), alignof(T)>::type storage_;
+    bool has_value_;
+};
+} // namespace RUST_SWIG_USER_NAMESPACE
@@ -0,0 +1,42 @@
+#pragma once
+
+#include <cassert>
+#include <cstddef>
+#include <cstring>
+#include <string>
+
+namespace RUST_SWIG_USER_NAMESPACE {
+
+/// Minimal replacement of std::string_view for C++11
+class RustStringView final {
+public:
+    using const_iterator = const char *;
+
+    RustStringView() noexcept : data_(nullptr), size_(0) {}
+    RustStringView(const char *data, size_t size) noexcept : data_(data), size_(size) {}
+    RustStringView(const char *s) noexcept : data_(s), size_(std::strlen(s)) {}
+    RustStringView(const std::string &s) noexcept : data_(s.data()), size_(s.size()) {}
+    const char *data() const noexcept { return data_; }
+    size_t size() const noexcept { return size_; }
+    size_t length() const noexcept { return size_; }
+    bool empty() const noexcept { return size_ == 0; }
+    const_iterator begin() const noexcept { return data_; }
+    const_iterator end() const noexcept { return data_ + size_; }
+    char operator[](size_t i) const noexcept
+    {
+        assert(i < size_);
+        return data_[i];
+    }
+    std::string to_string() const { return std::string(data_, size_); }
+    explicit operator std::string() const { return to_string(); }
+    friend bool operator==(RustStringView a, RustStringView b) noexcept
+    {
+        return a.size_ == b.size_ && (a.size_ == 0 || std::memcmp(a.data_, b.data_, a.size_) == 0);
+    }
+    friend bool operator!=(RustStringView a, RustStringView b) noexcept { return !(a == b); }
+
+private:
+    const char *data_;
+    size_t size_;
+};
+} // namespace RUST_SWIG_USER_NAMESPACE
@@ -0,0 +1,107 @@
+#pragma once
+
+#include <cassert>
+#include <cstddef>
+#include <new>
+#include <type_traits>
+#include <utility>
+
+namespace RUST_SWIG_USER_NAMESPACE {
+
+/// Minimal replacement of std::variant for C++11,
+/// holds `Ok` or `Err` value of Rust's `Result`
+template <typename T, typename E> class RustVariant final {
+public:
+    static RustVariant ok(T v)
+    {
+        RustVariant ret;
+        new (&ret.storage_) T(std::move(v));
+        ret.is_ok_ = true;
+        return ret;
+    }
+    static RustVariant err(E e)
+    {
+        RustVariant ret;
+        new (&ret.storage_) E(std::move(e));
+        ret.is_ok_ = false;
+        return ret;
+    }
+    RustVariant(const RustVariant &o) : is_ok_(o.is_ok_)
+    {
+        if (is_ok_) {
+            new (&storage_) T(o.ok_value());
+        } else {
+            new (&storage_) E(o.err_value());
+        }
+    }
+    RustVariant(RustVariant &&o) : is_ok_(o.is_ok_)
+    {
+        if (is_ok_) {
+            new (&storage_) T(std::move(o.ok_value()));
+        } else {
+            new (&storage_) E(std::move(o.err_value()));
+        }
+    }
+    RustVariant &operator=(const RustVariant &o)
+    {
+        if (this != &o) {
+            RustVariant tmp(o);
+            *this = std::move(tmp);
+        }
+        return *this;
+    }
+    RustVariant &operator=(RustVariant &&o)
+    {
+        if (this != &o) {
+            destroy();
+            is_ok_ = o.is_ok_;
+            if (is_ok_) {
+                new (&storage_) T(std::move(o.ok_value()));
+            } else {
+                new (&storage_) E(std::move(o.err_value()));
+            }
+        }
+        return *this;
+    }
+    ~RustVariant() noexcept { destroy(); }
+    /// 0 for `Ok`, 1 for `Err`, like std::variant::index
+    size_t index() const noexcept { return is_ok_ ? 0 : 1; }
+    bool is_ok() const noexcept { return is_ok_; }
+    T &ok_value() noexcept
+    {
+        assert(is_ok_);
+        return *reinterpret_cast<T *>(&storage_);
+    }
+    const T &ok_value() const noexcept
+    {
+        assert(is_ok_);
+        return *reinterpret_cast<const T *>(&storage_);
+    }
+    E &err_value() noexcept
+    {
+        assert(!is_ok_);
+        return *reinterpret_cast<E *>(&storage_);
+    }
+    const E &err_value() const noexcept
+    {
+        assert(!is_ok_);
+        return *reinterpret_cast<const E *>(&storage_);
+    }
+
+private:
+    RustVariant() noexcept {}
+    void destroy() noexcept
+    {
+        if (is_ok_) {
+            ok_value().~T();
+        } else {
+            err_value().~E();
+        }
+    }
+
+    typename std::aligned_storage<(sizeof(T) > sizeof(E) ? sizeof(T) : sizeof(E)),
+                                  (alignof(T) > alignof(E) ? alignof(T) : alignof(E))>::type
+        storage_;
+    bool is_ok_;
+};
+} // namespace RUST_SWIG_USER_NAMESPACE
@@ -0,0 +1,504 @@
+//! Conversion of Rust doc comments (markdown) to markup
+//! of foreign languages documentation tools
+
+use std::fmt::Write;
+
+use crate::{
+    types::{ForeignClassInfo, MethodVariant},
+    WRITE_TO_MEM_FAILED_MSG,
+};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum DocMarkup {
+    /// `\param`, `\return`, `\code`
+    Doxygen,
+    /// `@param`, `@return`, `{@link}`, `<pre>`
+    Javadoc,
+}
+
+/// Convert Rust doc comments to lines of foreign doc comment (without `///` or ` * `):
+/// code fences, intra-doc links, inline code, `# Arguments` and `# Returns` sections.
+/// `resolve_link` returns foreign name for target of intra-doc link,
+/// like `Foo` or `Foo::bar`, or `None` if there is no such exported item.
+pub(crate) fn convert_doc_comments(
+    doc_comments: &[String],
+    markup: DocMarkup,
+    resolve_link: &dyn Fn(&str) -> Option<String>,
+) -> Vec<String> {
+    let mut lines = Vec::with_capacity(doc_comments.len());
+    let mut params = Vec::<(String, String)>::new();
+    let mut returns = String::new();
+    let mut section = Section::Description;
+    let mut code_block: Option<bool> = None;
+
+    for comment in doc_comments {
+        // `/// text` is parsed as " text"
+        let line = comment.strip_prefix(' ').unwrap_or(comment).trim_end();
+        let trimmed = line.trim_start();
+
+        if let Some(lang) = trimmed.strip_prefix("```") {
+            match code_block.take() {
+                Some(_) => lines.push(
+                    match markup {
+                        DocMarkup::Doxygen => "\\endcode",
+                        DocMarkup::Javadoc => "</pre>",
+                    }
+                    .into(),
+                ),
+                None => {
+                    code_block = Some(is_rust_code_block(lang.trim()));
+                    lines.push(
+                        match markup {
+                            DocMarkup::Doxygen => "\\code",
+                            DocMarkup::Javadoc => "<pre>",
+                        }
+                        .into(),
+                    );
+                }
+            }
+            continue;
+        }
+        if let Some(rust_code) = code_block {
+            // hidden lines of Rust doc tests
+            if rust_code && (trimmed == "#" || trimmed.starts_with("# ")) {
+                continue;
+            }
+            lines.push(match markup {
+                DocMarkup::Doxygen => line.into(),
+                // `@` at line start is treated as block tag even inside `<pre>`
+                DocMarkup::Javadoc => escape_html(line).replace('@', "&#64;"),
+            });
+            continue;
+        }
+
+        if trimmed.starts_with('#') {
+            let title = trimmed.trim_start_matches('#').trim();
+            section = match title.to_lowercase().as_str() {
+                "arguments" | "parameters" => Section::Arguments,
+                "returns" | "return" => Section::Returns,
+                _ => {
+                    lines.push(match markup {
+                        DocMarkup::Doxygen => format!("\\par {}", title),
+                        DocMarkup::Javadoc => format!("<p><b>{}</b>", escape_html(title)),
+                    });
+                    Section::Description
+                }
+            };
+            continue;
+        }
+
+        match section {
+            Section::Description => lines.push(convert_inline(trimmed, markup, resolve_link)),
+            Section::Arguments => {
+                if trimmed.is_empty() {
+                    continue;
+                }
+                match parse_param(trimmed) {
+                    Some((name, desc)) => {
+                        params.push((name, convert_inline(desc, markup, resolve_link)))
+                    }
+                    None => match params.last_mut() {
+                        Some((_, desc)) => {
+                            desc.push(' ');
+                            desc.push_str(&convert_inline(trimmed, markup, resolve_link));
+                        }
+                        None => lines.push(convert_inline(trimmed, markup, resolve_link)),
+                    },
+                }
+            }
+            Section::Returns => {
+                if trimmed.is_empty() {
+                    continue;
+                }
+                if !returns.is_empty() {
+                    returns.push(' ');
+                }
+                returns.push_str(&convert_inline(trimmed, markup, resolve_link));
+            }
+        }
+    }
+    if code_block.is_some() {
+        lines.push(
+            match markup {
+                DocMarkup::Doxygen => "\\endcode",
+                DocMarkup::Javadoc => "</pre>",
+            }
+            .into(),
+        );
+    }
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+
+    // Javadoc requires block tags after main description
+    let tag_prefix = match markup {
+        DocMarkup::Doxygen => '\\',
+        DocMarkup::Javadoc => '@',
+    };
+    if !lines.is_empty() && (!params.is_empty() || !returns.is_empty()) {
+        lines.push(String::new());
+    }
+    for (name, desc) in params {
+        lines.push(format!("{}param {} {}", tag_prefix, name, desc));
+    }
+    if !returns.is_empty() {
+        lines.push(format!("{}return {}", tag_prefix, returns));
+    }
+    lines
+}
+
+/// Find exported class and name of its method (or constructor) for intra-doc link,
+/// like `Foo`, `Foo::bar` or `Foo::bar()`, `Foo` can be name of foreign class or its `self_type`
+pub(crate) fn find_link_target<'a>(
+    classes: &'a [ForeignClassInfo],
+    link: &str,
+) -> Option<(&'a ForeignClassInfo, Option<String>)> {
+    let link = link.trim_end_matches("()");
+    let (type_path, member) = match link.rfind("::") {
+        Some(pos) => (&link[..pos], Some(&link[pos + 2..])),
+        None => (link, None),
+    };
+    let type_name = type_path.rsplit("::").next().unwrap_or(type_path);
+    let find_class = |type_name: &str| {
+        classes.iter().find(|class| {
+            class.name == type_name
+                || class
+                    .self_desc
+                    .as_ref()
+                    .is_some_and(|desc| match desc.self_type {
+                        syn::Type::Path(ref path) => path
+                            .path
+                            .segments
+                            .last()
+                            .is_some_and(|seg| seg.ident == type_name),
+                        _ => false,
+                    })
+        })
+    };
+    match member {
+        None => find_class(type_name).map(|class| (class, None)),
+        Some(member) => {
+            if let Some(class) = find_class(type_name) {
+                let method = class.methods.iter().find(|m| {
+                    m.short_name() == member
+                        || m.rust_id
+                            .segments
+                            .last()
+                            .is_some_and(|seg| seg.ident == member)
+                })?;
+                let member_name = if method.variant == MethodVariant::Constructor {
+                    class.name.to_string()
+                } else {
+                    method.short_name()
+                };
+                Some((class, Some(member_name)))
+            } else {
+                // `Foo::Bar` may be path to class itself
+                find_class(member).map(|class| (class, None))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Description,
+    Arguments,
+    Returns,
+}
+
+fn is_rust_code_block(lang: &str) -> bool {
+    lang.is_empty()
+        || lang.split(',').map(str::trim).any(|attr| {
+            matches!(
+                attr,
+                "rust" | "no_run" | "ignore" | "should_panic" | "compile_fail" | "edition2018"
+            )
+        })
+}
+
+/// `* `name` - description` or `- name: description`
+fn parse_param(line: &str) -> Option<(String, &str)> {
+    let item = line
+        .strip_prefix("* ")
+        .or_else(|| line.strip_prefix("- "))?
+        .trim_start();
+    let (name, rest) = if let Some(item) = item.strip_prefix('`') {
+        let end = item.find('`')?;
+        (&item[..end], &item[end + 1..])
+    } else {
+        let end = item
+            .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+            .unwrap_or(item.len());
+        (&item[..end], &item[end..])
+    };
+    if name.is_empty() {
+        return None;
+    }
+    let desc = rest.trim_start();
+    let desc = desc
+        .strip_prefix('-')
+        .or_else(|| desc.strip_prefix(':'))
+        .or_else(|| desc.strip_prefix('–'))
+        .unwrap_or(desc)
+        .trim_start();
+    Some((name.into(), desc))
+}
+
+/// Inline code and links, for Javadoc text outside of `{@code}` is HTML escaped
+fn convert_inline(
+    line: &str,
+    markup: DocMarkup,
+    resolve_link: &dyn Fn(&str) -> Option<String>,
+) -> String {
+    let push_text = |out: &mut String, text: &str| match markup {
+        DocMarkup::Doxygen => out.push_str(text),
+        DocMarkup::Javadoc => out.push_str(&escape_html(text)),
+    };
+    let mut ret = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(pos) = rest.find(['[', '`']) {
+        push_text(&mut ret, &rest[..pos]);
+        rest = &rest[pos..];
+        if rest.starts_with('`') {
+            match rest[1..].find('`') {
+                Some(end) => {
+                    let code = &rest[1..end + 1];
+                    match markup {
+                        DocMarkup::Doxygen => write!(&mut ret, "`{}`", code),
+                        DocMarkup::Javadoc => {
+                            write!(&mut ret, "{{@code {}}}", escape_javadoc_code(code))
+                        }
+                    }
+                    .expect(WRITE_TO_MEM_FAILED_MSG);
+                    rest = &rest[end + 2..];
+                }
+                None => {
+                    push_text(&mut ret, rest);
+                    rest = "";
+                }
+            }
+            continue;
+        }
+        match parse_link(rest) {
+            Some((label, target, len)) => {
+                write_link(&mut ret, label, target, markup, resolve_link);
+                rest = &rest[len..];
+            }
+            None => {
+                ret.push('[');
+                rest = &rest[1..];
+            }
+        }
+    }
+    push_text(&mut ret, rest);
+    ret
+}
+
+/// `[label](target)`, `[`target`]` or `[target]`, returns label, target and length of link
+fn parse_link(s: &str) -> Option<(Option<&str>, &str, usize)> {
+    let end = s.find(']')?;
+    let text = &s[1..end];
+    let after = &s[end + 1..];
+    if after.starts_with('(') {
+        let target_end = after.find(')')?;
+        let target = &after[1..target_end];
+        Some((Some(text), target, end + 1 + target_end + 1))
+    } else {
+        let target = text.trim_matches('`');
+        if !is_item_path(target) {
+            return None;
+        }
+        Some((None, target, end + 1))
+    }
+}
+
+fn is_item_path(s: &str) -> bool {
+    let s = s.trim_end_matches("()");
+    !s.is_empty()
+        && s.split("::").all(|part| {
+            !part.is_empty()
+                && part.chars().all(|ch| ch.is_alphanumeric() || ch == '_')
+                && !part.starts_with(|ch: char| ch.is_ascii_digit())
+        })
+}
+
+fn write_link(
+    out: &mut String,
+    label: Option<&str>,
+    target: &str,
+    markup: DocMarkup,
+    resolve_link: &dyn Fn(&str) -> Option<String>,
+) {
+    let target = target.trim_matches('`');
+    let resolved = if is_item_path(target) {
+        resolve_link(target)
+    } else {
+        None
+    };
+    match (markup, resolved, label) {
+        (DocMarkup::Doxygen, Some(name), None) => out.push_str(&name),
+        (DocMarkup::Doxygen, Some(name), Some(label)) => {
+            write!(out, "\\ref {} \"{}\"", name, label.trim_matches('`'))
+                .expect(WRITE_TO_MEM_FAILED_MSG)
+        }
+        (DocMarkup::Javadoc, Some(name), None) => {
+            write!(out, "{{@link {}}}", name).expect(WRITE_TO_MEM_FAILED_MSG)
+        }
+        (DocMarkup::Javadoc, Some(name), Some(label)) => write!(
+            out,
+            "{{@link {} {}}}",
+            name,
+            escape_html(label.trim_matches('`'))
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG),
+        (DocMarkup::Doxygen, None, None) => {
+            write!(out, "`{}`", target).expect(WRITE_TO_MEM_FAILED_MSG)
+        }
+        (DocMarkup::Javadoc, None, None) => {
+            write!(out, "{{@code {}}}", target).expect(WRITE_TO_MEM_FAILED_MSG)
+        }
+        (_, None, Some(label)) if is_item_path(target) => {
+            out.push_str(&convert_inline(label, markup, &|_| None))
+        }
+        (DocMarkup::Doxygen, None, Some(label)) => {
+            write!(out, "[{}]({})", label, target).expect(WRITE_TO_MEM_FAILED_MSG)
+        }
+        (DocMarkup::Javadoc, None, Some(label)) => write!(
+            out,
+            "<a href=\"{}\">{}</a>",
+            escape_html(target),
+            escape_html(label)
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG),
+    }
+}
+
+/// Also escape `*/`, because of it ends Javadoc comment
+fn escape_html(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    let mut prev = None;
+    for ch in s.chars() {
+        match ch {
+            '<' => ret.push_str("&lt;"),
+            '>' => ret.push_str("&gt;"),
+            '&' => ret.push_str("&amp;"),
+            '/' if prev == Some('*') => ret.push_str("&#47;"),
+            _ => ret.push(ch),
+        }
+        prev = Some(ch);
+    }
+    ret
+}
+
+/// Text inside of `{@code}` is not HTML, but `*/` still ends Javadoc comment
+fn escape_javadoc_code(code: &str) -> String {
+    code.replace("*/", "*&#47;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(doc: &str, markup: DocMarkup) -> Vec<String> {
+        let doc: Vec<String> = doc.lines().map(|x| format!(" {}", x)).collect();
+        convert_doc_comments(&doc, markup, &|link| match link {
+            "Boo" => Some("Boo".into()),
+            "Boo::f" => Some(match markup {
+                DocMarkup::Doxygen => "Boo::f".into(),
+                DocMarkup::Javadoc => "Boo#f".into(),
+            }),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_convert_doc_comments() {
+        let doc = r#"Create [`Boo`], see [`Boo::f`] and [`Unknown`].
+Uses `x < 5`.
+
+# Arguments
+
+* `a` - first
+  argument
+* b: second
+
+# Returns
+
+New [Boo]
+
+# Examples
+```
+# let x = 1;
+let v: Vec<u8> = vec![];
+```"#;
+        assert_eq!(
+            vec![
+                "Create Boo, see Boo::f and `Unknown`.",
+                "Uses `x < 5`.",
+                "",
+                "\\par Examples",
+                "\\code",
+                "let v: Vec<u8> = vec![];",
+                "\\endcode",
+                "",
+                "\\param a first argument",
+                "\\param b second",
+                "\\return New Boo",
+            ],
+            convert(doc, DocMarkup::Doxygen)
+        );
+        assert_eq!(
+            vec![
+                "Create {@link Boo}, see {@link Boo#f} and {@code Unknown}.",
+                "Uses {@code x < 5}.",
+                "",
+                "<p><b>Examples</b>",
+                "<pre>",
+                "let v: Vec&lt;u8&gt; = vec![];",
+                "</pre>",
+                "",
+                "@param a first argument",
+                "@param b second",
+                "@return New {@link Boo}",
+            ],
+            convert(doc, DocMarkup::Javadoc)
+        );
+        assert_eq!(
+            vec!["See [docs](https://example.com) and \\ref Boo::f \"f\""],
+            convert(
+                "See [docs](https://example.com) and [f](Boo::f)",
+                DocMarkup::Doxygen
+            )
+        );
+        assert_eq!(
+            vec!["See <a href=\"https://example.com\">docs</a>, a[0]"],
+            convert("See [docs](https://example.com), a[0]", DocMarkup::Javadoc)
+        );
+        assert_eq!(
+            vec![
+                "Returns {@code Vec<u8>} if a &lt; b &amp;&amp; b &gt; c, see {@link Boo#f Boo&lt;T&gt;}",
+                "<p><b>Vec&lt;T&gt;</b>",
+                "<pre>",
+                "&#64;Override a &lt; b",
+                "</pre>",
+            ],
+            convert(
+                "Returns `Vec<u8>` if a < b && b > c, see [Boo<T>](Boo::f)\n# Vec<T>\n```text\n@Override a < b\n```",
+                DocMarkup::Javadoc
+            )
+        );
+        assert_eq!(
+            vec![
+                "Glob {@code src/*&#47;*.rs} or a/*&#47;b",
+                "<pre>",
+                "let x = 1; /* comment *&#47;",
+                "</pre>",
+            ],
+            convert(
+                "Glob `src/*/*.rs` or a/*/b\n```\nlet x = 1; /* comment */\n```",
+                DocMarkup::Javadoc
+            )
+        );
+    }
+}
@@ -24,6 +24,8 @@ pub(crate) fn extend_foreign_class(
     class: &ForeignClassInfo,
     cnt: &mut Vec<u8>,
     reserved_class_derives: &[&str],
+    reserved_method_attrs: &[&str],
+    reserved_arg_attrs: &[&str],
     class_ext_handlers: &ClassExtHandlers,
     method_ext_handlers: &MethodExtHandlers,
 ) -> Result<()> {
@@ -46,7 +48,34 @@ pub(crate) fn extend_foreign_class(
     }
 
     for method in &class.methods {
+        for arg in method
+            .fn_decl
+            .inputs
+            .iter()
+            .filter_map(|x| x.as_named_arg().ok())
+        {
+            if let Some(attr) = arg
+                .unknown_attrs
+                .iter()
+                .find(|attr| !reserved_arg_attrs.iter().any(|x| x == attr))
+            {
+                return Err(DiagnosticError::new(
+                    class.src_id,
+                    arg.span,
+                    format!(
+                        "class {}, method {}: argument {} has unknown attribute {}",
+                        class.name,
+                        method.short_name(),
+                        arg.name,
+                        attr
+                    ),
+                ));
+            }
+        }
         for attr in &method.unknown_attrs {
+            if reserved_method_attrs.iter().any(|x| x == attr) {
+                continue;
+            }
             if let Some(cb) = method_ext_handlers.get(attr) {
                 cb(
                     cnt,
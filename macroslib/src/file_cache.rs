@@ -1,7 +1,7 @@
 /// To prevent modification time changing
 use std::{
     collections::HashSet,
-    fs::File,
+    fs::{self, File},
     io,
     io::{Read, Write},
     mem,
@@ -50,6 +50,11 @@ impl FileWriteCache {
                 return Ok(());
             }
         }
+        if let Some(parent) = self.path.parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                fs::create_dir_all(parent)?;
+            }
+        }
         let mut f = File::create(&self.path)?;
         f.write_all(&self.cnt)?;
         Ok(())
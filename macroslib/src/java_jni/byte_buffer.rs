@@ -0,0 +1,228 @@
+use proc_macro2::Span;
+use quote::quote;
+use std::io::Write;
+
+use super::{
+    java_class_full_name, java_class_name_to_jni, kotlin_code, map_write_err, merge_rule,
+    object_cleanup, proguard, register_natives, rust_code, JavaContext, JavaForeignTypeInfo,
+    JniForeignMethodSignature,
+};
+use crate::{
+    error::{invalid_src_id_span, panic_on_syn_error, DiagnosticError, Result},
+    file_cache::FileWriteCache,
+    source_registry::SourceId,
+    typemap::ForeignTypeInfo,
+    types::MethodVariant,
+    WRITE_TO_MEM_FAILED_MSG,
+};
+
+const RUST_BYTE_BUFFER_CLASS: &str = "RustByteBuffer";
+
+/// Generate Java class that frees `Vec<u8>` returned from Rust
+/// as direct `java.nio.ByteBuffer`, after buffer becomes unreachable.
+/// Memory is owned by buffer itself, so it can not be freed while Java code uses it
+pub(in crate::java_jni) fn generate_rust_byte_buffer(ctx: &mut JavaContext) -> Result<()> {
+    generate_java_class(ctx).map_err(DiagnosticError::map_any_err_to_our_err)?;
+
+    let class_full_name = java_class_full_name(&ctx.cfg.package_name, RUST_BYTE_BUFFER_CLASS);
+    let helper_code = format!(
+        r#"
+#[allow(dead_code)]
+fn vec_u8_to_rust_byte_buffer(
+    env: *mut JNIEnv,
+    data: Vec<u8>,
+) -> internal_aliases::JRustByteBuffer {{
+    let mut data: Box<Vec<u8>> = Box::new(data);
+    let buffer: jobject = unsafe {{
+        (**env).NewDirectByteBuffer.unwrap()(
+            env,
+            data.as_mut_ptr() as *mut ::std::os::raw::c_void,
+            data.len() as jlong,
+        )
+    }};
+    if buffer.is_null() {{
+        // exception thrown by JVM, `data` is freed here
+        return ::std::ptr::null_mut();
+    }}
+    let class: jclass = swig_jni_find_class!(FOREIGN_CLASS_RUSTBYTEBUFFER, "{class_for_jni}");
+    assert!(!class.is_null());
+    let own_method: jmethodID = swig_jni_get_static_method_id!(
+        FOREIGN_CLASS_RUSTBYTEBUFFER_OWN,
+        FOREIGN_CLASS_RUSTBYTEBUFFER,
+        "own",
+        "(Ljava/nio/ByteBuffer;J)V"
+    );
+    assert!(!own_method.is_null());
+    let ptr = Box::into_raw(data) as jlong;
+    unsafe {{
+        (**env).CallStaticVoidMethod.unwrap()(env, class, own_method, buffer, ptr);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {{
+            drop(Box::from_raw(jlong_to_pointer::<Vec<u8>>(ptr)));
+            (**env).DeleteLocalRef.unwrap()(env, buffer);
+            return ::std::ptr::null_mut();
+        }}
+    }}
+    buffer
+}}
+"#,
+        class_for_jni = java_class_name_to_jni(&class_full_name),
+    );
+    ctx.rust_code
+        .push(syn::parse_str(&helper_code).unwrap_or_else(|err| {
+            panic_on_syn_error("java/jni internal RustByteBuffer", helper_code, err)
+        }));
+    proguard::keep_method(
+        ctx,
+        RUST_BYTE_BUFFER_CLASS,
+        "own",
+        "(Ljava/nio/ByteBuffer;J)V",
+    );
+
+    let conv_code = r#"
+foreign_typemap!(
+    ($p:r_type) Vec<u8> => internal_aliases::JRustByteBuffer {
+        $out = vec_u8_to_rust_byte_buffer(env, $p);
+    };
+    ($p:f_type, option = "DirectByteBuffer") => "java.nio.ByteBuffer";
+);
+"#;
+    ctx.conv_map
+        .merge(SourceId::none(), conv_code, ctx.pointer_target_width)?;
+    for rule in ctx.conv_map.take_not_merged_not_generic_rules() {
+        merge_rule(ctx, rule)?;
+    }
+
+    let dummy_rust_ty = ctx
+        .conv_map
+        .find_or_alloc_rust_type_no_src_id(&parse_type! { () });
+    let jlong_type = ctx.conv_map.ty_to_rust_type(&parse_type! { jlong });
+    let destructor_sign = JniForeignMethodSignature {
+        output: ForeignTypeInfo {
+            name: "".into(),
+            correspoding_rust_type: dummy_rust_ty,
+        }
+        .into(),
+        input: vec![JavaForeignTypeInfo {
+            base: ForeignTypeInfo {
+                name: "long".into(),
+                correspoding_rust_type: jlong_type,
+            },
+            java_converter: None,
+            annotation: None,
+            unsigned_check: None,
+        }],
+    };
+    let jni_destructor_name = rust_code::generate_jni_func_name(
+        ctx,
+        RUST_BYTE_BUFFER_CLASS,
+        invalid_src_id_span(),
+        "do_delete",
+        MethodVariant::StaticMethod,
+        &destructor_sign,
+        false,
+    )?;
+    register_natives::remember_native_method(
+        ctx,
+        RUST_BYTE_BUFFER_CLASS,
+        "do_delete",
+        MethodVariant::StaticMethod,
+        &destructor_sign,
+        &jni_destructor_name,
+    );
+    let jni_destructor_name = syn::Ident::new(&jni_destructor_name, Span::call_site());
+    let linkage = register_natives::jni_func_linkage_tokens(ctx.cfg);
+    ctx.rust_code.push(quote! {
+        #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
+        #linkage extern "C" fn #jni_destructor_name(env: *mut JNIEnv, _: jclass, this: jlong) {
+            let this: Box<Vec<u8>> = unsafe { Box::from_raw(jlong_to_pointer::<Vec<u8>>(this)) };
+            drop(this);
+        }
+    });
+    Ok(())
+}
+
+fn generate_java_class(ctx: &mut JavaContext) -> std::result::Result<(), String> {
+    if kotlin_code::use_kotlin(ctx.cfg) {
+        return generate_kotlin_class(ctx);
+    }
+    let path = ctx
+        .cfg
+        .output_dir
+        .join(format!("{}.java", RUST_BYTE_BUFFER_CLASS));
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+    writeln!(
+        file,
+        r#"// Automatically generated by flapigen
+package {package_name};
+
+/**
+ * Frees memory allocated by Rust for direct {{@link java.nio.ByteBuffer}},
+ * after buffer becomes unreachable. Keep reference to buffer
+ * while you use buffers created from it via {{@code slice}} or {{@code duplicate}}
+ */
+/*package*/ final class {class_name} {{
+    private {class_name}() {{}}
+
+    /*package*/ static void own(java.nio.ByteBuffer buffer, long ptr) {{
+        {cleaner}.register(buffer, new Deleter(ptr));
+    }}
+    private static final class Deleter implements Runnable {{
+        private final long ptr;
+        Deleter(long ptr) {{
+            this.ptr = ptr;
+        }}
+        @Override
+        public void run() {{
+            do_delete(ptr);
+        }}
+    }}
+    private static native void do_delete(long me);
+}}"#,
+        package_name = ctx.cfg.package_name,
+        class_name = RUST_BYTE_BUFFER_CLASS,
+        cleaner = object_cleanup::RUST_CLEANER_CLASS,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+fn generate_kotlin_class(ctx: &mut JavaContext) -> std::result::Result<(), String> {
+    let path = ctx
+        .cfg
+        .output_dir
+        .join(format!("{}.kt", RUST_BYTE_BUFFER_CLASS));
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+    writeln!(
+        file,
+        r#"// Automatically generated by flapigen
+package {package_name}
+
+/**
+ * Frees memory allocated by Rust for direct [java.nio.ByteBuffer],
+ * after buffer becomes unreachable. Keep reference to buffer
+ * while you use buffers created from it via `slice` or `duplicate`
+ */
+internal object {class_name} {{
+    @JvmStatic
+    fun own(buffer: java.nio.ByteBuffer, ptr: Long) {{
+        {cleaner}.register(buffer, Deleter(ptr))
+    }}
+
+    private class Deleter(private val ptr: Long) : Runnable {{
+        override fun run() {{
+            do_delete(ptr)
+        }}
+    }}
+
+    @JvmStatic
+    private external fun do_delete(me: Long)
+}}"#,
+        package_name = ctx.cfg.package_name,
+        class_name = RUST_BYTE_BUFFER_CLASS,
+        cleaner = object_cleanup::RUST_CLEANER_CLASS,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
@@ -8,9 +8,11 @@ use std::io::Write;
 use syn::{spanned::Spanned, Type};
 
 use super::{
-    calc_this_type_for_method, java_class_full_name, java_class_name_to_jni, java_code,
-    map_type::map_type, method_name, rust_code, JavaContext, JavaConverter, JavaForeignTypeInfo,
-    JniForeignMethodSignature, INTERNAL_PTR_MARKER, JAVA_RUST_SELF_NAME, REACHABILITY_FENCE_CLASS,
+    all_cfg_options, calc_this_type_for_method, cfg_options, java_class_full_name,
+    java_class_name_to_jni, java_code, kotlin_code, map_type::map_type, method_name,
+    need_null_check, null_check_in_rust, object_cleanup, proguard, register_natives, rust_code,
+    unsigned, JavaContext, JavaConverter, JavaForeignTypeInfo, JniForeignMethodSignature,
+    INTERNAL_PTR_MARKER, JAVA_RUST_SELF_NAME, REACHABILITY_FENCE_CLASS,
 };
 use crate::{
     error::{panic_on_syn_error, DiagnosticError, Result, SourceIdSpan},
@@ -22,14 +24,15 @@ use crate::{
         ty::RustType,
         utils::{
             convert_to_heap_pointer, create_suitable_types_for_constructor_and_self,
-            foreign_from_rust_convert_method_output, foreign_to_rust_convert_method_inputs,
-            unpack_from_heap_pointer,
+            foreign_from_rust_convert_method_output, unpack_from_heap_pointer,
         },
         ForeignTypeInfo, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE, TO_VAR_TYPE_TEMPLATE,
     },
-    types::{ForeignClassInfo, ForeignMethod, MethodAccess, MethodVariant, SelfTypeVariant},
-    JavaConfig, JavaReachabilityFence, CLONE_TRAIT, COPY_TRAIT, SMART_PTR_COPY_TRAIT,
-    WRITE_TO_MEM_FAILED_MSG,
+    types::{
+        ForeignClassInfo, ForeignMethod, MethodAccess, MethodVariant, NamedArg, SelfTypeVariant,
+    },
+    JavaConfig, JavaReachabilityFence, CLONE_TRAIT, COPY_TRAIT, KNOWN_JAVA_ARG_ATTRS,
+    KNOWN_METHOD_ATTRS, PRIMITIVE_ARRAY_CRITICAL, SMART_PTR_COPY_TRAIT, WRITE_TO_MEM_FAILED_MSG,
 };
 
 pub(in crate::java_jni) fn generate(ctx: &mut JavaContext, class: &ForeignClassInfo) -> Result<()> {
@@ -39,12 +42,16 @@ pub(in crate::java_jni) fn generate(ctx: &mut JavaContext, class: &ForeignClassI
     );
 
     let f_methods_sign = find_suitable_foreign_types_for_methods(ctx, class)?;
-    generate_java_code(
-        ctx,
-        class,
-        &f_methods_sign,
-        ctx.cfg.null_annotation_package.as_deref(),
-    )?;
+    if kotlin_code::use_kotlin(ctx.cfg) {
+        generate_kotlin_code(ctx, class, &f_methods_sign)?;
+    } else {
+        generate_java_code(
+            ctx,
+            class,
+            &f_methods_sign,
+            ctx.cfg.null_annotation_package.as_deref(),
+        )?;
+    }
     debug!("generate: java code done");
     generate_rust_code(ctx, class, &f_methods_sign)?;
 
@@ -70,6 +77,17 @@ pub(in crate::java_jni) fn generate(ctx: &mut JavaContext, class: &ForeignClassI
     Ok(())
 }
 
+/// Call of Rust function, arguments accessed via `GetPrimitiveArrayCritical`
+/// are released right after call
+fn call_with_critical_arrays(mc: &MethodContext, critical_code: &str) -> String {
+    let call = mc.method.generate_code_to_call_rust_func();
+    if critical_code.is_empty() {
+        call
+    } else {
+        format!("{{{}\n    {}\n    }}", critical_code, call)
+    }
+}
+
 struct MethodContext<'a> {
     class: &'a ForeignClassInfo,
     method: &'a ForeignMethod,
@@ -78,6 +96,7 @@ struct MethodContext<'a> {
     decl_func_args: &'a str,
     real_output_typename: &'a str,
     ret_name: &'a str,
+    native_kind: register_natives::NativeKind,
 }
 
 fn generate_java_code(
@@ -91,17 +110,27 @@ fn generate_java_code(
 
     let imports = java_code::get_null_annotation_imports(null_annotation_package, methods_sign);
 
-    let class_doc_comments = java_code::doc_comments_to_java_comments(&class.doc_comments, true);
+    let class_doc_comments =
+        java_code::doc_comments_to_java_comments(ctx.conv_map, &class.doc_comments, true);
+    let has_constructor = class
+        .methods
+        .iter()
+        .any(|m| m.variant == MethodVariant::Constructor);
     writeln!(
         file,
         r#"// Automatically generated by flapigen
 package {package_name};
 {imports}
 {doc_comments}
-public final class {class_name} {{"#,
+public final class {class_name}{implements} {{"#,
         package_name = ctx.cfg.package_name,
         imports = imports,
         class_name = class.name,
+        implements = if has_constructor {
+            object_cleanup::class_implements(ctx.cfg)
+        } else {
+            ""
+        },
         doc_comments = class_doc_comments,
     )
     .expect(WRITE_TO_MEM_FAILED_MSG);
@@ -113,23 +142,14 @@ public final class {class_name} {{"#,
         write!(
             &mut file,
             "{doc_comments}",
-            doc_comments = java_code::doc_comments_to_java_comments(&method.doc_comments, false)
+            doc_comments =
+                java_code::doc_comments_to_java_comments(ctx.conv_map, &method.doc_comments, false)
         )
         .expect(WRITE_TO_MEM_FAILED_MSG);
 
-        let may_return_error = match method.fn_decl.output {
-            syn::ReturnType::Default => false,
-            syn::ReturnType::Type(_, ref ptype) => {
-                let ret_rust_ty = ctx.conv_map.find_or_alloc_rust_type(ptype, class.src_id);
-                if_result_return_ok_err_types(&ret_rust_ty).is_some()
-            }
-        };
-
-        let exception_spec = if may_return_error {
-            " throws Exception"
-        } else {
-            ""
-        };
+        let exception_spec = method_exception(ctx, class, method)
+            .map(|exception| format!(" throws {}", exception))
+            .unwrap_or_default();
 
         let method_access = match method.access {
             MethodAccess::Private => "private",
@@ -158,6 +178,12 @@ public final class {class_name} {{"#,
         known_names.insert(ret_name.clone());
         let conv_ret = new_unique_name(&known_names, "convRet");
         known_names.insert(conv_ret.clone());
+        let ret_check_code = match (method.variant, f_method.output.unsigned_check.as_ref()) {
+            (MethodVariant::Constructor, _) | (_, None) => String::new(),
+            (_, Some(check)) => {
+                unsigned::ret_check_code(ctx.cfg, check, &ret_name, &mut known_names)
+            }
+        };
 
         let (convert_code, args_for_call_internal, reachability_fence_code) =
             convert_code_for_method(
@@ -167,8 +193,11 @@ public final class {class_name} {{"#,
                 method.arg_names_without_self(),
                 known_names,
                 conv_code_flags,
+                !null_check_in_rust(method, f_method),
             )?;
         let func_name = method_name(method, f_method);
+        let native_annotation =
+            register_natives::native_kind(ctx.cfg, class, method, f_method)?.annotation();
 
         let external_args_except_self = java_code::args_with_java_types(
             f_method,
@@ -180,7 +209,7 @@ public final class {class_name} {{"#,
         let (ret_type, intermidiate_ret_type, ret_conv_code) = match method.variant {
             MethodVariant::StaticMethod => {
                 if let Some(conv) = f_method.output.java_converter.as_ref() {
-                    calc_output_conv(&f_method.output, conv, &ret_name, &conv_ret)
+                    calc_output_conv(ctx.cfg, &f_method.output, conv, &ret_name, &conv_ret)
                 } else {
                     let ret_type = f_method.output.base.name.display();
                     (ret_type, ret_type, String::new())
@@ -188,7 +217,7 @@ public final class {class_name} {{"#,
             }
             MethodVariant::Method(_) => {
                 if let Some(conv) = f_method.output.java_converter.as_ref() {
-                    calc_output_conv(&f_method.output, conv, &ret_name, &conv_ret)
+                    calc_output_conv(ctx.cfg, &f_method.output, conv, &ret_name, &conv_ret)
                 } else {
                     let ret_type = f_method.output.base.name.display();
                     (ret_type, ret_type, String::new())
@@ -202,7 +231,8 @@ public final class {class_name} {{"#,
             &intermidiate_ret_type
         };
 
-        let need_conversation = !convert_code.is_empty() || !ret_conv_code.is_empty();
+        let need_conversation =
+            !convert_code.is_empty() || !ret_conv_code.is_empty() || !ret_check_code.is_empty();
 
         match method.variant {
             MethodVariant::StaticMethod => {
@@ -214,7 +244,8 @@ public final class {class_name} {{"#,
                 write!(
                     file,
                     r#"
-    {method_access} static {native}{ret_type} {func_name}({args_with_types}){exception_spec}{end}"#,
+    {annotation}{method_access} static {native}{ret_type} {func_name}({args_with_types}){exception_spec}{end}"#,
+                    annotation = if need_conversation { "" } else { native_annotation },
                     method_access = method_access,
                     ret_type = ret_type,
                     func_name = method.short_name(),
@@ -237,8 +268,9 @@ public final class {class_name} {{"#,
                     if ret_type != "void" {
                         writeln!(
                             file,
-                            r#"        {intermidiate_ret_type} {ret_name} = {func_name}({args});{ret_conv_code}"#,
+                            r#"        {intermidiate_ret_type} {ret_name} = {func_name}({args});{ret_conv_code}{ret_check_code}"#,
                             ret_conv_code = ret_conv_code,
+                            ret_check_code = ret_check_code,
                             ret_name = ret_name,
                             intermidiate_ret_type =
                                 java_code::filter_null_annotation(intermidiate_ret_type_code)
@@ -277,7 +309,8 @@ public final class {class_name} {{"#,
                     writeln!(
                         file,
                         r#"
-    private static native {intermidiate_ret_type} {func_name}({args_with_types}){exception_spec};"#,
+    {native_annotation}private static native {intermidiate_ret_type} {func_name}({args_with_types}){exception_spec};"#,
+                        native_annotation = native_annotation,
                         func_name = func_name,
                         intermidiate_ret_type = intermidiate_ret_type,
                         exception_spec = exception_spec,
@@ -304,6 +337,11 @@ public final class {class_name} {{"#,
                     single_args_with_types = external_args_except_self,
                 )
                 .expect(WRITE_TO_MEM_FAILED_MSG);
+                file.write_all(
+                    object_cleanup::deleted_object_check(ctx.cfg, &class.name.to_string())
+                        .as_bytes(),
+                )
+                .expect(WRITE_TO_MEM_FAILED_MSG);
                 if !convert_code.is_empty() {
                     if convert_code.as_bytes()[0] != b'\n' {
                         file.write_all(b"\n").expect(WRITE_TO_MEM_FAILED_MSG);
@@ -315,9 +353,10 @@ public final class {class_name} {{"#,
                     writeln!(
                         file,
                         r#"
-        {intermidiate_ret_type} {ret_name} = {func_name}({rust_self_name}{args});{ret_conv_code}"#,
+        {intermidiate_ret_type} {ret_name} = {func_name}({rust_self_name}{args});{ret_conv_code}{ret_check_code}"#,
                         rust_self_name = JAVA_RUST_SELF_NAME,
                         ret_conv_code = ret_conv_code,
+                        ret_check_code = ret_check_code,
                         ret_name = ret_name,
                         intermidiate_ret_type =
                             java_code::filter_null_annotation(intermidiate_ret_type_code).trim(),
@@ -360,7 +399,8 @@ public final class {class_name} {{"#,
                 writeln!(
                     file,
                     r#"
-    private static native {intermidiate_ret_type} {func_name}(long self{args_with_types}){exception_spec};"#,
+    {native_annotation}private static native {intermidiate_ret_type} {func_name}(long self{args_with_types}){exception_spec};"#,
+                    native_annotation = native_annotation,
                     intermidiate_ret_type = intermidiate_ret_type,
                     exception_spec = exception_spec,
                     func_name = func_name,
@@ -406,9 +446,10 @@ public final class {class_name} {{"#,
                     }
                     writeln!(
                         file,
-                        r#"        {rust_self_name} = init({args});{reachability_fence_code}
+                        r#"        {rust_self_name} = init({args});{register_cleanup}{reachability_fence_code}
     }}
-    private static native long {func_name}({args_with_types}){exception_spec};"#,
+    {native_annotation}private static native long {func_name}({args_with_types}){exception_spec};"#,
+                        native_annotation = native_annotation,
                         rust_self_name = JAVA_RUST_SELF_NAME,
                         exception_spec = exception_spec,
                         func_name = func_name,
@@ -419,6 +460,7 @@ public final class {class_name} {{"#,
                             null_annotation_package.is_some()
                         ),
                         args = args_for_call_internal,
+                        register_cleanup = object_cleanup::register_cleanup_call(ctx.cfg),
                         reachability_fence_code = reachability_fence_code,
                     )
                     .expect(WRITE_TO_MEM_FAILED_MSG);
@@ -441,31 +483,17 @@ May be you need to use `private constructor = empty;` syntax?",
     if have_constructor {
         writeln!(
             file,
-            r#"
-    public synchronized void delete() {{
-        if ({rust_self_name} != 0) {{
-            do_delete({rust_self_name});
-            {rust_self_name} = 0;
-       }}
-    }}
-    @Override
-    protected void finalize() throws Throwable {{
-        try {{
-            delete();
-        }}
-        finally {{
-             super.finalize();
-        }}
-    }}
-    private static native void do_delete(long me);
+            r#"{delete_methods}
     /*package*/ {class_name}({internal_ptr_marker} marker, long ptr) {{
         assert marker == {internal_ptr_marker}.RAW_PTR;
-        this.{rust_self_name} = ptr;
+        this.{rust_self_name} = ptr;{register_cleanup}
     }}
     /*package*/ long {rust_self_name};"#,
+            delete_methods = object_cleanup::generate_delete_methods(ctx.cfg, ""),
             rust_self_name = JAVA_RUST_SELF_NAME,
             class_name = class.name,
             internal_ptr_marker = INTERNAL_PTR_MARKER,
+            register_cleanup = object_cleanup::register_cleanup_call(ctx.cfg),
         )
         .expect(WRITE_TO_MEM_FAILED_MSG);
     }
@@ -482,8 +510,387 @@ May be you need to use `private constructor = empty;` syntax?",
         .expect(WRITE_TO_MEM_FAILED_MSG);
     }
 
-    file.write_all(class.foreign_code.as_bytes())
+    let foreign_code = class.foreign_code_for(&all_cfg_options(), &cfg_options(ctx.cfg))?;
+    file.write_all(foreign_code.as_bytes())
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    write!(file, "}}").expect(WRITE_TO_MEM_FAILED_MSG);
+
+    let mut cnt = file.take_content();
+    extend_foreign_class(
+        class,
+        &mut cnt,
+        &[CLONE_TRAIT, COPY_TRAIT, SMART_PTR_COPY_TRAIT],
+        &KNOWN_METHOD_ATTRS,
+        &KNOWN_JAVA_ARG_ATTRS,
+        ctx.class_ext_handlers,
+        ctx.method_ext_handlers,
+    )?;
+    file.replace_content(cnt);
+
+    file.update_file_if_necessary()
+        .map_err(DiagnosticError::map_any_err_to_our_err)?;
+    Ok(())
+}
+
+fn generate_kotlin_code(
+    ctx: &mut JavaContext,
+    class: &ForeignClassInfo,
+    methods_sign: &[JniForeignMethodSignature],
+) -> Result<()> {
+    let path = ctx.cfg.output_dir.join(format!("{}.kt", class.name));
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+
+    let have_constructor = class
+        .methods
+        .iter()
+        .any(|m| m.variant == MethodVariant::Constructor);
+    let have_methods = class
+        .methods
+        .iter()
+        .any(|m| matches!(m.variant, MethodVariant::Method(_)));
+    if have_methods && !have_constructor {
+        return Err(DiagnosticError::new(
+            class.src_id,
+            class.span(),
+            format!(
+                "package {}, class {}: has methods, but no constructor\n
+May be you need to use `private constructor = empty;` syntax?",
+                ctx.cfg.package_name, class.name
+            ),
+        ));
+    }
+
+    let class_doc_comments = kotlin_code::doc_comments_to_kdoc(&class.doc_comments, true);
+    writeln!(
+        file,
+        r#"// Automatically generated by flapigen
+package {package_name}
+
+{doc_comments}
+class {class_name}{header_end} {{"#,
+        package_name = ctx.cfg.package_name,
+        class_name = class.name,
+        //utility class, so add private constructor
+        //to prevent object creation
+        header_end = if have_constructor {
+            " : java.io.Closeable"
+        } else {
+            " private constructor()"
+        },
+        doc_comments = class_doc_comments,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+
+    let mut companion = String::new();
+    let register_cleanup = object_cleanup::register_cleanup_call(ctx.cfg);
+
+    for (method, f_method) in class.methods.iter().zip(methods_sign) {
+        let mut doc_comments = kotlin_code::doc_comments_to_kdoc(&method.doc_comments, false);
+        if !doc_comments.is_empty() {
+            doc_comments.insert(0, '\n');
+        }
+        let throws = method_exception(ctx, class, method)
+            .map(|exception| format!("\n    @Throws({}::class)", exception))
+            .unwrap_or_default();
+        let method_access = match method.access {
+            MethodAccess::Private => "private ",
+            MethodAccess::Public => "",
+            MethodAccess::Protected => "protected ",
+        };
+        let conv_code_flags = match method.variant {
+            MethodVariant::StaticMethod => java_code::ArgsFormatFlags::INTERNAL,
+            MethodVariant::Method(_) => {
+                java_code::ArgsFormatFlags::COMMA_BEFORE | java_code::ArgsFormatFlags::INTERNAL
+            }
+            MethodVariant::Constructor => java_code::ArgsFormatFlags::INTERNAL,
+        };
+        let arg_names: Vec<String> = method
+            .arg_names_without_self()
+            .map(|x| kotlin_code::escape_name(x).into_owned())
+            .collect();
+        let mut known_names: FxHashSet<SmolStr> =
+            method.arg_names_without_self().map(|x| x.into()).collect();
+        if let MethodVariant::Method(_) = method.variant {
+            if known_names.contains(JAVA_RUST_SELF_NAME) {
+                return Err(DiagnosticError::new(class.src_id,
+                                                method.rust_id.span(),
+                                                format!("In method {} there is argument with name {}, this name reserved for generated code",
+                                                        method.short_name(), JAVA_RUST_SELF_NAME)));
+            }
+            known_names.insert(JAVA_RUST_SELF_NAME.into());
+        }
+        let ret_name = new_unique_name(&known_names, "ret");
+        known_names.insert(ret_name.clone());
+        let conv_ret = new_unique_name(&known_names, "convRet");
+        known_names.insert(conv_ret.clone());
+        let ret_check_code = match (method.variant, f_method.output.unsigned_check.as_ref()) {
+            (MethodVariant::Constructor, _) | (_, None) => String::new(),
+            (_, Some(check)) => {
+                unsigned::ret_check_code(ctx.cfg, check, &ret_name, &mut known_names)
+            }
+        };
+
+        let (convert_code, args_for_call_internal, reachability_fence_code) =
+            convert_code_for_method(
+                (class.src_id, method.rust_id.span()),
+                ctx.cfg,
+                f_method,
+                arg_names.iter().map(String::as_str),
+                known_names,
+                conv_code_flags,
+                !null_check_in_rust(method, f_method),
+            )?;
+        let func_name = method_name(method, f_method);
+        let native_annotation =
+            register_natives::native_kind(ctx.cfg, class, method, f_method)?.annotation();
+        let external_args = kotlin_code::args_with_kotlin_types(
+            f_method,
+            arg_names.iter().map(String::as_str),
+            java_code::ArgsFormatFlags::EXTERNAL,
+        );
+        let mut internal_args_flags = java_code::ArgsFormatFlags::INTERNAL;
+        if let MethodVariant::Method(_) = method.variant {
+            internal_args_flags |= java_code::ArgsFormatFlags::USE_COMMA_IF_NEED;
+        }
+        let internal_args = kotlin_code::args_with_kotlin_types(
+            f_method,
+            arg_names.iter().map(String::as_str),
+            internal_args_flags,
+        );
+
+        let (ret_type, intermidiate_ret_type, ret_conv_code) = match method.variant {
+            MethodVariant::StaticMethod | MethodVariant::Method(_) => {
+                let output = &f_method.output;
+                if let Some(conv) = output.java_converter.as_ref() {
+                    let (_, _, ret_conv_code) =
+                        calc_output_conv(ctx.cfg, output, conv, &ret_name, &conv_ret);
+                    (
+                        kotlin_code::kotlin_type(output.base.name.display(), output.annotation),
+                        kotlin_code::kotlin_type(
+                            conv.java_transition_type.display(),
+                            conv.annotation,
+                        ),
+                        ret_conv_code,
+                    )
+                } else {
+                    let ret_type =
+                        kotlin_code::kotlin_type(output.base.name.display(), output.annotation);
+                    (ret_type.clone(), ret_type, String::new())
+                }
+            }
+            MethodVariant::Constructor => ("Long".into(), "Long".into(), String::new()),
+        };
+
+        let mut call_code = String::new();
+        if let MethodVariant::Method(_) = method.variant {
+            call_code.push_str(&object_cleanup::deleted_object_check(
+                ctx.cfg,
+                &class.name.to_string(),
+            ));
+        }
+        if !convert_code.is_empty() {
+            let code = convert_code.trim_matches('\n');
+            call_code.push_str(code);
+            call_code.push('\n');
+        }
+        let self_arg = match method.variant {
+            MethodVariant::Method(_) => JAVA_RUST_SELF_NAME,
+            MethodVariant::StaticMethod | MethodVariant::Constructor => "",
+        };
+        if ret_type != "Unit" {
+            call_code.push_str(&format!(
+                "        val {ret_name}: {intermidiate_ret_type} = {func_name}({self_arg}{args}){ret_conv_code}\n",
+                ret_name = ret_name,
+                intermidiate_ret_type = intermidiate_ret_type,
+                func_name = func_name,
+                self_arg = self_arg,
+                args = args_for_call_internal,
+                ret_conv_code = ret_conv_code,
+            ));
+            if !ret_check_code.is_empty() {
+                call_code.push_str(ret_check_code.trim_start_matches('\n'));
+                call_code.push('\n');
+            }
+        } else {
+            call_code.push_str(&format!(
+                "        {func_name}({self_arg}{args})\n",
+                func_name = func_name,
+                self_arg = self_arg,
+                args = args_for_call_internal,
+            ));
+        }
+        if !reachability_fence_code.is_empty() {
+            let code = reachability_fence_code
+                .strip_prefix('\n')
+                .unwrap_or(&reachability_fence_code);
+            call_code.push_str(code);
+            call_code.push('\n');
+        }
+        if ret_type != "Unit" {
+            call_code.push_str(&format!(
+                "        return {}\n",
+                if ret_conv_code.is_empty() {
+                    &ret_name
+                } else {
+                    &conv_ret
+                },
+            ));
+        }
+
+        match method.variant {
+            MethodVariant::StaticMethod => {
+                let need_conversation = !convert_code.is_empty()
+                    || !ret_conv_code.is_empty()
+                    || !ret_check_code.is_empty();
+                let code = if !need_conversation {
+                    format!(
+                        r#"{doc_comments}{throws}
+    @JvmStatic
+    {native_annotation}{method_access}external fun {method_name}({args}){ret_type}
+"#,
+                        native_annotation = native_annotation,
+                        doc_comments = doc_comments,
+                        throws = throws,
+                        method_access = method_access,
+                        method_name = kotlin_code::escape_name(&method.short_name()),
+                        args = external_args,
+                        ret_type = kotlin_code::ret_type_decl(&ret_type),
+                    )
+                } else {
+                    format!(
+                        r#"{doc_comments}{throws}
+    @JvmStatic
+    {method_access}fun {method_name}({args}){ret_type} {{
+{call_code}    }}
+    @JvmStatic
+    {native_annotation}private external fun {func_name}({internal_args}){intermidiate_ret_type}
+"#,
+                        native_annotation = native_annotation,
+                        doc_comments = doc_comments,
+                        throws = throws,
+                        method_access = method_access,
+                        method_name = kotlin_code::escape_name(&method.short_name()),
+                        args = external_args,
+                        ret_type = kotlin_code::ret_type_decl(&ret_type),
+                        call_code = call_code,
+                        func_name = func_name,
+                        internal_args = internal_args,
+                        intermidiate_ret_type = kotlin_code::ret_type_decl(&intermidiate_ret_type),
+                    )
+                };
+                companion.push_str(&kotlin_code::indent_for_companion(&code));
+            }
+            MethodVariant::Method(_) => {
+                write!(
+                    file,
+                    r#"{doc_comments}{throws}
+    {method_access}fun {method_name}({args}){ret_type} {{
+{call_code}    }}
+"#,
+                    doc_comments = doc_comments,
+                    throws = throws,
+                    method_access = method_access,
+                    method_name = kotlin_code::escape_name(&method.short_name()),
+                    args = external_args,
+                    ret_type = kotlin_code::ret_type_decl(&ret_type),
+                    call_code = call_code,
+                )
+                .expect(WRITE_TO_MEM_FAILED_MSG);
+                companion.push_str(&kotlin_code::indent_for_companion(&format!(
+                    r#"
+    @JvmStatic
+    {native_annotation}private external fun {func_name}(self: Long{internal_args}){intermidiate_ret_type}
+"#,
+                    native_annotation = native_annotation,
+                    func_name = func_name,
+                    internal_args = internal_args,
+                    intermidiate_ret_type = kotlin_code::ret_type_decl(&intermidiate_ret_type),
+                )));
+            }
+            MethodVariant::Constructor => {
+                if method.is_dummy_constructor() {
+                    write!(
+                        file,
+                        r#"{doc_comments}
+    {method_access}constructor()
+"#,
+                        doc_comments = doc_comments,
+                        method_access = method_access,
+                    )
+                    .expect(WRITE_TO_MEM_FAILED_MSG);
+                } else {
+                    let mut code = String::new();
+                    if !convert_code.is_empty() {
+                        let conv = convert_code.trim_matches('\n');
+                        code.push_str(conv);
+                        code.push('\n');
+                    }
+                    write!(
+                        file,
+                        r#"{doc_comments}{throws}
+    {method_access}constructor({args}) {{
+{code}        {rust_self_name} = {func_name}({call_args}){register_cleanup}{reachability_fence_code}
+    }}
+"#,
+                        doc_comments = doc_comments,
+                        throws = throws,
+                        method_access = method_access,
+                        args = external_args,
+                        code = code,
+                        rust_self_name = JAVA_RUST_SELF_NAME,
+                        func_name = func_name,
+                        call_args = args_for_call_internal,
+                        register_cleanup = register_cleanup,
+                        reachability_fence_code = reachability_fence_code,
+                    )
+                    .expect(WRITE_TO_MEM_FAILED_MSG);
+                    companion.push_str(&kotlin_code::indent_for_companion(&format!(
+                        r#"
+    @JvmStatic
+    {native_annotation}private external fun {func_name}({internal_args}): Long
+"#,
+                        native_annotation = native_annotation,
+                        func_name = func_name,
+                        internal_args = internal_args,
+                    )));
+                }
+            }
+        }
+    }
+
+    if have_constructor {
+        let (delete_methods, delete_companion) =
+            object_cleanup::generate_kotlin_delete_methods(ctx.cfg, "");
+        write!(
+            file,
+            r#"{delete_methods}
+    internal constructor(marker: {internal_ptr_marker}, ptr: Long) {{
+        assert(marker == {internal_ptr_marker}.RAW_PTR)
+        {rust_self_name} = ptr{register_cleanup}
+    }}
+    @JvmField
+    internal var {rust_self_name}: Long = 0L
+"#,
+            delete_methods = delete_methods,
+            rust_self_name = JAVA_RUST_SELF_NAME,
+            internal_ptr_marker = INTERNAL_PTR_MARKER,
+            register_cleanup = register_cleanup,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        companion.push_str(&kotlin_code::indent_for_companion(&delete_companion));
+    }
+
+    let foreign_code = class.foreign_code_for(&all_cfg_options(), &cfg_options(ctx.cfg))?;
+    file.write_all(foreign_code.as_bytes())
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    if !companion.is_empty() {
+        write!(
+            file,
+            "\n    companion object {{\n{companion}    }}\n",
+            companion = companion.trim_start_matches('\n')
+        )
         .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
     write!(file, "}}").expect(WRITE_TO_MEM_FAILED_MSG);
 
     let mut cnt = file.take_content();
@@ -491,6 +898,8 @@ May be you need to use `private constructor = empty;` syntax?",
         class,
         &mut cnt,
         &[CLONE_TRAIT, COPY_TRAIT, SMART_PTR_COPY_TRAIT],
+        &KNOWN_METHOD_ATTRS,
+        &KNOWN_JAVA_ARG_ATTRS,
         ctx.class_ext_handlers,
         ctx.method_ext_handlers,
     )?;
@@ -501,6 +910,28 @@ May be you need to use `private constructor = empty;` syntax?",
     Ok(())
 }
 
+/// Name of Java exception class, if method returns `Result`
+fn method_exception<'a>(
+    ctx: &'a mut JavaContext,
+    class: &ForeignClassInfo,
+    method: &ForeignMethod,
+) -> Option<&'a str> {
+    match method.fn_decl.output {
+        syn::ReturnType::Default => None,
+        syn::ReturnType::Type(_, ref ptype) => {
+            let ret_rust_ty = ctx.conv_map.find_or_alloc_rust_type(ptype, class.src_id);
+            let (_, err_ty) = if_result_return_ok_err_types(&ret_rust_ty)?;
+            let err_rust_ty = ctx.conv_map.find_or_alloc_rust_type(&err_ty, class.src_id);
+            Some(
+                ctx.exceptions
+                    .get(&err_rust_ty.normalized_name)
+                    .map(String::as_str)
+                    .unwrap_or("Exception"),
+            )
+        }
+    }
+}
+
 fn generate_rust_code(
     ctx: &mut JavaContext,
     class: &ForeignClassInfo,
@@ -552,6 +983,40 @@ fn generate_rust_code(
                 ),
                 Span::call_site(),
             );
+            let has_constructor = class
+                .methods
+                .iter()
+                .any(|m| m.variant == MethodVariant::Constructor);
+            let init_object_code = if has_constructor && object_cleanup::use_cleaner(ctx.cfg) {
+                let global_var_with_register_cleanup = Ident::new(
+                    &format!(
+                        "FOREIGN_CLASS_{}_{}_METHOD",
+                        class.name.to_string().to_uppercase(),
+                        object_cleanup::REGISTER_CLEANUP_METHOD.to_uppercase(),
+                    ),
+                    Span::call_site(),
+                );
+                let register_cleanup = object_cleanup::REGISTER_CLEANUP_METHOD;
+                quote! {
+                    fn jni_init_object(env: *mut JNIEnv, obj: jobject) {
+                        let method_id: jmethodID = swig_jni_get_method_id!(
+                            #global_var_with_register_cleanup,
+                            #global_var_with_jclass,
+                            #register_cleanup,
+                            "()V"
+                        );
+                        assert!(!method_id.is_null());
+                        unsafe {
+                            (**env).CallVoidMethod.unwrap()(env, obj, method_id);
+                            if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                                panic!("Can not register object in cleaner: catch exception");
+                            }
+                        }
+                    }
+                }
+            } else {
+                TokenStream::new()
+            };
             let fclass_impl_code = quote! {
                 impl<#(#lifetimes),*> SwigForeignClass for #class_name {
                     type PointedType = #this_type_for_method_ty_as_is;
@@ -581,9 +1046,21 @@ fn generate_rust_code(
                         };
                         ::std::ptr::NonNull::<Self::PointedType>::new(x).unwrap()
                     }
+                    #init_object_code
                 }
             };
             ctx.rust_code.push(fclass_impl_code);
+            let class_name = class.name.to_string();
+            proguard::keep_class(ctx, &class_name);
+            proguard::keep_field(ctx, &class_name, JAVA_RUST_SELF_NAME, "J", false);
+            if has_constructor && object_cleanup::use_cleaner(ctx.cfg) {
+                proguard::keep_method(
+                    ctx,
+                    &class_name,
+                    object_cleanup::REGISTER_CLEANUP_METHOD,
+                    "()V",
+                );
+            }
             (this_type_for_method, code_box_this)
         } else {
             (dummy_rust_ty.clone(), TokenStream::new())
@@ -616,6 +1093,14 @@ May be you need to use `private constructor = empty;` syntax?",
             method_overloading,
         )?;
         trace!("generate_rust_code jni name: {}", jni_func_name);
+        register_natives::remember_native_method(
+            ctx,
+            &class.name.to_string(),
+            &java_method_name,
+            method.variant,
+            f_method,
+            &jni_func_name,
+        );
 
         let mut known_names: FxHashSet<SmolStr> =
             method.arg_names_without_self().map(|x| x.into()).collect();
@@ -662,6 +1147,7 @@ May be you need to use `private constructor = empty;` syntax?",
             decl_func_args: &decl_func_args,
             real_output_typename: &real_output_typename,
             ret_name: &ret_name,
+            native_kind: register_natives::native_kind(ctx.cfg, class, method, f_method)?,
         };
 
         match method.variant {
@@ -703,34 +1189,43 @@ May be you need to use `private constructor = empty;` syntax?",
 
         let unpack_code = unpack_from_heap_pointer(&this_type, "this", false);
 
+        let destructor_sign = JniForeignMethodSignature {
+            output: ForeignTypeInfo {
+                name: "".into(),
+                correspoding_rust_type: dummy_rust_ty,
+            }
+            .into(),
+            input: vec![JavaForeignTypeInfo {
+                base: ForeignTypeInfo {
+                    name: "long".into(),
+                    correspoding_rust_type: jlong_type,
+                },
+                java_converter: None,
+                annotation: None,
+                unsigned_check: None,
+            }],
+        };
         let jni_destructor_name = rust_code::generate_jni_func_name(
             ctx,
             &class.name.to_string(),
             (class.src_id, class.span()),
             "do_delete",
             MethodVariant::StaticMethod,
-            &JniForeignMethodSignature {
-                output: ForeignTypeInfo {
-                    name: "".into(),
-                    correspoding_rust_type: dummy_rust_ty,
-                }
-                .into(),
-                input: vec![JavaForeignTypeInfo {
-                    base: ForeignTypeInfo {
-                        name: "long".into(),
-                        correspoding_rust_type: jlong_type,
-                    },
-                    java_converter: None,
-                    annotation: None,
-                }],
-            },
+            &destructor_sign,
             false,
         )?;
+        register_natives::remember_native_method(
+            ctx,
+            &class.name.to_string(),
+            "do_delete",
+            MethodVariant::StaticMethod,
+            &destructor_sign,
+            &jni_destructor_name,
+        );
         let code = format!(
             r#"
 #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
-#[no_mangle]
-pub extern "C" fn {jni_destructor_name}(env: *mut JNIEnv, _: jclass, this: jlong) {{
+{linkage}extern "C" fn {jni_destructor_name}(env: *mut JNIEnv, _: jclass, this: jlong) {{
     let this: *mut {this_type} = unsafe {{
         jlong_to_pointer::<{this_type}>(this).as_mut().unwrap()
     }};
@@ -739,6 +1234,7 @@ pub extern "C" fn {jni_destructor_name}(env: *mut JNIEnv, _: jclass, this: jlong
 }}
 "#,
             jni_destructor_name = jni_destructor_name,
+            linkage = register_natives::jni_func_linkage(ctx.cfg),
             unpack_code = unpack_code,
             this_type = this_type_for_method,
         );
@@ -833,6 +1329,8 @@ fn find_suitable_foreign_types_for_methods(
 }
 
 fn generate_static_method(ctx: &mut JavaContext, mc: &MethodContext) -> Result<()> {
+    use std::fmt::Write;
+
     let jni_ret_type = mc.f_method.output.base.correspoding_rust_type.typename();
     let (mut deps_code_out, convert_output_code) = foreign_from_rust_convert_method_output(
         ctx.conv_map,
@@ -843,34 +1341,49 @@ fn generate_static_method(ctx: &mut JavaContext, mc: &MethodContext) -> Result<(
         &jni_ret_type,
     )?;
     ctx.rust_code.append(&mut deps_code_out);
-    let (mut deps_code_in, convert_input_code) = foreign_to_rust_convert_method_inputs(
-        ctx.conv_map,
-        mc.class.src_id,
-        mc.method,
-        mc.f_method,
-        mc.method.arg_names_without_self(),
-        &jni_ret_type,
-    )?;
-    ctx.rust_code.append(&mut deps_code_in);
+    let (convert_input_code, critical_code) = convert_rust_inputs(ctx, mc, &jni_ret_type)?;
+    let mut null_check_code = String::new();
+    if null_check_in_rust(mc.method, mc.f_method) {
+        for (arg, arg_name) in mc
+            .f_method
+            .input
+            .iter()
+            .zip(mc.method.arg_names_without_self())
+        {
+            if need_null_check(ctx.cfg, arg) {
+                writeln!(
+                    &mut null_check_code,
+                    r#"    if {arg_name}.is_null() {{
+        jni_throw_null_pointer_exception(env, "{arg_name} must not be null");
+        return JniInvalidValue::jni_invalid_value();
+    }}"#,
+                    arg_name = arg_name,
+                )
+                .expect(WRITE_TO_MEM_FAILED_MSG);
+            }
+        }
+    }
 
     let code = format!(
         r#"
 #[allow(non_snake_case, unused_variables, unused_mut, unused_unsafe)]
-#[no_mangle]
-pub extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) -> {jni_ret_type} {{
-{convert_input_code}
+{linkage}extern "C" fn {func_name}({env_args}{decl_func_args}) -> {jni_ret_type} {{
+{null_check_code}{convert_input_code}
     let mut {ret_name}: {real_output_typename} = {call};
 {convert_output_code}
     {ret_name}
 }}
 "#,
         func_name = mc.jni_func_name,
+        linkage = register_natives::jni_func_linkage(ctx.cfg),
+        env_args = mc.native_kind.jni_env_args(),
         decl_func_args = mc.decl_func_args,
         jni_ret_type = jni_ret_type,
+        null_check_code = null_check_code,
         convert_input_code = convert_input_code,
         convert_output_code = convert_output_code,
         real_output_typename = mc.real_output_typename,
-        call = mc.method.generate_code_to_call_rust_func(),
+        call = call_with_critical_arrays(mc, &critical_code),
         ret_name = mc.ret_name,
     );
 
@@ -889,15 +1402,7 @@ fn generate_constructor(
     this_type: Type,
     code_box_this: &TokenStream,
 ) -> Result<()> {
-    let (mut deps_code_in, convert_input_code) = foreign_to_rust_convert_method_inputs(
-        ctx.conv_map,
-        mc.class.src_id,
-        mc.method,
-        mc.f_method,
-        mc.method.arg_names_without_self(),
-        "jlong",
-    )?;
-    ctx.rust_code.append(&mut deps_code_in);
+    let (convert_input_code, critical_code) = convert_rust_inputs(ctx, mc, "jlong")?;
     let this_type = ctx.conv_map.ty_to_rust_type(&this_type);
     let construct_ret_type = ctx.conv_map.ty_to_rust_type(&construct_ret_type);
 
@@ -921,8 +1426,7 @@ fn generate_constructor(
     let code = format!(
         r#"
 #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
-#[no_mangle]
-pub extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) -> jlong {{
+{linkage}extern "C" fn {func_name}({env_args}{decl_func_args}) -> jlong {{
 {convert_input_code}
     let this: {real_output_typename} = {call};
 {convert_this}
@@ -931,6 +1435,8 @@ pub extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) ->
 }}
 "#,
         func_name = mc.jni_func_name,
+        linkage = register_natives::jni_func_linkage(ctx.cfg),
+        env_args = mc.native_kind.jni_env_args(),
         convert_this = convert_this,
         decl_func_args = mc.decl_func_args,
         convert_input_code = convert_input_code,
@@ -940,7 +1446,7 @@ pub extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) ->
             code_box_this
         },
         real_output_typename = mc.real_output_typename,
-        call = mc.method.generate_code_to_call_rust_func(),
+        call = call_with_critical_arrays(mc, &critical_code),
     );
 
     ctx.rust_code.push(
@@ -958,15 +1464,7 @@ fn generate_method(
     this_type_for_method: &RustType,
 ) -> Result<()> {
     let jni_ret_type = mc.f_method.output.base.correspoding_rust_type.typename();
-    let (mut deps_code_in, convert_input_code) = foreign_to_rust_convert_method_inputs(
-        ctx.conv_map,
-        mc.class.src_id,
-        mc.method,
-        mc.f_method,
-        mc.method.arg_names_without_self(),
-        &jni_ret_type,
-    )?;
-    ctx.rust_code.append(&mut deps_code_in);
+    let (convert_input_code, critical_code) = convert_rust_inputs(ctx, mc, &jni_ret_type)?;
     let (mut deps_code_out, convert_output_code) = foreign_from_rust_convert_method_output(
         ctx.conv_map,
         mc.class.src_id,
@@ -1005,9 +1503,8 @@ fn generate_method(
     let code = format!(
         r#"
 #[allow(non_snake_case, unused_variables, unused_mut, unused_unsafe)]
-#[no_mangle]
-pub extern "C"
- fn {func_name}(env: *mut JNIEnv, _: jclass, this: jlong, {decl_func_args}) -> {jni_ret_type} {{
+{linkage}extern "C"
+ fn {func_name}({env_args}this: jlong, {decl_func_args}) -> {jni_ret_type} {{
 {convert_input_code}
     let this: {this_type_ref} = unsafe {{
         jlong_to_pointer::<{this_type}>(this).as_mut().unwrap()
@@ -1019,6 +1516,8 @@ pub extern "C"
 }}
 "#,
         func_name = mc.jni_func_name,
+        linkage = register_natives::jni_func_linkage(ctx.cfg),
+        env_args = mc.native_kind.jni_env_args(),
         decl_func_args = mc.decl_func_args,
         convert_input_code = convert_input_code,
         jni_ret_type = jni_ret_type,
@@ -1027,7 +1526,7 @@ pub extern "C"
         convert_this = convert_this,
         convert_output_code = convert_output_code,
         real_output_typename = mc.real_output_typename,
-        call = mc.method.generate_code_to_call_rust_func(),
+        call = call_with_critical_arrays(mc, &critical_code),
         ret_name = mc.ret_name,
     );
 
@@ -1038,6 +1537,180 @@ pub extern "C"
     Ok(())
 }
 
+/// Convert arguments of JNI function to Rust types. Arguments marked with
+/// `#[PrimitiveArrayCritical]` are converted separately, so the caller can put
+/// them just around the call of Rust function and the critical region
+/// has no JNI calls inside.
+fn convert_rust_inputs(
+    ctx: &mut JavaContext,
+    mc: &MethodContext,
+    func_ret_type: &str,
+) -> Result<(String, String)> {
+    let skip_n = match mc.method.variant {
+        MethodVariant::Method(_) => 1,
+        _ => 0,
+    };
+    let mut convert_code = String::new();
+    let mut critical_args = Vec::new();
+    for ((arg, f_from), arg_name) in mc
+        .method
+        .fn_decl
+        .inputs
+        .iter()
+        .skip(skip_n)
+        .zip(mc.f_method.input.iter())
+        .zip(mc.method.arg_names_without_self())
+    {
+        let arg = arg
+            .as_named_arg()
+            .map_err(|err| DiagnosticError::from_syn_err(mc.class.src_id, err))?;
+        if arg
+            .unknown_attrs
+            .iter()
+            .any(|x| x == PRIMITIVE_ARRAY_CRITICAL)
+        {
+            let (elem_ty, mutable) = primitive_array_critical_elem(mc, arg, f_from)?;
+            critical_args.push((arg_name, elem_ty, mutable));
+            continue;
+        }
+        let to = ctx
+            .conv_map
+            .find_or_alloc_rust_type(&arg.ty, mc.class.src_id);
+        let (mut deps, code) = ctx.conv_map.convert_rust_types(
+            f_from.base.correspoding_rust_type.to_idx(),
+            to.to_idx(),
+            arg_name,
+            arg_name,
+            func_ret_type,
+            (mc.class.src_id, arg.ty.span()),
+        )?;
+        ctx.rust_code.append(&mut deps);
+        convert_code.push_str(&code);
+    }
+    Ok((
+        convert_code,
+        primitive_array_critical_code(&critical_args, func_ret_type),
+    ))
+}
+
+/// Access arrays via `GetPrimitiveArrayCritical`. Lengths are queried
+/// before the first array is acquired, and if acquire fails, the already
+/// acquired arrays are released before exception is thrown.
+fn primitive_array_critical_code(
+    critical_args: &[(&str, &str, bool)],
+    func_ret_type: &str,
+) -> String {
+    let mut code = String::new();
+    for (arg_name, elem_ty, _) in critical_args {
+        code.push_str(&format!(
+            r#"
+    let {arg}_len: usize = JavaPrimitiveArrayCritical::<{elem_ty}>::len(env, {arg});"#,
+            arg = arg_name,
+            elem_ty = elem_ty,
+        ));
+    }
+    for (i, (arg_name, elem_ty, mutable)) in critical_args.iter().enumerate() {
+        for (other_arg, _, other_mutable) in &critical_args[..i] {
+            if !*mutable && !*other_mutable {
+                continue;
+            }
+            code.push_str(&format!(
+                r#"
+    if !JavaPrimitiveArrayCritical::<{elem_ty}>::check_not_same(env, {arg}, {other_arg}, "{other_arg} and {arg} should be different arrays") {{
+        return <{ret_type}>::jni_invalid_value();
+    }}"#,
+                arg = arg_name,
+                other_arg = other_arg,
+                elem_ty = elem_ty,
+                ret_type = func_ret_type,
+            ));
+        }
+    }
+    for (i, (arg_name, elem_ty, mutable)) in critical_args.iter().enumerate() {
+        let release_acquired: String = critical_args[..i]
+            .iter()
+            .map(|(prev_arg, _, _)| format!("\n            drop({}_arr);", prev_arg))
+            .collect();
+        code.push_str(&format!(
+            r#"
+    let mut {arg}_arr: JavaPrimitiveArrayCritical<{elem_ty}> = match JavaPrimitiveArrayCritical::acquire(env, {arg}, {arg}_len, {mutable}) {{
+        Some(x) => x,
+        None => {{{release_acquired}
+            JavaPrimitiveArrayCritical::<{elem_ty}>::acquire_failed(env);
+            return <{ret_type}>::jni_invalid_value();
+        }}
+    }};"#,
+            arg = arg_name,
+            elem_ty = elem_ty,
+            mutable = mutable,
+            release_acquired = release_acquired,
+            ret_type = func_ret_type,
+        ));
+    }
+    for (arg_name, elem_ty, mutable) in critical_args {
+        code.push_str(&format!(
+            r#"
+    let mut {arg}: &{mut_ref}[{elem_ty}] = {arg}_arr.{to_slice}();"#,
+            arg = arg_name,
+            elem_ty = elem_ty,
+            mut_ref = if *mutable { "mut " } else { "" },
+            to_slice = if *mutable { "to_slice_mut" } else { "to_slice" },
+        ));
+    }
+    code
+}
+
+/// Check that argument can be accessed via `GetPrimitiveArrayCritical`:
+/// `&[T]` or `&mut [T]` passed as Java array of primitive type with the same layout
+fn primitive_array_critical_elem(
+    mc: &MethodContext,
+    arg: &NamedArg,
+    f_arg: &JavaForeignTypeInfo,
+) -> Result<(&'static str, bool)> {
+    static ELEM_TYPES: [(&str, &str); 7] = [
+        ("i8", "byte []"),
+        ("u8", "byte []"),
+        ("i16", "short []"),
+        ("i32", "int []"),
+        ("i64", "long []"),
+        ("f32", "float []"),
+        ("f64", "double []"),
+    ];
+    let java_ty = java_code::filter_null_annotation(f_arg.base.name.display());
+    if let Type::Reference(syn::TypeReference {
+        ref mutability,
+        ref elem,
+        ..
+    }) = arg.ty
+    {
+        if let Type::Slice(syn::TypeSlice {
+            elem: ref slice_elem,
+            ..
+        }) = **elem
+        {
+            let slice_elem = normalize_type(slice_elem);
+            if let Some((rust_elem, _)) = ELEM_TYPES.iter().find(|(rust_elem, java_arr)| {
+                *rust_elem == slice_elem && *java_arr == java_ty.trim()
+            }) {
+                if f_arg.java_converter.is_none() {
+                    return Ok((rust_elem, mutability.is_some()));
+                }
+            }
+        }
+    }
+    Err(DiagnosticError::new(
+        mc.class.src_id,
+        arg.span,
+        format!(
+            "class {}, method {}: argument {} can not be {}, expect &[T] or &mut [T] mapped to Java array of primitive type",
+            mc.class.name,
+            mc.method.short_name(),
+            arg.name,
+            PRIMITIVE_ARRAY_CRITICAL
+        ),
+    ))
+}
+
 fn convert_code_for_method<'a, NI: Iterator<Item = &'a str>>(
     ctx_span: SourceIdSpan,
     cfg: &JavaConfig,
@@ -1045,6 +1718,7 @@ fn convert_code_for_method<'a, NI: Iterator<Item = &'a str>>(
     arg_name_iter: NI,
     mut known_names: FxHashSet<SmolStr>,
     flags: java_code::ArgsFormatFlags,
+    null_checks: bool,
 ) -> Result<(String, String, String)> {
     use std::fmt::Write;
 
@@ -1057,19 +1731,50 @@ fn convert_code_for_method<'a, NI: Iterator<Item = &'a str>>(
     }
     let mut protect_args = Vec::new();
     for (i, (arg, arg_name)) in f_method.input.iter().zip(arg_name_iter).enumerate() {
+        let null_check = null_checks && need_null_check(cfg, arg);
+        if null_check {
+            write!(
+                &mut conv_code,
+                r#"
+        if ({arg_name} == null)
+            throw new NullPointerException("{arg_name} must not be null");"#,
+                arg_name = arg_name
+            )
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+        if let Some(check) = arg.unsigned_check.as_ref() {
+            conv_code.push_str(&unsigned::arg_check_code(
+                cfg,
+                check,
+                arg_name,
+                &mut known_names,
+            ));
+        }
         let after_conv_arg_name = if let Some(java_conv) = arg.java_converter.as_ref() {
             let templ = format!("a{}", i);
             let after_conv_arg_name = new_unique_name(&known_names, &templ);
             known_names.insert(after_conv_arg_name.clone());
+            let after_conv_arg_decl = if kotlin_code::use_kotlin(cfg) {
+                format!(
+                    "val {}: {}",
+                    after_conv_arg_name,
+                    kotlin_code::kotlin_type(
+                        java_conv.java_transition_type.display(),
+                        java_conv.annotation
+                    )
+                )
+            } else {
+                format!("{} {}", java_conv.java_transition_type, after_conv_arg_name)
+            };
             let java_code: String = java_conv
                 .converter
-                .replace(
-                    TO_VAR_TYPE_TEMPLATE,
-                    &format!("{} {}", java_conv.java_transition_type, after_conv_arg_name),
-                )
+                .replace(TO_VAR_TYPE_TEMPLATE, &after_conv_arg_decl)
                 .replace(TO_VAR_TEMPLATE, &after_conv_arg_name)
                 .replace(FROM_VAR_TEMPLATE, arg_name);
             let java_code = java_code::filter_null_annotation(&java_code);
+            if (null_check || arg.unsigned_check.is_some()) && !java_code.starts_with('\n') {
+                conv_code.push('\n');
+            }
             conv_code.push_str(&java_code);
             Some(after_conv_arg_name)
         } else {
@@ -1087,6 +1792,11 @@ fn convert_code_for_method<'a, NI: Iterator<Item = &'a str>>(
             args_for_call_internal.push_str(", ");
         }
     }
+    let end_of_statement = if kotlin_code::use_kotlin(cfg) {
+        ""
+    } else {
+        ";"
+    };
     match cfg.reachability_fence {
         JavaReachabilityFence::Std => {
             for arg_name in &protect_args {
@@ -1095,8 +1805,8 @@ fn convert_code_for_method<'a, NI: Iterator<Item = &'a str>>(
                 }
                 write!(
                     &mut reachability_fence_code,
-                    "        java.lang.ref.Reference.reachabilityFence({});",
-                    arg_name
+                    "        java.lang.ref.Reference.reachabilityFence({}){}",
+                    arg_name, end_of_statement
                 )
                 .expect(WRITE_TO_MEM_FAILED_MSG);
             }
@@ -1129,7 +1839,8 @@ fn convert_code_for_method<'a, NI: Iterator<Item = &'a str>>(
                     reachability_fence_code.push_str(arg_name);
                     first_arg = false;
                 }
-                reachability_fence_code.push_str(");");
+                reachability_fence_code.push(')');
+                reachability_fence_code.push_str(end_of_statement);
             }
         }
     }
@@ -1138,6 +1849,7 @@ fn convert_code_for_method<'a, NI: Iterator<Item = &'a str>>(
 }
 
 fn calc_output_conv<'a>(
+    cfg: &JavaConfig,
     output: &'a JavaForeignTypeInfo,
     conv: &'a JavaConverter,
     ret_name: &str,
@@ -1145,10 +1857,19 @@ fn calc_output_conv<'a>(
 ) -> (&'a str, &'a str, String) {
     let ret_type = output.base.name.display();
     let intermidiate_ret_type = conv.java_transition_type.display();
+    let conv_ret_decl = if kotlin_code::use_kotlin(cfg) {
+        format!(
+            "val {}: {}",
+            conv_ret,
+            kotlin_code::kotlin_type(ret_type, output.annotation)
+        )
+    } else {
+        format!("{} {}", ret_type, conv_ret)
+    };
     let conv_code = conv
         .converter
         .replace(FROM_VAR_TEMPLATE, ret_name)
-        .replace(TO_VAR_TYPE_TEMPLATE, &format!("{} {}", ret_type, conv_ret))
+        .replace(TO_VAR_TYPE_TEMPLATE, &conv_ret_decl)
         .replace(TO_VAR_TEMPLATE, &conv_ret);
     let mut conv_code: String = java_code::filter_null_annotation(&conv_code).trim().into();
     if !conv_code.is_empty() && !conv_code.starts_with('\n') {
@@ -6,7 +6,7 @@ use syn::{Ident, Type};
 
 use super::{
     java_class_full_name, java_class_name_to_jni, java_code::doc_comments_to_java_comments,
-    map_write_err, JavaContext,
+    kotlin_code, map_write_err, proguard, JavaContext,
 };
 use crate::{
     error::{invalid_src_id_span, DiagnosticError, Result},
@@ -43,8 +43,13 @@ pub(in crate::java_jni) fn generate_enum(
         fenum.src_id,
     );
 
-    generate_java_code_for_enum(ctx, fenum)
-        .map_err(|err| DiagnosticError::new(fenum.src_id, fenum.span(), &err))?;
+    let use_kotlin = kotlin_code::use_kotlin(ctx.cfg);
+    if use_kotlin {
+        generate_kotlin_code_for_enum(ctx, fenum)
+    } else {
+        generate_java_code_for_enum(ctx, fenum)
+    }
+    .map_err(|err| DiagnosticError::new(fenum.src_id, fenum.span(), &err))?;
     generate_rust_code_for_enum(ctx, fenum)?;
 
     let jint_rty = ctx.conv_map.ty_to_rust_type(&parse_type! { jint });
@@ -58,12 +63,21 @@ pub(in crate::java_jni) fn generate_enum(
                 input_to_output: false,
                 intermediate_ty: jint_rty.to_idx(),
                 conv_code: Rc::new(TypeConvCode::new(
-                    format!(
-                        "        {enum_name} {out} = {enum_name}.fromInt({var});",
-                        out = TO_VAR_TEMPLATE,
-                        enum_name = fenum.name,
-                        var = FROM_VAR_TEMPLATE
-                    ),
+                    if use_kotlin {
+                        format!(
+                            "        val {out}: {enum_name} = {enum_name}.fromInt({var})",
+                            out = TO_VAR_TEMPLATE,
+                            enum_name = fenum.name,
+                            var = FROM_VAR_TEMPLATE
+                        )
+                    } else {
+                        format!(
+                            "        {enum_name} {out} = {enum_name}.fromInt({var});",
+                            out = TO_VAR_TEMPLATE,
+                            enum_name = fenum.name,
+                            var = FROM_VAR_TEMPLATE
+                        )
+                    },
                     invalid_src_id_span(),
                 )),
             }),
@@ -74,7 +88,11 @@ pub(in crate::java_jni) fn generate_enum(
                 input_to_output: false,
                 intermediate_ty: jint_rty.to_idx(),
                 conv_code: Rc::new(TypeConvCode::new(
-                    format!("        int {out} = {in}.getValue();", out = TO_VAR_TEMPLATE, in = FROM_VAR_TEMPLATE),
+                    if use_kotlin {
+                        format!("        val {out}: Int = {in}.value", out = TO_VAR_TEMPLATE, in = FROM_VAR_TEMPLATE)
+                    } else {
+                        format!("        int {out} = {in}.getValue();", out = TO_VAR_TEMPLATE, in = FROM_VAR_TEMPLATE)
+                    },
                     invalid_src_id_span(),
                 )),
             }),
@@ -102,7 +120,7 @@ fn generate_java_code_for_enum(
 ) -> std::result::Result<(), String> {
     let path = ctx.cfg.output_dir.join(format!("{}.java", fenum.name));
     let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
-    let enum_doc_comments = doc_comments_to_java_comments(&fenum.doc_comments, true);
+    let enum_doc_comments = doc_comments_to_java_comments(ctx.conv_map, &fenum.doc_comments, true);
     writeln!(
         file,
         r#"// Automatically generated by flapigen
@@ -117,7 +135,8 @@ public enum {enum_name} {{"#,
     .expect(WRITE_TO_MEM_FAILED_MSG);
 
     for (i, item) in fenum.items.iter().enumerate() {
-        let mut doc_comments = doc_comments_to_java_comments(&item.doc_comments, false);
+        let mut doc_comments =
+            doc_comments_to_java_comments(ctx.conv_map, &item.doc_comments, false);
         if !doc_comments.is_empty() {
             if !doc_comments.ends_with('\n') {
                 doc_comments.push('\n');
@@ -175,6 +194,80 @@ public enum {enum_name} {{"#,
     Ok(())
 }
 
+fn generate_kotlin_code_for_enum(
+    ctx: &mut JavaContext,
+    fenum: &ForeignEnumInfo,
+) -> std::result::Result<(), String> {
+    let path = ctx.cfg.output_dir.join(format!("{}.kt", fenum.name));
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+    let enum_doc_comments = kotlin_code::doc_comments_to_kdoc(&fenum.doc_comments, true);
+    writeln!(
+        file,
+        r#"// Automatically generated by flapigen
+package {package_name}
+
+{doc_comments}
+enum class {enum_name}(val value: Int) {{"#,
+        package_name = ctx.cfg.package_name,
+        enum_name = fenum.name,
+        doc_comments = enum_doc_comments,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+
+    for (i, item) in fenum.items.iter().enumerate() {
+        let mut doc_comments = kotlin_code::doc_comments_to_kdoc(&item.doc_comments, false);
+        if !doc_comments.is_empty() {
+            if !doc_comments.ends_with('\n') {
+                doc_comments.push('\n');
+            }
+            doc_comments.push_str("    ");
+        }
+        writeln!(
+            file,
+            "    {doc_comments}{item_name}({index}){separator}",
+            item_name = item.name,
+            index = i,
+            doc_comments = doc_comments,
+            separator = if i == fenum.items.len() - 1 { ';' } else { ',' },
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+
+    write!(
+        file,
+        r#"
+    companion object {{
+        internal fun fromInt(x: Int): {enum_name} = when (x) {{"#,
+        enum_name = fenum.name
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+
+    for (i, item) in fenum.items.iter().enumerate() {
+        write!(
+            file,
+            r#"
+            {index} -> {item_name}"#,
+            index = i,
+            item_name = item.name
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+
+    writeln!(
+        file,
+        r#"
+            else -> throw Error("Invalid value for enum {enum_name}: " + x)
+        }}
+    }}
+}}"#,
+        enum_name = fenum.name
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
 fn generate_rust_code_for_enum(ctx: &mut JavaContext, fenum: &ForeignEnumInfo) -> Result<()> {
     let mut arms_to_jint = Vec::with_capacity(fenum.items.len());
     let mut arms_from_jint = Vec::with_capacity(fenum.items.len());
@@ -223,10 +316,12 @@ fn add_conversation_from_enum_to_jobject_for_callbacks(
     );
 
     let mut arms_match_fields_names = Vec::with_capacity(fenum.items.len());
+    proguard::keep_class(ctx, &fenum.name.to_string());
     for item in &fenum.items {
         let rust_name = &item.rust_name;
         let java_item = item.name.to_string();
         let enum_sig = format!("L{};", enum_class_name);
+        proguard::keep_field(ctx, &fenum.name.to_string(), &java_item, &enum_sig, true);
         let enum_filed_global_var = Ident::new(
             &format!("{}_{}", enum_id_upper, java_item.to_uppercase()),
             Span::call_site(),
@@ -0,0 +1,284 @@
+use log::trace;
+use proc_macro2::Span;
+use quote::quote;
+use std::io::Write;
+use syn::Ident;
+
+use super::{
+    java_class_full_name, java_class_name_to_jni, kotlin_code, map_write_err, proguard, JavaContext,
+};
+use crate::{
+    error::{DiagnosticError, Result},
+    file_cache::FileWriteCache,
+    source_registry::SourceId,
+    JavaException, WRITE_TO_MEM_FAILED_MSG,
+};
+
+const JAVA_EXCEPTION_TRAIT: &str = "SwigJavaException";
+const EXCEPTION_CONSTRUCTOR_SIG: &str = "(Ljava/lang/String;Ljava/lang/Throwable;)V";
+const EXCEPTION_WITH_CODE_CONSTRUCTOR_SIG: &str = "(Ljava/lang/String;Ljava/lang/Throwable;I)V";
+
+pub(in crate::java_jni) fn generate_exception(
+    ctx: &mut JavaContext,
+    exception: &JavaException,
+) -> Result<()> {
+    trace!(
+        "generate_exception: {} for {}",
+        exception.class_name,
+        exception.rust_error_type
+    );
+    let err_ty: syn::Type = syn::parse_str(&exception.rust_error_type).map_err(|err| {
+        DiagnosticError::map_any_err_to_our_err(format!(
+            "Can not parse Rust error type '{}' of exception {}: {}",
+            exception.rust_error_type, exception.class_name, err
+        ))
+    })?;
+    let err_rty = ctx.conv_map.find_or_alloc_rust_type_that_implements(
+        &err_ty,
+        &[JAVA_EXCEPTION_TRAIT],
+        SourceId::none(),
+    );
+    if let Some(prev) = ctx.exceptions.insert(
+        err_rty.normalized_name.clone(),
+        exception.class_name.clone(),
+    ) {
+        return Err(DiagnosticError::map_any_err_to_our_err(format!(
+            "Rust error type '{}' registered for two exceptions: {} and {}",
+            exception.rust_error_type, prev, exception.class_name
+        )));
+    }
+
+    let with_code = !exception.error_codes.is_empty();
+    generate_java_class(
+        ctx,
+        JavaExceptionClass {
+            class_name: &exception.class_name,
+            base_class: &exception.base_class,
+            have_subclasses: !exception.variants.is_empty(),
+            error_codes: if with_code {
+                Some(&exception.error_codes)
+            } else {
+                None
+            },
+        },
+    )
+    .map_err(DiagnosticError::map_any_err_to_our_err)?;
+    for (_, variant_class) in &exception.variants {
+        generate_java_class(
+            ctx,
+            JavaExceptionClass {
+                class_name: variant_class,
+                base_class: &exception.class_name,
+                have_subclasses: false,
+                error_codes: if with_code { Some(&[]) } else { None },
+            },
+        )
+        .map_err(DiagnosticError::map_any_err_to_our_err)?;
+    }
+
+    // `Self::` instead of type name, because of type can be generic, like `Error<String>`
+    let mut arms = Vec::with_capacity(exception.variants.len() + 1);
+    for (rust_variant, variant_class) in &exception.variants {
+        let variant = Ident::new(rust_variant, Span::call_site());
+        let find_class = class_and_constructor_ids(ctx, variant_class, with_code);
+        arms.push(quote! { Self::#variant { .. } => #find_class });
+    }
+    let find_class = class_and_constructor_ids(ctx, &exception.class_name, with_code);
+    arms.push(quote! { _ => #find_class });
+
+    let error_code = if with_code {
+        let mut code_arms = Vec::with_capacity(exception.error_codes.len() + 1);
+        for (rust_variant, code) in &exception.error_codes {
+            let variant = Ident::new(rust_variant, Span::call_site());
+            let code = proc_macro2::Literal::i32_unsuffixed(*code);
+            code_arms.push(quote! { Self::#variant { .. } => #code });
+        }
+        code_arms.push(quote! { _ => 0 });
+        quote! {
+            #[allow(unreachable_patterns)]
+            fn java_error_code(&self) -> Option<jint> {
+                Some(match self {
+                    #(#code_arms),*
+                })
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    ctx.rust_code.push(quote! {
+        impl SwigJavaException for #err_ty {
+            #[allow(unreachable_patterns)]
+            fn java_exception_class(&self) -> (jclass, jmethodID) {
+                match self {
+                    #(#arms),*
+                }
+            }
+            #error_code
+        }
+    });
+
+    Ok(())
+}
+
+fn class_and_constructor_ids(
+    ctx: &mut JavaContext,
+    class_name: &str,
+    with_code: bool,
+) -> proc_macro2::TokenStream {
+    let constructor_sig = if with_code {
+        EXCEPTION_WITH_CODE_CONSTRUCTOR_SIG
+    } else {
+        EXCEPTION_CONSTRUCTOR_SIG
+    };
+    proguard::keep_method(ctx, class_name, "<init>", constructor_sig);
+    let class_for_jni =
+        java_class_name_to_jni(&java_class_full_name(&ctx.cfg.package_name, class_name));
+    let class_id = Ident::new(
+        &format!("FOREIGN_EXCEPTION_{}", class_name.to_uppercase()),
+        Span::call_site(),
+    );
+    let constructor_id = Ident::new(
+        &format!(
+            "FOREIGN_EXCEPTION_{}_CONSTRUCTOR",
+            class_name.to_uppercase()
+        ),
+        Span::call_site(),
+    );
+    quote! {
+        (
+            swig_jni_find_class!(#class_id, #class_for_jni),
+            swig_jni_get_method_id!(#constructor_id, #class_id, "<init>", #constructor_sig)
+        )
+    }
+}
+
+struct JavaExceptionClass<'a> {
+    class_name: &'a str,
+    base_class: &'a str,
+    have_subclasses: bool,
+    /// `Some` if constructor accepts error code,
+    /// not empty for class that declares `getErrorCode()`
+    error_codes: Option<&'a [(String, i32)]>,
+}
+
+/// `NotFound` -> `NOT_FOUND`
+fn error_code_const_name(rust_variant: &str) -> String {
+    let mut name = String::with_capacity(rust_variant.len() + 4);
+    for (i, ch) in rust_variant.chars().enumerate() {
+        if ch.is_uppercase() && i > 0 && !name.ends_with('_') {
+            name.push('_');
+        }
+        name.extend(ch.to_uppercase());
+    }
+    name
+}
+
+fn generate_java_class(
+    ctx: &mut JavaContext,
+    exc: JavaExceptionClass,
+) -> std::result::Result<(), String> {
+    use std::fmt::Write as _;
+
+    let JavaExceptionClass {
+        class_name,
+        base_class,
+        have_subclasses,
+        error_codes,
+    } = exc;
+    let declares_code = error_codes.map(|x| !x.is_empty()).unwrap_or(false);
+    let path = ctx.cfg.output_dir.join(format!(
+        "{}.{}",
+        class_name,
+        kotlin_code::src_file_ext(ctx.cfg)
+    ));
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+    if kotlin_code::use_kotlin(ctx.cfg) {
+        let (code_arg, base_args) = match error_codes {
+            Some(_) if declares_code => (", val errorCode: Int", "(message, cause)"),
+            Some(_) => (", errorCode: Int", "(message, cause, errorCode)"),
+            None => ("", "(message, cause)"),
+        };
+        let mut body = String::new();
+        if declares_code {
+            body.push_str(" {\n    companion object {\n");
+            for (rust_variant, code) in error_codes.unwrap_or(&[]) {
+                writeln!(
+                    &mut body,
+                    "        const val {}: Int = {}",
+                    error_code_const_name(rust_variant),
+                    code
+                )
+                .expect(WRITE_TO_MEM_FAILED_MSG);
+            }
+            body.push_str("    }\n}");
+        }
+        writeln!(
+            file,
+            r#"// Automatically generated by flapigen
+package {package_name}
+
+{open_mark}class {class_name} internal constructor(message: String?, cause: Throwable?{code_arg}) :
+    {base_class}{base_args}{body}"#,
+            package_name = ctx.cfg.package_name,
+            open_mark = if have_subclasses { "open " } else { "" },
+            class_name = class_name,
+            code_arg = code_arg,
+            base_class = base_class,
+            base_args = base_args,
+            body = body,
+        )
+    } else {
+        let (code_arg, super_args, set_code) = match error_codes {
+            Some(_) if declares_code => (
+                ", int errorCode",
+                "message, cause",
+                "\n        this.errorCode = errorCode;",
+            ),
+            Some(_) => (", int errorCode", "message, cause, errorCode", ""),
+            None => ("", "message, cause", ""),
+        };
+        let mut code_members = String::new();
+        if declares_code {
+            for (rust_variant, code) in error_codes.unwrap_or(&[]) {
+                writeln!(
+                    &mut code_members,
+                    "    public static final int {} = {};",
+                    error_code_const_name(rust_variant),
+                    code
+                )
+                .expect(WRITE_TO_MEM_FAILED_MSG);
+            }
+            code_members.push_str(
+                r#"
+    public final int getErrorCode() {
+        return errorCode;
+    }
+    private final int errorCode;
+"#,
+            );
+        }
+        writeln!(
+            file,
+            r#"// Automatically generated by flapigen
+package {package_name};
+
+public {final_mark}class {class_name} extends {base_class} {{
+    /*package*/ {class_name}(String message, Throwable cause{code_arg}) {{
+        super({super_args});{set_code}
+    }}
+{code_members}}}"#,
+            package_name = ctx.cfg.package_name,
+            final_mark = if have_subclasses { "" } else { "final " },
+            class_name = class_name,
+            base_class = base_class,
+            code_arg = code_arg,
+            super_args = super_args,
+            set_code = set_code,
+            code_members = code_members,
+        )
+    }
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
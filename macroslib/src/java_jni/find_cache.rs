@@ -152,7 +152,7 @@ impl<'ast> Visit<'ast> for JniCacheMacroCallsVisitor<'ast> {
                 syn::parse2(mac.tokens.clone()).expect("Can not parse swig_jni_find_class call");
             let id = find_class.id.to_string();
             if let Some(call) = self.inner.calls.get(&id) {
-                if *call != find_class {
+                if call.path != find_class.path {
                     println!(
                         "waring=You use the same id '{}' for different classes '{}' vs '{}'",
                         id,
@@ -168,8 +168,9 @@ impl<'ast> Visit<'ast> for JniCacheMacroCallsVisitor<'ast> {
                             find_class.path.value()
                         ),
                     ));
-                    return;
                 }
+                // class already registered, keep already collected ids of methods and fields
+                return;
             }
             self.inner.calls.insert(id, find_class);
         } else if mac.path.is_ident(SWIG_JNI_GET_METHOD_ID) {
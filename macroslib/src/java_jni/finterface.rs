@@ -4,24 +4,28 @@ use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use rustc_hash::FxHashMap;
 use std::io::Write;
-use syn::{spanned::Spanned, Ident};
+use syn::{spanned::Spanned, Ident, Type};
 
 use super::{
-    java_code, map_type::map_type, map_write_err, rust_code, JavaContext, JavaForeignTypeInfo,
-    JniForeignMethodSignature,
+    java_class_full_name, java_class_name_to_jni, java_code, kotlin_code, map_type::map_type,
+    map_write_err, object_cleanup, proguard, register_natives, rust_code, JavaContext,
+    JavaForeignTypeInfo, JniForeignMethodSignature, JAVA_RUST_SELF_NAME,
 };
 use crate::{
-    error::{panic_on_syn_error, DiagnosticError, Result},
+    error::{invalid_src_id_span, panic_on_syn_error, DiagnosticError, Result},
     file_cache::FileWriteCache,
     source_registry::SourceId,
     typemap::{
-        ast::{DisplayToTokens, ForeignTypeName},
+        ast::{
+            if_result_return_ok_err_types, parse_ty_with_given_span, DisplayToTokens,
+            ForeignTypeName,
+        },
         ty::RustType,
         utils::rust_to_foreign_convert_method_inputs,
-        ForeignTypeInfo,
+        ForeignTypeInfo, TypeConvCode, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
     },
-    types::ForeignInterface,
-    WRITE_TO_MEM_FAILED_MSG,
+    types::{ForeignInterface, ForeignInterfaceMethod, MethodVariant, SelfTypeVariant},
+    JavaCallbackExceptionPolicy, JavaConfig, WRITE_TO_MEM_FAILED_MSG,
 };
 
 pub(in crate::java_jni) fn generate_interface(
@@ -29,14 +33,33 @@ pub(in crate::java_jni) fn generate_interface(
     interface: &ForeignInterface,
 ) -> Result<()> {
     let f_methods = find_suitable_ftypes_for_interace_methods(ctx, interface)?;
-    generate_java_code_for_interface(
-        ctx,
-        interface,
-        &f_methods,
-        ctx.cfg.null_annotation_package.as_deref(),
-    )
+    let rust_impl = rust_code_generate_rust_impl(ctx, interface, &f_methods)?;
+    if kotlin_code::use_kotlin(ctx.cfg) {
+        generate_kotlin_code_for_interface(ctx, interface, &f_methods).and_then(|_| {
+            if rust_impl {
+                generate_kotlin_code_for_rust_impl(ctx, interface, &f_methods)
+            } else {
+                Ok(())
+            }
+        })
+    } else {
+        let null_annotation_package = ctx.cfg.null_annotation_package.as_deref();
+        generate_java_code_for_interface(ctx, interface, &f_methods, null_annotation_package)
+            .and_then(|_| {
+                if rust_impl {
+                    generate_java_code_for_rust_impl(
+                        ctx,
+                        interface,
+                        &f_methods,
+                        null_annotation_package,
+                    )
+                } else {
+                    Ok(())
+                }
+            })
+    }
     .map_err(|err| DiagnosticError::new(interface.src_id, interface.span(), err))?;
-    generate_rust_code_for_interface(ctx, interface, &f_methods)?;
+    generate_rust_code_for_interface(ctx, interface, &f_methods, rust_impl)?;
 
     let my_jobj_ti = ctx.conv_map.find_or_alloc_rust_type_with_suffix(
         &parse_type! { jobject },
@@ -44,9 +67,51 @@ pub(in crate::java_jni) fn generate_interface(
         SourceId::none(),
     );
     ctx.conv_map.add_foreign(
-        my_jobj_ti,
+        my_jobj_ti.clone(),
         ForeignTypeName::from_ident(&interface.name, interface.src_id),
     )?;
+    if rust_impl {
+        let trait_object = format!("dyn {}", DisplayToTokens(&interface.self_type));
+        let mut smart_pointers = vec![("Box", "Boxed")];
+        // `Arc` gives only shared access to trait object
+        if !rust_impl_needs_mut(interface)? {
+            smart_pointers.push(("Arc", "Shared"));
+        }
+        for (smart_pointer, variant) in smart_pointers {
+            let rust_ty: Type = parse_ty_with_given_span(
+                &format!("{}<{}>", smart_pointer, trait_object),
+                interface.name.span(),
+            )
+            .map_err(|err| DiagnosticError::from_syn_err(interface.src_id, err))?;
+            let rust_ty = ctx
+                .conv_map
+                .find_or_alloc_rust_type(&rust_ty, interface.src_id);
+            ctx.conv_map.add_conversation_rule(
+                rust_ty.to_idx(),
+                my_jobj_ti.to_idx(),
+                TypeConvCode::new2(
+                    format!(
+                        "let {to_var}: jobject = JavaRustImpl::<{trait_object}>::{variant}({from_var}).swig_into_jobject(env);",
+                        to_var = TO_VAR_TEMPLATE,
+                        from_var = FROM_VAR_TEMPLATE,
+                        trait_object = trait_object,
+                        variant = variant,
+                    ),
+                    invalid_src_id_span(),
+                )
+                .into(),
+            );
+        }
+    }
+    let interface_name = interface.name.to_string();
+    ctx.java_type_to_jni_sig_map.insert(
+        interface_name.clone().into(),
+        format!(
+            "L{};",
+            java_class_full_name(&ctx.cfg.package_name, &interface_name)
+        )
+        .into(),
+    );
     Ok(())
 }
 
@@ -101,16 +166,18 @@ It is impossible to use this Java code:{}\nfor callback types conversation",
 
             input.push(f_arg_type);
         }
-        let output = match method.fn_decl.output {
-            syn::ReturnType::Default => ForeignTypeInfo {
-                name: void_sym.into(),
-                correspoding_rust_type: dummy_rust_ty.clone(),
-            }
-            .into(),
-            syn::ReturnType::Type(_, ref ret_ty) => {
+        let ret_ty = match method.fn_decl.output {
+            syn::ReturnType::Default => None,
+            syn::ReturnType::Type(_, ref ret_ty) => Some(
+                callback_result_ok_type(ctx, interace.src_id, method)
+                    .unwrap_or_else(|| (**ret_ty).clone()),
+            ),
+        };
+        let output = match ret_ty {
+            Some(ref ret_ty) if !is_unit_type(ret_ty) => {
                 let rust_ret_ty = ctx
                     .conv_map
-                    .find_or_alloc_rust_type(&ret_ty, interace.src_id);
+                    .find_or_alloc_rust_type(ret_ty, interace.src_id);
                 let f_ret_type = map_type(
                     ctx,
                     &rust_ret_ty,
@@ -127,12 +194,40 @@ It is impossible to use for callback function.", conv.converter),
 
                 f_ret_type
             }
+            _ => ForeignTypeInfo {
+                name: void_sym.into(),
+                correspoding_rust_type: dummy_rust_ty.clone(),
+            }
+            .into(),
         };
         f_methods.push(JniForeignMethodSignature { output, input });
     }
     Ok(f_methods)
 }
 
+/// If callback method returns `Result<T, E>`, return `T`,
+/// Java implementation returns `T` and Java exception converted to `E`
+fn callback_result_ok_type(
+    ctx: &mut JavaContext,
+    src_id: SourceId,
+    method: &ForeignInterfaceMethod,
+) -> Option<Type> {
+    match method.fn_decl.output {
+        syn::ReturnType::Default => None,
+        syn::ReturnType::Type(_, ref ret_ty) => {
+            let rust_ret_ty = ctx.conv_map.find_or_alloc_rust_type(ret_ty, src_id);
+            if_result_return_ok_err_types(&rust_ret_ty).map(|(ok_ty, _err_ty)| ok_ty)
+        }
+    }
+}
+
+fn is_unit_type(ty: &Type) -> bool {
+    match ty {
+        Type::Tuple(ref tuple) => tuple.elems.is_empty(),
+        _ => false,
+    }
+}
+
 fn generate_java_code_for_interface(
     ctx: &mut JavaContext,
     interface: &ForeignInterface,
@@ -143,7 +238,7 @@ fn generate_java_code_for_interface(
     let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
     let imports = java_code::get_null_annotation_imports(use_null_annotation, methods_sign);
     let interface_comments =
-        java_code::doc_comments_to_java_comments(&interface.doc_comments, true);
+        java_code::doc_comments_to_java_comments(ctx.conv_map, &interface.doc_comments, true);
     writeln!(
         file,
         r#"// Automatically generated by flapigen
@@ -159,13 +254,19 @@ public interface {interface_name} {{"#,
     .expect(WRITE_TO_MEM_FAILED_MSG);
 
     for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let exception_spec = if callback_result_ok_type(ctx, interface.src_id, method).is_some() {
+            " throws Exception"
+        } else {
+            ""
+        };
         writeln!(
             file,
             r#"
 {doc_comments}
-    {output_type} {method_name}({single_args_with_types});"#,
+    {output_type} {method_name}({single_args_with_types}){exception_spec};"#,
             method_name = method.name,
-            doc_comments = java_code::doc_comments_to_java_comments(&method.doc_comments, false),
+            doc_comments =
+                java_code::doc_comments_to_java_comments(ctx.conv_map, &method.doc_comments, false),
             single_args_with_types = java_code::args_with_java_types(
                 f_method,
                 method.arg_names_without_self(),
@@ -173,6 +274,64 @@ public interface {interface_name} {{"#,
                 use_null_annotation.is_some()
             ),
             output_type = f_method.output.base.name,
+            exception_spec = exception_spec,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+
+    file.write_all(b"\n}\n").expect(WRITE_TO_MEM_FAILED_MSG);
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+fn generate_kotlin_code_for_interface(
+    ctx: &mut JavaContext,
+    interface: &ForeignInterface,
+    methods_sign: &[JniForeignMethodSignature],
+) -> std::result::Result<(), String> {
+    let path = ctx.cfg.output_dir.join(format!("{}.kt", interface.name));
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+    let interface_comments = kotlin_code::doc_comments_to_kdoc(&interface.doc_comments, true);
+    writeln!(
+        file,
+        r#"// Automatically generated by flapigen
+package {package_name}
+
+{doc_comments}
+interface {interface_name} {{"#,
+        package_name = ctx.cfg.package_name,
+        interface_name = interface.name,
+        doc_comments = interface_comments,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let throws = if callback_result_ok_type(ctx, interface.src_id, method).is_some() {
+            "\n    @Throws(Exception::class)"
+        } else {
+            ""
+        };
+        let arg_names: Vec<String> = method
+            .arg_names_without_self()
+            .map(|x| kotlin_code::escape_name(x).into_owned())
+            .collect();
+        writeln!(
+            file,
+            r#"
+{doc_comments}{throws}
+    fun {method_name}({single_args_with_types}){output_type}"#,
+            method_name = kotlin_code::escape_name(&method.name.to_string()),
+            doc_comments = kotlin_code::doc_comments_to_kdoc(&method.doc_comments, false),
+            throws = throws,
+            single_args_with_types = kotlin_code::args_with_kotlin_types(
+                f_method,
+                arg_names.iter().map(String::as_str),
+                java_code::ArgsFormatFlags::EXTERNAL,
+            ),
+            output_type = kotlin_code::ret_type_decl(&kotlin_code::kotlin_type(
+                f_method.output.base.name.display(),
+                f_method.output.annotation
+            )),
         )
         .expect(WRITE_TO_MEM_FAILED_MSG);
     }
@@ -182,30 +341,631 @@ public interface {interface_name} {{"#,
     Ok(())
 }
 
+/// Java class that implements interface via Rust implementation of trait
+fn rust_impl_class_name(interface: &ForeignInterface) -> String {
+    format!("{}Rust", interface.name)
+}
+
+/// Method that Rust code calls to move Rust implementation out of Java object,
+/// names of Kotlin's `internal` methods are mangled, so there is public wrapper
+fn rust_impl_release_method(cfg: &JavaConfig) -> &'static str {
+    if kotlin_code::use_kotlin(cfg) {
+        "releaseRustImpl"
+    } else {
+        "releaseNativeObj"
+    }
+}
+
+/// Is `&mut self` used by methods, so implementation can not be shared via `Arc`
+fn rust_impl_needs_mut(interface: &ForeignInterface) -> Result<bool> {
+    for method in &interface.items {
+        if let SelfTypeVariant::RptrMut = method.fn_decl.inputs[0].as_self_arg(interface.src_id)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Can Java type of callback be used as is for Java method that calls Rust
+fn is_the_same_java_type(
+    ftype: &JavaForeignTypeInfo,
+    callback_ftype: &JavaForeignTypeInfo,
+) -> bool {
+    // `Result` is mapped with empty conversation in Java code
+    let no_java_conv = ftype.java_converter.as_ref().map_or(true, |conv| {
+        conv.converter.trim().is_empty()
+            && conv.java_transition_type.display() == ftype.base.name.display()
+    });
+    no_java_conv
+        && ftype.unsigned_check.is_none()
+        && ftype.base.name.display() == callback_ftype.base.name.display()
+        && ftype.base.correspoding_rust_type.to_idx()
+            == callback_ftype.base.correspoding_rust_type.to_idx()
+}
+
+/// Generate native methods of Java class, that implements interface
+/// via Rust implementation of trait, and conversations of this class
+/// from/to Java object.
+/// Returns `false` if some type can not be converted from Java to Rust
+/// for argument, or from Rust to Java for result
+fn rust_code_generate_rust_impl(
+    ctx: &mut JavaContext,
+    interface: &ForeignInterface,
+    methods_sign: &[JniForeignMethodSignature],
+) -> Result<bool> {
+    use std::fmt::Write;
+
+    // names of methods generated by `object_cleanup`
+    const RESERVED_NAMES: [&str; 6] = [
+        "delete",
+        "close",
+        "finalize",
+        "releaseNativeObj",
+        "releaseRustImpl",
+        object_cleanup::REGISTER_CLEANUP_METHOD,
+    ];
+    let class_name = rust_impl_class_name(interface);
+    let trait_object = format!("dyn {}", DisplayToTokens(&interface.self_type));
+    let impl_type = format!("JavaRustImpl<{}>", trait_object);
+    let needs_mut = rust_impl_needs_mut(interface)?;
+    let linkage = register_natives::jni_func_linkage(ctx.cfg);
+    let mut conv_deps = Vec::new();
+    let mut natives = Vec::with_capacity(interface.items.len() + 1);
+    // `Arc` can be taken back only as `Box` with forwarding implementation of trait
+    let mut arc_impl_funcs = String::new();
+
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let java_method_name = format!("do_{}", method.name);
+        if RESERVED_NAMES.contains(&method.name.to_string().as_str())
+            || method
+                .arg_names_without_self()
+                .any(|x| x == JAVA_RUST_SELF_NAME)
+        {
+            return Ok(false);
+        }
+        let self_variant = method.fn_decl.inputs[0].as_self_arg(interface.src_id)?;
+        let (this_ref, get_this) = match self_variant {
+            SelfTypeVariant::Rptr => ("&", "get"),
+            SelfTypeVariant::RptrMut => ("&mut ", "get_mut"),
+            SelfTypeVariant::Mut | SelfTypeVariant::Default => return Ok(false),
+        };
+        let jni_ret_type = f_method.output.base.correspoding_rust_type.typename();
+        let mut args_with_types = String::new();
+        let mut convert_args = String::new();
+        let mut call_args = String::new();
+        let mut arc_impl_args = String::new();
+        for (i, (arg, f_arg)) in method
+            .fn_decl
+            .inputs
+            .iter()
+            .skip(1)
+            .zip(&f_method.input)
+            .enumerate()
+        {
+            let named_arg = arg
+                .as_named_arg()
+                .map_err(|err| DiagnosticError::from_syn_err(interface.src_id, err))?;
+            let arg_name = format!("a{}", i);
+            let arg_span = (interface.src_id, named_arg.ty.span());
+            let arg_rust_ty = ctx
+                .conv_map
+                .find_or_alloc_rust_type(&named_arg.ty, interface.src_id);
+            match map_type(ctx, &arg_rust_ty, Direction::Incoming, arg_span) {
+                Ok(ref ftype) if is_the_same_java_type(ftype, f_arg) => {}
+                _ => return Ok(false),
+            }
+            write!(
+                &mut args_with_types,
+                "{}: {}, ",
+                arg_name,
+                f_arg.base.correspoding_rust_type.typename()
+            )
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+            let (mut deps, conv_code) = match ctx.conv_map.convert_rust_types(
+                f_arg.base.correspoding_rust_type.to_idx(),
+                arg_rust_ty.to_idx(),
+                &arg_name,
+                &arg_name,
+                jni_ret_type,
+                arg_span,
+            ) {
+                Ok(x) => x,
+                Err(_) => return Ok(false),
+            };
+            conv_deps.append(&mut deps);
+            convert_args.push_str(&conv_code);
+            write!(&mut call_args, ", {}", arg_name).expect(WRITE_TO_MEM_FAILED_MSG);
+            write!(
+                &mut arc_impl_args,
+                ", {}: {}",
+                arg_name,
+                DisplayToTokens(&named_arg.ty)
+            )
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+        let (real_ret_type, convert_output) = match method.fn_decl.output {
+            syn::ReturnType::Type(_, ref ret_ty) if !is_unit_type(ret_ty) => {
+                let ret_span = (interface.src_id, ret_ty.span());
+                let real_ret_type = ctx
+                    .conv_map
+                    .find_or_alloc_rust_type(ret_ty, interface.src_id);
+                match map_type(ctx, &real_ret_type, Direction::Outgoing, ret_span) {
+                    Ok(ref ftype) if is_the_same_java_type(ftype, &f_method.output) => {}
+                    _ => return Ok(false),
+                }
+                let (mut deps, conv_code) = match ctx.conv_map.convert_rust_types(
+                    real_ret_type.to_idx(),
+                    f_method.output.base.correspoding_rust_type.to_idx(),
+                    "ret",
+                    "ret",
+                    jni_ret_type,
+                    ret_span,
+                ) {
+                    Ok(x) => x,
+                    Err(_) => return Ok(false),
+                };
+                conv_deps.append(&mut deps);
+                (real_ret_type.normalized_name.to_string(), conv_code)
+            }
+            _ => ("()".to_string(), String::new()),
+        };
+        let jni_func_name = rust_code::generate_jni_func_name(
+            ctx,
+            &class_name,
+            (interface.src_id, interface.span()),
+            &java_method_name,
+            MethodVariant::Method(self_variant),
+            f_method,
+            false,
+        )?;
+        let code = format!(
+            r#"
+#[allow(non_snake_case, unused_variables, unused_mut, unused_unsafe)]
+{linkage}extern "C" fn {jni_func_name}(env: *mut JNIEnv, _: jclass, this: jlong, {args_with_types}) -> {jni_ret_type} {{
+{convert_args}
+    let this: {this_ref}{impl_type} = unsafe {{
+        jlong_to_pointer::<{impl_type}>(this).as_mut().unwrap()
+    }};
+    let mut ret: {real_ret_type} = {rust_name}(this.{get_this}(){call_args});
+{convert_output}
+    ret
+}}
+"#,
+            linkage = linkage,
+            jni_func_name = jni_func_name,
+            args_with_types = args_with_types,
+            jni_ret_type = jni_ret_type,
+            convert_args = convert_args,
+            this_ref = this_ref,
+            impl_type = impl_type,
+            real_ret_type = real_ret_type,
+            rust_name = DisplayToTokens(&method.rust_name),
+            get_this = get_this,
+            call_args = call_args,
+            convert_output = convert_output,
+        );
+        natives.push((
+            java_method_name,
+            MethodVariant::Method(self_variant),
+            jni_func_name,
+            code,
+        ));
+
+        let trait_func_name = match method.rust_name.segments.last() {
+            Some(seg) => &seg.ident,
+            None => return Ok(false),
+        };
+        writeln!(
+            &mut arc_impl_funcs,
+            r#"
+    fn {func_name}(&self{args}){ret} {{
+        {rust_name}(&*self.0{call_args})
+    }}"#,
+            func_name = trait_func_name,
+            args = arc_impl_args,
+            ret = DisplayToTokens(&method.fn_decl.output),
+            rust_name = DisplayToTokens(&method.rust_name),
+            call_args = call_args,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+
+    let jlong_type = ctx.conv_map.ty_to_rust_type(&parse_type! { jlong });
+    let dummy_rust_ty = ctx
+        .conv_map
+        .find_or_alloc_rust_type_no_src_id(&parse_type! { () });
+    let destructor_sign = JniForeignMethodSignature {
+        output: ForeignTypeInfo {
+            name: "".into(),
+            correspoding_rust_type: dummy_rust_ty,
+        }
+        .into(),
+        input: vec![ForeignTypeInfo {
+            name: "long".into(),
+            correspoding_rust_type: jlong_type,
+        }
+        .into()],
+    };
+    let jni_destructor_name = rust_code::generate_jni_func_name(
+        ctx,
+        &class_name,
+        (interface.src_id, interface.span()),
+        "do_delete",
+        MethodVariant::StaticMethod,
+        &destructor_sign,
+        false,
+    )?;
+    let destructor_code = format!(
+        r#"
+#[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
+{linkage}extern "C" fn {jni_destructor_name}(env: *mut JNIEnv, _: jclass, this: jlong) {{
+    let this: Box<{impl_type}> = unsafe {{ Box::from_raw(jlong_to_pointer::<{impl_type}>(this)) }};
+    drop(this);
+}}
+"#,
+        linkage = linkage,
+        jni_destructor_name = jni_destructor_name,
+        impl_type = impl_type,
+    );
+
+    for ((java_method_name, method_variant, jni_func_name, _), f_method) in
+        natives.iter().zip(methods_sign)
+    {
+        register_natives::remember_native_method(
+            ctx,
+            &class_name,
+            java_method_name,
+            *method_variant,
+            f_method,
+            jni_func_name,
+        );
+    }
+    register_natives::remember_native_method(
+        ctx,
+        &class_name,
+        "do_delete",
+        MethodVariant::StaticMethod,
+        &destructor_sign,
+        &jni_destructor_name,
+    );
+    let release_method = rust_impl_release_method(ctx.cfg);
+    proguard::keep_class(ctx, &class_name);
+    proguard::keep_method(ctx, &class_name, "<init>", "(J)V");
+    proguard::keep_method(ctx, &class_name, release_method, "()J");
+    proguard::keep_field(ctx, &class_name, JAVA_RUST_SELF_NAME, "J", false);
+
+    let class_name_for_jni =
+        java_class_name_to_jni(&java_class_full_name(&ctx.cfg.package_name, &class_name));
+    let class_id = format!("FOREIGN_CLASS_{}", class_name.to_uppercase());
+    let arc_impl = if needs_mut || !interface.arc_can_be_boxed() {
+        String::new()
+    } else {
+        format!(
+            r#"
+/// `Arc` implementation taken back from `{class_name}`
+#[allow(non_camel_case_types)]
+struct SwigArcImpl{interface_name}(::std::sync::Arc<{trait_object}>);
+
+impl {trait_name} for SwigArcImpl{interface_name} {{{arc_impl_funcs}}}
+"#,
+            class_name = class_name,
+            interface_name = interface.name,
+            trait_object = trait_object,
+            trait_name = DisplayToTokens(&interface.self_type.bounds[0]),
+            arc_impl_funcs = arc_impl_funcs,
+        )
+    };
+    let mut code = format!(
+        r#"{arc_impl}
+impl {impl_type} {{
+    /// `{class_name}` object that owns `self`,
+    /// or Java object itself if `self` wraps Java implementation
+    fn swig_into_jobject(self, env: *mut JNIEnv) -> jobject {{
+        let class: jclass = swig_jni_find_class!({class_id}, "{class_name_for_jni}");
+        let constructor: jmethodID = swig_jni_get_method_id!(
+            {class_id}_CONSTRUCTOR,
+            {class_id},
+            "<init>",
+            "(J)V"
+        );
+        self.into_jobject(env, class, constructor)
+    }}
+    /// Take Rust implementation from `{class_name}` object
+    fn swig_from_jobject(env: *mut JNIEnv, obj: jobject) -> Option<Self> {{
+        let class: jclass = swig_jni_find_class!({class_id}, "{class_name_for_jni}");
+        let field: jfieldID = swig_jni_get_field_id!(
+            {class_id}_{field_id}_FIELD,
+            {class_id},
+            "{rust_self_name}",
+            "J"
+        );
+        let release: jmethodID = swig_jni_get_method_id!(
+            {class_id}_{release_id}_METHOD,
+            {class_id},
+            "{release_method}",
+            "()J"
+        );
+        Self::from_jobject(env, obj, class, field, release)
+    }}
+}}
+{destructor_code}"#,
+        arc_impl = arc_impl,
+        impl_type = impl_type,
+        class_name = class_name,
+        class_id = class_id,
+        class_name_for_jni = class_name_for_jni,
+        field_id = JAVA_RUST_SELF_NAME.to_uppercase(),
+        rust_self_name = JAVA_RUST_SELF_NAME,
+        release_id = release_method.to_uppercase(),
+        release_method = release_method,
+        destructor_code = destructor_code,
+    );
+    for (_, _, _, native_code) in natives {
+        code.push_str(&native_code);
+    }
+    ctx.rust_code.append(&mut conv_deps);
+    ctx.rust_code
+        .push(syn::parse_str(&code).unwrap_or_else(|err| {
+            panic_on_syn_error(
+                "java/jni internal rust implementation of interface",
+                code,
+                err,
+            )
+        }));
+    Ok(true)
+}
+
+/// Generate Java class, that implements interface
+/// via Rust implementation of trait
+fn generate_java_code_for_rust_impl(
+    ctx: &mut JavaContext,
+    interface: &ForeignInterface,
+    methods_sign: &[JniForeignMethodSignature],
+    use_null_annotation: Option<&str>,
+) -> std::result::Result<(), String> {
+    let class_name = rust_impl_class_name(interface);
+    let path = ctx.cfg.output_dir.join(format!("{}.java", class_name));
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+    let imports = java_code::get_null_annotation_imports(use_null_annotation, methods_sign);
+    let implements = object_cleanup::class_implements(ctx.cfg);
+    writeln!(
+        file,
+        r#"// Automatically generated by flapigen
+package {package_name};
+{imports}
+/**
+ * Implementation of {{@link {interface_name}}} by Rust object
+ */
+/*package*/ final class {class_name} implements {interface_name}{implements} {{
+    /*package*/ {class_name}(long ptr) {{
+        {rust_self_name} = ptr;{register_cleanup}
+    }}"#,
+        package_name = ctx.cfg.package_name,
+        imports = imports,
+        interface_name = interface.name,
+        class_name = class_name,
+        implements = implements.replace(" implements ", ", "),
+        rust_self_name = JAVA_RUST_SELF_NAME,
+        register_cleanup = object_cleanup::register_cleanup_call(ctx.cfg),
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let arg_names = || method.arg_names_without_self();
+        let output_type = f_method.output.base.name.display();
+        writeln!(
+            file,
+            r#"
+    @Override
+    public {output_type} {method_name}({args_with_types}) {{{deleted_check}
+        {return_stmt}do_{method_name}({rust_self_name}{args});
+    }}
+    private static native {output_type} do_{method_name}(long self{native_args_with_types});"#,
+            output_type = output_type,
+            method_name = method.name,
+            args_with_types = java_code::args_with_java_types(
+                f_method,
+                arg_names(),
+                java_code::ArgsFormatFlags::EXTERNAL,
+                use_null_annotation.is_some()
+            ),
+            deleted_check = object_cleanup::deleted_object_check(ctx.cfg, &class_name),
+            return_stmt = if output_type == "void" { "" } else { "return " },
+            rust_self_name = JAVA_RUST_SELF_NAME,
+            args = arg_names().map(|x| format!(", {}", x)).collect::<String>(),
+            native_args_with_types = java_code::args_with_java_types(
+                f_method,
+                arg_names(),
+                java_code::ArgsFormatFlags::INTERNAL
+                    | java_code::ArgsFormatFlags::USE_COMMA_IF_NEED,
+                false
+            ),
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+
+    writeln!(
+        file,
+        r#"{delete_methods}
+
+    /*package*/ long {rust_self_name};
+}}"#,
+        delete_methods = object_cleanup::generate_delete_methods(ctx.cfg, ""),
+        rust_self_name = JAVA_RUST_SELF_NAME,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// The same as `generate_java_code_for_rust_impl`, but for Kotlin
+fn generate_kotlin_code_for_rust_impl(
+    ctx: &mut JavaContext,
+    interface: &ForeignInterface,
+    methods_sign: &[JniForeignMethodSignature],
+) -> std::result::Result<(), String> {
+    let class_name = rust_impl_class_name(interface);
+    let path = ctx.cfg.output_dir.join(format!("{}.kt", class_name));
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+    writeln!(
+        file,
+        r#"// Automatically generated by flapigen
+package {package_name}
+
+/**
+ * Implementation of [{interface_name}] by Rust object
+ */
+internal class {class_name} : {interface_name}, java.io.Closeable {{
+    internal constructor(ptr: Long) {{
+        {rust_self_name} = ptr{register_cleanup}
+    }}"#,
+        package_name = ctx.cfg.package_name,
+        interface_name = interface.name,
+        class_name = class_name,
+        rust_self_name = JAVA_RUST_SELF_NAME,
+        register_cleanup = object_cleanup::register_cleanup_call(ctx.cfg),
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+
+    let mut companion = String::new();
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let arg_names: Vec<String> = method
+            .arg_names_without_self()
+            .map(|x| kotlin_code::escape_name(x).into_owned())
+            .collect();
+        let output_type = kotlin_code::ret_type_decl(&kotlin_code::kotlin_type(
+            f_method.output.base.name.display(),
+            f_method.output.annotation,
+        ));
+        writeln!(
+            file,
+            r#"
+    override fun {method_name}({args_with_types}){output_type} {{
+{deleted_check}        {return_stmt}do_{method_name}({rust_self_name}{args})
+    }}"#,
+            method_name = kotlin_code::escape_name(&method.name.to_string()),
+            args_with_types = kotlin_code::args_with_kotlin_types(
+                f_method,
+                arg_names.iter().map(String::as_str),
+                java_code::ArgsFormatFlags::EXTERNAL,
+            ),
+            output_type = output_type,
+            deleted_check = object_cleanup::deleted_object_check(ctx.cfg, &class_name),
+            return_stmt = if output_type.is_empty() {
+                ""
+            } else {
+                "return "
+            },
+            rust_self_name = JAVA_RUST_SELF_NAME,
+            args = arg_names
+                .iter()
+                .map(|x| format!(", {}", x))
+                .collect::<String>(),
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        companion.push_str(&format!(
+            r#"        @JvmStatic
+        private external fun do_{method_name}(self: Long{args_with_types}){output_type}
+"#,
+            method_name = method.name,
+            args_with_types = kotlin_code::args_with_kotlin_types(
+                f_method,
+                arg_names.iter().map(String::as_str),
+                java_code::ArgsFormatFlags::INTERNAL
+                    | java_code::ArgsFormatFlags::USE_COMMA_IF_NEED,
+            ),
+            output_type = output_type,
+        ));
+    }
+
+    let (delete_methods, delete_companion) =
+        object_cleanup::generate_kotlin_delete_methods(ctx.cfg, "");
+    writeln!(
+        file,
+        r#"{delete_methods}
+    fun {release_method}(): Long = releaseNativeObj()
+    @JvmField
+    internal var {rust_self_name}: Long = 0L
+
+    companion object {{
+{companion}{delete_companion}    }}
+}}"#,
+        delete_methods = delete_methods,
+        release_method = rust_impl_release_method(ctx.cfg),
+        rust_self_name = JAVA_RUST_SELF_NAME,
+        companion = companion,
+        delete_companion = kotlin_code::indent_for_companion(&delete_companion),
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
 fn generate_rust_code_for_interface(
     ctx: &mut JavaContext,
     interface: &ForeignInterface,
     methods_sign: &[JniForeignMethodSignature],
+    rust_impl: bool,
 ) -> Result<()> {
     use std::fmt::Write;
 
-    let mut new_conv_code = format!(
+    let trait_name = DisplayToTokens(&interface.self_type);
+    let (take_rust_impl_box, take_rust_impl_arc) = if rust_impl {
+        let take_shared = if rust_impl_needs_mut(interface)? {
+            "Some(JavaRustImpl::Shared(_)) => unreachable!(),".to_string()
+        } else if interface.arc_can_be_boxed() {
+            format!(
+                "Some(JavaRustImpl::Shared(x)) => return Box::new(SwigArcImpl{}(x)),",
+                interface.name
+            )
+        } else {
+            // wrapper of `Arc` is not `Send`, so wrap Java object instead
+            "Some(JavaRustImpl::Shared(_)) => {}".to_string()
+        };
+        (
+            format!(
+                r#"
+        match JavaRustImpl::<dyn {trait_name}>::swig_from_jobject(env, this) {{
+            Some(JavaRustImpl::Boxed(x)) => return x,
+            {take_shared}
+            None => {{}}
+        }}"#,
+                trait_name = trait_name,
+                take_shared = take_shared,
+            ),
+            format!(
+                r#"
+        match JavaRustImpl::<dyn {trait_name}>::swig_from_jobject(env, this) {{
+            Some(JavaRustImpl::Boxed(x)) => return ::std::sync::Arc::from(x),
+            Some(JavaRustImpl::Shared(x)) => return x,
+            None => {{}}
+        }}"#,
+                trait_name = trait_name,
+            ),
+        )
+    } else {
+        (String::new(), String::new())
+    };
+    let mut new_callback_code = format!(
         r#"
-#[swig_from_foreigner_hint = "{interface_name}"]
-impl SwigFrom<jobject> for Box<dyn {trait_name}> {{
-    fn swig_from(this: jobject, env: *mut JNIEnv) -> Self {{
         let mut cb = JavaCallback::new(this, env);
         cb.methods.reserve({methods_len});
         let class = unsafe {{ (**env).GetObjectClass.unwrap()(env, cb.this) }};
         assert!(!class.is_null(), "GetObjectClass return null class for {interface_name}");
 "#,
         interface_name = interface.name,
-        trait_name = DisplayToTokens(&interface.self_type),
         methods_len = interface.items.len(),
     );
     for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let method_sig = rust_code::jni_method_signature(ctx, f_method);
+        proguard::keep_interface_method(
+            ctx,
+            &interface.name.to_string(),
+            &method.name.to_string(),
+            &method_sig,
+        );
         writeln!(
-            &mut new_conv_code,
+            &mut new_callback_code,
             r#"
         let method_id: jmethodID = unsafe {{
             (**env).GetMethodID.unwrap()(env, class, swig_c_str!("{method_name}"),
@@ -214,16 +974,37 @@ impl SwigFrom<jobject> for Box<dyn {trait_name}> {{
         assert!(!method_id.is_null(), "Can not find {method_name} id");
         cb.methods.push(method_id);"#,
             method_name = method.name,
-            method_sig = rust_code::jni_method_signature(ctx, f_method),
+            method_sig = method_sig,
         )
         .unwrap();
     }
-    new_conv_code.push_str(
+    // address of `JavaCallback` is registered, so it should not be moved
+    // into `Arc` via `Box`
+    let new_conv_code = format!(
         r#"
-        Box::new(cb)
-    }
-}
+#[swig_from_foreigner_hint = "{interface_name}"]
+impl SwigFrom<jobject> for Box<dyn {trait_name}> {{
+    fn swig_from(this: jobject, env: *mut JNIEnv) -> Self {{{take_rust_impl_box}{new_callback_code}
+        let cb = Box::new(cb);
+        cb.register();
+        cb
+    }}
+}}
+
+#[swig_from_foreigner_hint = "{interface_name}"]
+impl SwigFrom<jobject> for Arc<dyn {trait_name}> {{
+    fn swig_from(this: jobject, env: *mut JNIEnv) -> Self {{{take_rust_impl_arc}{new_callback_code}
+        let cb = ::std::sync::Arc::new(cb);
+        cb.register();
+        cb
+    }}
+}}
 "#,
+        interface_name = interface.name,
+        trait_name = trait_name,
+        take_rust_impl_box = take_rust_impl_box,
+        take_rust_impl_arc = take_rust_impl_arc,
+        new_callback_code = new_callback_code,
     );
     ctx.conv_map
         .merge(SourceId::none(), &new_conv_code, ctx.pointer_target_width)?;
@@ -282,8 +1063,33 @@ impl SwigFrom<jobject> for Box<dyn {trait_name}> {{
                 err,
             )
         });
-        match method.fn_decl.output {
-            syn::ReturnType::Default => trait_impl_funcs.push(quote! {
+        let result_ok_ty = callback_result_ok_type(ctx, interface.src_id, method);
+        let is_result = result_ok_ty.is_some();
+        let exception_check = if is_result {
+            quote! {
+                if let Some(ex) = jni_take_pending_exception(env) {
+                    return Err(ex.into());
+                }
+            }
+        } else {
+            callback_exception_check(ctx.cfg.callback_exception_policy, func_name)
+        };
+        let ret_ty = match method.fn_decl.output {
+            syn::ReturnType::Default => None,
+            syn::ReturnType::Type(_, ref ret_ty) => Some(ret_ty),
+        };
+        let output_ty = ret_ty.map(|ret_ty| result_ok_ty.unwrap_or_else(|| (**ret_ty).clone()));
+        let ret_value = match output_ty {
+            Some(ref output_ty) if !is_unit_type(output_ty) => quote!(ret),
+            _ => quote!(()),
+        };
+        let ret_value = if is_result {
+            quote!(Ok(#ret_value))
+        } else {
+            ret_value
+        };
+        match (ret_ty, output_ty) {
+            (None, _) => trait_impl_funcs.push(quote! {
                 #[allow(unused_mut)]
                 fn #func_name(#(#args_with_types),*) {
                     #type_size_asserts
@@ -294,19 +1100,35 @@ impl SwigFrom<jobject> for Box<dyn {trait_name}> {{
                             (**env).CallVoidMethod.unwrap()(env, self.this,
                                                             self.methods[#method_idx],
                                                             #(#args),*);
-                            if (**env).ExceptionCheck.unwrap()(env) != 0 {
-                                log::error!(concat!(stringify!(#func_name), ": java throw exception"));
-                                (**env).ExceptionDescribe.unwrap()(env);
-                                (**env).ExceptionClear.unwrap()(env);
-                            }
+                            #exception_check
                         };
                     }
                 }
             }),
-            syn::ReturnType::Type(_, ref ret_ty) => {
+            (Some(ret_ty), Some(ref output_ty)) if is_unit_type(output_ty) => {
+                trait_impl_funcs.push(quote! {
+                    #[allow(unused_mut)]
+                    fn #func_name(#(#args_with_types),*) -> #ret_ty {
+                        #type_size_asserts
+                        let env = self.get_jni_env();
+                        let env = env.env.expect(concat!("Can not get env for ", stringify!(#func_name)));
+
+                        #convert_args
+                        unsafe {
+                            (**env).CallVoidMethod.unwrap()(env, self.this,
+                                                            self.methods[#method_idx],
+                                                            #(#args),*);
+                            #exception_check
+                        };
+                        #ret_value
+                    }
+                })
+            }
+            (Some(ret_ty), output_ty) => {
+                let output_ty = output_ty.expect("Internal error: no output type for callback");
                 let real_output_type: RustType = ctx
                     .conv_map
-                    .find_or_alloc_rust_type(ret_ty, interface.src_id);
+                    .find_or_alloc_rust_type(&output_ty, interface.src_id);
                 let jni_ret_type = &f_method.output.base.correspoding_rust_type;
                 let (mut conv_deps, out_conv_code) = ctx.conv_map.convert_rust_types(
                     jni_ret_type.to_idx(),
@@ -347,16 +1169,12 @@ impl SwigFrom<jobject> for Box<dyn {trait_name}> {{
                             ret = (**env).#jni_caller.unwrap()(env, self.this,
                                                             self.methods[#method_idx],
                                                             #(#args),*);
-                            if (**env).ExceptionCheck.unwrap()(env) != 0 {
-                                log::error!(concat!(stringify!(#func_name), ": java throw exception"));
-                                (**env).ExceptionDescribe.unwrap()(env);
-                                (**env).ExceptionClear.unwrap()(env);
-                            }
+                            #exception_check
                         };
                         #out_conv_code
-                        ret
+                        #ret_value
                     }
-                });  
+                });
             }
         }
     }
@@ -371,6 +1189,29 @@ impl SwigFrom<jobject> for Box<dyn {trait_name}> {{
     Ok(())
 }
 
+fn callback_exception_check(policy: JavaCallbackExceptionPolicy, func_name: &Ident) -> TokenStream {
+    match policy {
+        JavaCallbackExceptionPolicy::Log => quote! {
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                log::error!(concat!(stringify!(#func_name), ": java throw exception"));
+                (**env).ExceptionDescribe.unwrap()(env);
+                (**env).ExceptionClear.unwrap()(env);
+            }
+        },
+        JavaCallbackExceptionPolicy::Panic => quote! {
+            if let Some(ex) = jni_take_pending_exception(env) {
+                panic!(concat!(stringify!(#func_name), ": java throw exception: {}"), ex);
+            }
+        },
+        JavaCallbackExceptionPolicy::Abort => quote! {
+            if let Some(ex) = jni_take_pending_exception(env) {
+                log::error!(concat!(stringify!(#func_name), ": java throw exception: {}"), ex);
+                ::std::process::abort();
+            }
+        },
+    }
+}
+
 lazy_static! {
     static ref JNI_FOR_VARIADIC_C_FUNC_CALL: FxHashMap<&'static str, &'static str> = {
         let mut m = FxHashMap::default();
@@ -1,7 +1,13 @@
 use bitflags::bitflags;
 
+use std::fmt::Write;
+
 use super::{JniForeignMethodSignature, NullAnnotation};
-use crate::WRITE_TO_MEM_FAILED_MSG;
+use crate::{
+    doc_comments::{convert_doc_comments, find_link_target, DocMarkup},
+    typemap::TypeMap,
+    WRITE_TO_MEM_FAILED_MSG,
+};
 
 bitflags! {
     pub(in crate::java_jni) struct ArgsFormatFlags: u8 {
@@ -19,8 +25,6 @@ pub(in crate::java_jni) fn args_with_java_types<'a, NI: Iterator<Item = &'a str>
     flags: ArgsFormatFlags,
     use_null_annotation: bool,
 ) -> String {
-    use std::fmt::Write;
-
     assert!(flags.contains(ArgsFormatFlags::INTERNAL) || flags.contains(ArgsFormatFlags::EXTERNAL));
 
     let mut res = String::new();
@@ -61,36 +65,39 @@ pub(in crate::java_jni) fn args_with_java_types<'a, NI: Iterator<Item = &'a str>
     res
 }
 
+/// Javadoc comments, intra-doc links are resolved to `{@link Class#method}`
 pub(in crate::java_jni) fn doc_comments_to_java_comments(
+    conv_map: &TypeMap,
     doc_comments: &[String],
     class_comments: bool,
 ) -> String {
-    use std::fmt::Write;
-    let mut comments = String::new();
-    for (i, comment) in doc_comments.iter().enumerate() {
-        if i != 0 {
-            comments.push('\n');
-        }
-        if !class_comments {
-            comments.push_str("    ");
-        }
-        if i == 0 {
-            comments.push_str("/**\n");
-            if !class_comments {
-                comments.push_str("    ");
-            }
-        }
-
-        write!(&mut comments, " * {}", comment.trim()).unwrap();
+    let resolve_link = |link: &str| {
+        find_link_target(conv_map.foreign_classes(), link).map(|(class, member)| match member {
+            Some(member) => format!("{}#{}", class.name, member),
+            None => class.name.to_string(),
+        })
+    };
+    let lines = convert_doc_comments(doc_comments, DocMarkup::Javadoc, &resolve_link);
+    format_doc_comments(&lines, class_comments)
+}
 
-        if i == doc_comments.len() - 1 {
-            comments.push('\n');
-            if !class_comments {
-                comments.push_str("    ");
-            }
-            comments.push_str(" */");
+/// Wrap lines into `/** ... */` block
+pub(in crate::java_jni) fn format_doc_comments(lines: &[String], class_comments: bool) -> String {
+    if lines.is_empty() {
+        return String::new();
+    }
+    let indent = if class_comments { "" } else { "    " };
+    let mut comments = format!("{}/**\n", indent);
+    for line in lines {
+        comments.push_str(indent);
+        comments.push_str(" *");
+        if !line.is_empty() {
+            write!(&mut comments, " {}", line).expect(WRITE_TO_MEM_FAILED_MSG);
         }
+        comments.push('\n');
     }
+    comments.push_str(indent);
+    comments.push_str(" */");
     comments
 }
 
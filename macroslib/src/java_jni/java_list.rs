@@ -0,0 +1,544 @@
+use proc_macro2::Span;
+use quote::quote;
+use std::io::Write;
+
+use super::{
+    java_class_full_name, java_class_name_to_jni, kotlin_code, map_write_err, merge_rule,
+    object_cleanup, proguard, register_natives, rust_code, JavaContext, JavaForeignTypeInfo,
+    JniForeignMethodSignature, JAVA_RUST_SELF_NAME,
+};
+use crate::{
+    error::{invalid_src_id_span, panic_on_syn_error, DiagnosticError, Result},
+    file_cache::FileWriteCache,
+    source_registry::SourceId,
+    typemap::ForeignTypeInfo,
+    types::MethodVariant,
+    JavaConfig, JavaVecMapping, WRITE_TO_MEM_FAILED_MSG,
+};
+
+const RUST_LIST_CLASS: &str = "RustList";
+
+/// Rust type, internal alias for JNI type, Java boxed type
+const PRIMITIVE_LIST_ELEMENTS: [(&str, &str, &str); 6] = [
+    ("i8", "JByteList", "Byte"),
+    ("i16", "JShortList", "Short"),
+    ("i32", "JIntegerList", "Integer"),
+    ("i64", "JLongList", "Long"),
+    ("f32", "JFloatList", "Float"),
+    ("f64", "JDoubleList", "Double"),
+];
+
+pub(in crate::java_jni) fn use_java_list(cfg: &JavaConfig) -> bool {
+    cfg.vec_mapping != JavaVecMapping::Array
+}
+
+/// Generate typemaps to map `Vec<T>` and `&[T]` to `java.util.List`,
+/// plus `RustList` class for `JavaVecMapping::LazyList`
+pub(in crate::java_jni) fn generate_java_list_support(ctx: &mut JavaContext) -> Result<()> {
+    ctx.java_type_to_jni_sig_map
+        .insert("java.util.List".into(), "Ljava.util.List;".into());
+
+    let lazy = ctx.cfg.vec_mapping == JavaVecMapping::LazyList;
+    let rust_list_code = if lazy {
+        generate_rust_list(ctx)?
+    } else {
+        String::new()
+    };
+    let helper_code = format!(
+        "{}{}{}",
+        LIST_ELEMENTS_CODE, VEC_TO_ARRAY_LIST_CODE, rust_list_code
+    );
+    ctx.rust_code
+        .push(syn::parse_str(&helper_code).unwrap_or_else(|err| {
+            panic_on_syn_error("java/jni internal java.util.List", helper_code, err)
+        }));
+
+    let vec_to_list = if lazy {
+        "vec_to_java_list"
+    } else {
+        "vec_to_java_array_list"
+    };
+    // elements of `RustList` are cloned on access,
+    // so if `T` is not `Clone` fallback to copying of `Vec<T>`
+    let mut conv_code = String::new();
+    let out_rules: &[(&str, &str)] = if lazy {
+        &[
+            ("SwigForeignClass + Clone", vec_to_list),
+            ("SwigForeignClass", "vec_to_java_array_list"),
+        ]
+    } else {
+        &[("SwigForeignClass", vec_to_list)]
+    };
+    for (out_bounds, out_vec_to_list) in out_rules {
+        conv_code.push_str(&format!(
+            r#"
+foreign_typemap!(
+    ($p:r_type) <T: {out_bounds}> Vec<T> => internal_aliases::JForeignObjectsList<T> {{
+        $out = internal_aliases::JForeignObjectsList {{
+            inner: {out_vec_to_list}(env, $p),
+            _marker: ::std::marker::PhantomData,
+        }};
+    }};
+    ($p:f_type, option = "NoNullAnnotations") => "java.util.List<swig_f_type!(T)>";
+    ($p:f_type, option = "NullAnnotations")
+        => "@NonNull java.util.List<swig_f_type!(T, NoNullAnnotations)>";
+    ($p:f_type, option = "Kotlin") => "@NonNull java.util.List<swig_f_type!(T, NoNullAnnotations)>";
+);
+"#,
+            out_bounds = out_bounds,
+            out_vec_to_list = out_vec_to_list,
+        ));
+    }
+    conv_code.push_str(
+        r#"
+foreign_typemap!(
+    ($p:r_type) <T: SwigForeignClass + Clone> Vec<T> <= internal_aliases::JForeignObjectsList<T> {
+        $out = java_list_to_vec(env, $p.inner);
+    };
+    ($p:f_type, option = "NoNullAnnotations") <= "java.util.List<? extends swig_f_type!(T)>";
+    ($p:f_type, option = "NullAnnotations")
+        <= "@NonNull java.util.List<? extends swig_f_type!(T, NoNullAnnotations)>";
+    ($p:f_type, option = "Kotlin")
+        <= "@NonNull java.util.List<? extends swig_f_type!(T, NoNullAnnotations)>";
+);
+foreign_typemap!(
+    ($p:r_type) <T: SwigForeignClass + Clone> &[T] <= internal_aliases::JForeignObjectsList<T> {
+        let vec: Vec<_> = java_list_to_vec(env, $p.inner);
+        $out = &vec[..];
+    };
+    ($p:f_type, option = "NoNullAnnotations", unique_prefix = "/*slice*/")
+        <= "/*slice*/java.util.List<? extends swig_f_type!(T)>";
+    ($p:f_type, option = "NullAnnotations", unique_prefix = "/*slice*/")
+        <= "/*slice*/@NonNull java.util.List<? extends swig_f_type!(T, NoNullAnnotations)>";
+    ($p:f_type, option = "Kotlin", unique_prefix = "/*slice*/")
+        <= "/*slice*/@NonNull java.util.List<? extends swig_f_type!(T, NoNullAnnotations)>";
+);
+"#,
+    );
+    for (rust_ty, alias, java_ty) in
+        std::iter::once(&("String", "JStringList", "String")).chain(PRIMITIVE_LIST_ELEMENTS.iter())
+    {
+        conv_code.push_str(&format!(
+            r#"
+foreign_typemap!(
+    ($p:r_type) Vec<{rust_ty}> => internal_aliases::{alias} {{
+        $out = {vec_to_list}(env, $p);
+    }};
+    ($p:f_type, option = "NoNullAnnotations") => "java.util.List<{java_ty}>";
+    ($p:f_type, option = "NullAnnotations") => "@NonNull java.util.List<{java_ty}>";
+    ($p:f_type, option = "Kotlin") => "@NonNull java.util.List<{java_ty}>";
+);
+foreign_typemap!(
+    ($p:r_type) Vec<{rust_ty}> <= internal_aliases::{alias} {{
+        $out = java_list_to_vec(env, $p);
+    }};
+    ($p:f_type, option = "NoNullAnnotations") <= "java.util.List<? extends {java_ty}>";
+    ($p:f_type, option = "NullAnnotations") <= "@NonNull java.util.List<? extends {java_ty}>";
+    ($p:f_type, option = "Kotlin") <= "@NonNull java.util.List<? extends {java_ty}>";
+);
+foreign_typemap!(
+    ($p:r_type) &[{rust_ty}] <= internal_aliases::{alias} {{
+        let vec: Vec<{rust_ty}> = java_list_to_vec(env, $p);
+        $out = vec.as_slice();
+    }};
+);
+"#,
+            rust_ty = rust_ty,
+            alias = alias,
+            java_ty = java_ty,
+            vec_to_list = vec_to_list,
+        ));
+    }
+    ctx.conv_map
+        .merge(SourceId::none(), &conv_code, ctx.pointer_target_width)?;
+    for rule in ctx.conv_map.take_not_merged_not_generic_rules() {
+        merge_rule(ctx, rule)?;
+    }
+    Ok(())
+}
+
+const LIST_ELEMENTS_CODE: &str = r#"
+/// Element of `Vec<T>`, that can be converted to element of `java.util.List`
+trait SwigIntoJavaListElement {
+    fn swig_into_java_list_element(self, env: *mut JNIEnv) -> jobject;
+}
+
+impl<T: SwigForeignClass> SwigIntoJavaListElement for T {
+    fn swig_into_java_list_element(self, env: *mut JNIEnv) -> jobject {
+        object_to_jobject(env, self)
+    }
+}
+
+impl SwigIntoJavaListElement for String {
+    fn swig_into_java_list_element(self, env: *mut JNIEnv) -> jobject {
+        from_std_string_jstring(self, env)
+    }
+}
+
+/// Element of `java.util.List`, that can be converted to element of `Vec<T>`
+trait SwigFromJavaListElement: Sized {
+    fn swig_from_java_list_element(env: *mut JNIEnv, obj: jobject) -> Self;
+}
+
+impl<T: SwigForeignClass + Clone> SwigFromJavaListElement for T {
+    fn swig_from_java_list_element(env: *mut JNIEnv, obj: jobject) -> Self {
+        assert!(!obj.is_null(), "null element in java.util.List");
+        let field_id = <T>::jni_class_pointer_field();
+        assert!(!field_id.is_null());
+        let native: &T = unsafe {
+            let ptr = (**env).GetLongField.unwrap()(env, obj, field_id);
+            (jlong_to_pointer(ptr) as *mut T).as_ref().unwrap()
+        };
+        native.clone()
+    }
+}
+
+impl SwigFromJavaListElement for String {
+    fn swig_from_java_list_element(env: *mut JNIEnv, obj: jobject) -> Self {
+        assert!(!obj.is_null(), "null element in java.util.List");
+        JavaString::new(env, obj).to_str().to_string()
+    }
+}
+
+macro_rules! impl_java_list_element_for_primitive {
+    ($($rust_ty:ty, $to_java:ident, $from_java:ident);*) => {
+        $(
+            impl SwigIntoJavaListElement for $rust_ty {
+                fn swig_into_java_list_element(self, env: *mut JNIEnv) -> jobject {
+                    $to_java(env, Some(self))
+                }
+            }
+            impl SwigFromJavaListElement for $rust_ty {
+                fn swig_from_java_list_element(env: *mut JNIEnv, obj: jobject) -> Self {
+                    $from_java(env, obj).expect("null element in java.util.List")
+                }
+            }
+        )*
+    };
+}
+
+impl_java_list_element_for_primitive!(
+    i8, from_rust_to_java_lang_byte, from_java_lang_byte_to_rust;
+    i16, from_rust_to_java_lang_short, from_java_lang_short_to_rust;
+    i32, from_rust_to_java_lang_int, from_java_lang_int_to_rust;
+    i64, from_rust_to_java_lang_long, from_java_lang_long_to_rust;
+    f32, from_rust_to_java_lang_float, from_java_lang_float_to_rust;
+    f64, from_rust_to_java_lang_double, from_java_lang_double_to_rust
+);
+
+#[allow(dead_code)]
+fn java_list_to_vec<T: SwigFromJavaListElement>(env: *mut JNIEnv, list: jobject) -> Vec<T> {
+    assert!(!list.is_null(), "null instead of java.util.List");
+    let class: jclass = swig_jni_find_class!(JAVA_UTIL_LIST, "java/util/List");
+    assert!(!class.is_null());
+    let size_m: jmethodID =
+        swig_jni_get_method_id!(JAVA_UTIL_LIST_SIZE, JAVA_UTIL_LIST, "size", "()I");
+    assert!(!size_m.is_null());
+    let get_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_UTIL_LIST_GET,
+        JAVA_UTIL_LIST,
+        "get",
+        "(I)Ljava/lang/Object;"
+    );
+    assert!(!get_m.is_null());
+    let size: jint = unsafe {
+        let size = (**env).CallIntMethod.unwrap()(env, list, size_m);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            panic!("java.util.List.size failed: catch exception");
+        }
+        size
+    };
+    let len = <usize as ::std::convert::TryFrom<jint>>::try_from(size)
+        .expect("invalid jint, in jint => usize conversation");
+    let mut result = Vec::with_capacity(len);
+    for i in 0..size {
+        let elem: jobject = unsafe {
+            let elem = (**env).CallObjectMethod.unwrap()(env, list, get_m, i);
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                panic!("java.util.List.get({}) failed: catch exception", i);
+            }
+            elem
+        };
+        result.push(T::swig_from_java_list_element(env, elem));
+        unsafe { (**env).DeleteLocalRef.unwrap()(env, elem) };
+    }
+    result
+}
+"#;
+
+const VEC_TO_ARRAY_LIST_CODE: &str = r#"
+#[allow(dead_code)]
+fn vec_to_java_array_list<T: SwigIntoJavaListElement>(env: *mut JNIEnv, arr: Vec<T>) -> jobject {
+    let class: jclass = swig_jni_find_class!(JAVA_UTIL_ARRAY_LIST, "java/util/ArrayList");
+    assert!(!class.is_null());
+    let constructor: jmethodID = swig_jni_get_method_id!(
+        JAVA_UTIL_ARRAY_LIST_CONSTRUCTOR,
+        JAVA_UTIL_ARRAY_LIST,
+        "<init>",
+        "(I)V"
+    );
+    assert!(!constructor.is_null());
+    let add_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_UTIL_ARRAY_LIST_ADD,
+        JAVA_UTIL_ARRAY_LIST,
+        "add",
+        "(Ljava/lang/Object;)Z"
+    );
+    assert!(!add_m.is_null());
+    let capacity = <jint as ::std::convert::TryFrom<usize>>::try_from(arr.len())
+        .expect("invalid usize, in usize => to jint conversation");
+    let list: jobject = unsafe { (**env).NewObject.unwrap()(env, class, constructor, capacity) };
+    assert!(!list.is_null(), "Can not create java.util.ArrayList object");
+    for (i, elem) in arr.into_iter().enumerate() {
+        let elem: jobject = elem.swig_into_java_list_element(env);
+        unsafe {
+            (**env).CallBooleanMethod.unwrap()(env, list, add_m, elem);
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                panic!("java.util.ArrayList.add({}) failed: catch exception", i);
+            }
+            (**env).DeleteLocalRef.unwrap()(env, elem);
+        }
+    }
+    list
+}
+"#;
+
+/// Generate Java class `RustList`, that owns `Vec<T>` returned from Rust
+/// and converts its elements to Java objects on access,
+/// return Rust code to create it
+fn generate_rust_list(ctx: &mut JavaContext) -> Result<String> {
+    generate_java_class(ctx).map_err(DiagnosticError::map_any_err_to_our_err)?;
+
+    let class_full_name = java_class_full_name(&ctx.cfg.package_name, RUST_LIST_CLASS);
+    ctx.java_type_to_jni_sig_map.insert(
+        RUST_LIST_CLASS.into(),
+        format!("L{};", class_full_name).into(),
+    );
+    proguard::keep_method(ctx, RUST_LIST_CLASS, "<init>", "(JI)V");
+
+    let jlong_type = ctx.conv_map.ty_to_rust_type(&parse_type! { jlong });
+    let jint_type = ctx.conv_map.ty_to_rust_type(&parse_type! { jint });
+    let jobject_type = ctx
+        .conv_map
+        .find_or_alloc_rust_type_no_src_id(&parse_type! { jobject });
+    let dummy_rust_ty = ctx
+        .conv_map
+        .find_or_alloc_rust_type_no_src_id(&parse_type! { () });
+    let arg = |name: &str, rust_ty| JavaForeignTypeInfo {
+        base: ForeignTypeInfo {
+            name: name.into(),
+            correspoding_rust_type: rust_ty,
+        },
+        java_converter: None,
+        annotation: None,
+        unsigned_check: None,
+    };
+    let get_sign = JniForeignMethodSignature {
+        output: arg("Object", jobject_type),
+        input: vec![arg("long", jlong_type.clone()), arg("int", jint_type)],
+    };
+    let destructor_sign = JniForeignMethodSignature {
+        output: arg("", dummy_rust_ty),
+        input: vec![arg("long", jlong_type)],
+    };
+    let mut native_func_name = |java_method_name: &str, sign: &JniForeignMethodSignature| {
+        let name = rust_code::generate_jni_func_name(
+            ctx,
+            RUST_LIST_CLASS,
+            invalid_src_id_span(),
+            java_method_name,
+            MethodVariant::StaticMethod,
+            sign,
+            false,
+        )?;
+        register_natives::remember_native_method(
+            ctx,
+            RUST_LIST_CLASS,
+            java_method_name,
+            MethodVariant::StaticMethod,
+            sign,
+            &name,
+        );
+        Ok::<_, DiagnosticError>(syn::Ident::new(&name, Span::call_site()))
+    };
+    let jni_get_name = native_func_name("do_get", &get_sign)?;
+    let jni_destructor_name = native_func_name("do_delete", &destructor_sign)?;
+    let linkage = register_natives::jni_func_linkage_tokens(ctx.cfg);
+    ctx.rust_code.push(quote! {
+        #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
+        #linkage extern "C" fn #jni_get_name(
+            env: *mut JNIEnv,
+            _: jclass,
+            this: jlong,
+            index: jint,
+        ) -> jobject {
+            let this: &Box<dyn SwigJavaListView> =
+                unsafe { jlong_to_pointer::<Box<dyn SwigJavaListView>>(this).as_ref().unwrap() };
+            let index = <usize as ::std::convert::TryFrom<jint>>::try_from(index)
+                .expect("invalid jint, in jint => usize conversation");
+            this.swig_list_element(env, index)
+        }
+    });
+    ctx.rust_code.push(quote! {
+        #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
+        #linkage extern "C" fn #jni_destructor_name(env: *mut JNIEnv, _: jclass, this: jlong) {
+            let this: Box<Box<dyn SwigJavaListView>> =
+                unsafe { Box::from_raw(jlong_to_pointer::<Box<dyn SwigJavaListView>>(this)) };
+            drop(this);
+        }
+    });
+
+    Ok(format!(
+        r#"
+/// `Vec<T>` owned by Java's `{class_name}`
+trait SwigJavaListView {{
+    fn swig_list_element(&self, env: *mut JNIEnv, index: usize) -> jobject;
+}}
+
+impl<T: SwigIntoJavaListElement + Clone> SwigJavaListView for Vec<T> {{
+    fn swig_list_element(&self, env: *mut JNIEnv, index: usize) -> jobject {{
+        self[index].clone().swig_into_java_list_element(env)
+    }}
+}}
+
+#[allow(dead_code)]
+fn vec_to_java_list<T: SwigIntoJavaListElement + Clone + 'static>(
+    env: *mut JNIEnv,
+    arr: Vec<T>,
+) -> jobject {{
+    let size = <jint as ::std::convert::TryFrom<usize>>::try_from(arr.len())
+        .expect("invalid usize, in usize => to jint conversation");
+    let view: Box<Box<dyn SwigJavaListView>> = Box::new(Box::new(arr));
+    let class: jclass = swig_jni_find_class!(FOREIGN_CLASS_RUSTLIST, "{class_for_jni}");
+    assert!(!class.is_null());
+    let constructor: jmethodID = swig_jni_get_method_id!(
+        FOREIGN_CLASS_RUSTLIST_CONSTRUCTOR,
+        FOREIGN_CLASS_RUSTLIST,
+        "<init>",
+        "(JI)V"
+    );
+    assert!(!constructor.is_null());
+    let ptr = Box::into_raw(view) as jlong;
+    let obj: jobject = unsafe {{ (**env).NewObject.unwrap()(env, class, constructor, ptr, size) }};
+    assert!(!obj.is_null(), "Can not create {class_name} object");
+    obj
+}}
+"#,
+        class_for_jni = java_class_name_to_jni(&class_full_name),
+        class_name = RUST_LIST_CLASS,
+    ))
+}
+
+fn generate_java_class(ctx: &mut JavaContext) -> std::result::Result<(), String> {
+    if kotlin_code::use_kotlin(ctx.cfg) {
+        return generate_kotlin_class(ctx);
+    }
+    let path = ctx.cfg.output_dir.join(format!("{}.java", RUST_LIST_CLASS));
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+    let implements = object_cleanup::class_implements(ctx.cfg);
+    writeln!(
+        file,
+        r#"// Automatically generated by flapigen
+package {package_name};
+
+/**
+ * Read-only view of vector allocated by Rust code,
+ * elements are converted to Java objects on access.
+ * List is valid until {{@link #delete}} call
+ */
+public final class {class_name}<T> extends java.util.AbstractList<T>
+    implements java.util.RandomAccess{implements} {{
+    /*package*/ {class_name}(long ptr, int size) {{
+        this.{rust_self_name} = ptr;
+        this.size = size;{register_cleanup}
+    }}
+
+    @Override
+    public synchronized T get(int index) {{
+        if ({rust_self_name} == 0) {{
+            throw new IllegalStateException("{class_name} already deleted");
+        }}
+        if (index < 0 || index >= size) {{
+            throw new IndexOutOfBoundsException("Index: " + index + ", Size: " + size);
+        }}
+        @SuppressWarnings("unchecked")
+        T ret = (T) do_get({rust_self_name}, index);
+        return ret;
+    }}
+
+    @Override
+    public int size() {{
+        return size;
+    }}
+{delete_methods}
+    private static native Object do_get(long self, int index);
+
+    private long {rust_self_name};
+    private final int size;
+}}"#,
+        package_name = ctx.cfg.package_name,
+        class_name = RUST_LIST_CLASS,
+        rust_self_name = JAVA_RUST_SELF_NAME,
+        implements = implements.replace(" implements ", ", "),
+        register_cleanup = object_cleanup::register_cleanup_call(ctx.cfg),
+        delete_methods = object_cleanup::generate_delete_methods(ctx.cfg, ""),
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+fn generate_kotlin_class(ctx: &mut JavaContext) -> std::result::Result<(), String> {
+    let path = ctx.cfg.output_dir.join(format!("{}.kt", RUST_LIST_CLASS));
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+    let (delete_methods, delete_companion) =
+        object_cleanup::generate_kotlin_delete_methods(ctx.cfg, "");
+    writeln!(
+        file,
+        r#"// Automatically generated by flapigen
+package {package_name}
+
+/**
+ * Read-only view of vector allocated by Rust code,
+ * elements are converted to Kotlin objects on access.
+ * List is valid until [delete] call
+ */
+class {class_name}<T> : java.util.AbstractList<T>, java.util.RandomAccess, java.io.Closeable {{
+    internal constructor(ptr: Long, size: Int) : super() {{
+        {rust_self_name} = ptr
+        mSize = size{register_cleanup}
+    }}
+
+    @Synchronized
+    override fun get(index: Int): T {{
+        check({rust_self_name} != 0L) {{ "{class_name} already deleted" }}
+        if (index < 0 || index >= mSize) {{
+            throw IndexOutOfBoundsException("Index: $index, Size: $mSize")
+        }}
+        @Suppress("UNCHECKED_CAST")
+        return do_get({rust_self_name}, index) as T
+    }}
+
+    override val size: Int
+        get() = mSize
+{delete_methods}
+    @JvmField
+    internal var {rust_self_name}: Long = 0L
+    private val mSize: Int
+
+    companion object {{
+        @JvmStatic
+        private external fun do_get(self: Long, index: Int): Any?
+{delete_companion}    }}
+}}"#,
+        package_name = ctx.cfg.package_name,
+        class_name = RUST_LIST_CLASS,
+        rust_self_name = JAVA_RUST_SELF_NAME,
+        register_cleanup = object_cleanup::register_cleanup_call(ctx.cfg),
+        delete_methods = delete_methods,
+        delete_companion = kotlin_code::indent_for_companion(&delete_companion),
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
@@ -23,8 +23,36 @@ mod internal_aliases {
         pub(crate) inner: jobjectArray,
         pub(crate) _marker: ::std::marker::PhantomData<T>,
     }
+    impl<T: SwigForeignClass> JForeignObjectsArray<T> {
+        pub(crate) fn is_null(&self) -> bool {
+            self.inner.is_null()
+        }
+    }
     pub type JStringPath = jstring;
     pub type JStringObjectsArray = jobjectArray;
+    pub type JByteArrayU8 = jbyteArray;
+    pub type JDirectByteBuffer = jobject;
+    pub type JRustByteBuffer = jobject;
+    pub type JBigInteger = jobject;
+    pub type JBigIntegerOpt = jobject;
+    pub type JBigIntegerArray = jobjectArray;
+    #[repr(transparent)]
+    pub struct JForeignObjectsList<T: SwigForeignClass> {
+        pub(crate) inner: jobject,
+        pub(crate) _marker: ::std::marker::PhantomData<T>,
+    }
+    impl<T: SwigForeignClass> JForeignObjectsList<T> {
+        pub(crate) fn is_null(&self) -> bool {
+            self.inner.is_null()
+        }
+    }
+    pub type JStringList = jobject;
+    pub type JByteList = jobject;
+    pub type JShortList = jobject;
+    pub type JIntegerList = jobject;
+    pub type JLongList = jobject;
+    pub type JFloatList = jobject;
+    pub type JDoubleList = jobject;
 }
 
 /// Default JNI_VERSION
@@ -154,6 +182,7 @@ foreign_typemap!(
     (r_type) jstring;
     (f_type, option = "NoNullAnnotations") "String";
     (f_type, option = "NullAnnotations") "@NonNull String";
+    (f_type, option = "Kotlin") "@NonNull String";
 );
 
 #[allow(dead_code)]
@@ -164,6 +193,8 @@ pub trait SwigForeignClass {
     fn box_object(x: Self) -> jlong;
     fn unbox_object(x: jlong) -> Self;
     fn to_pointer(x: jlong) -> ::std::ptr::NonNull<Self::PointedType>;
+    /// Called for Java object created via `AllocObject`, after pointer field was set
+    fn jni_init_object(_env: *mut JNIEnv, _obj: jobject) {}
 }
 
 #[allow(dead_code)]
@@ -272,6 +303,16 @@ impl JavaCallback {
         }
     }
 
+    /// Remember address of heap allocated callback,
+    /// so `java_callback_object` can find Java object by it
+    fn register(&self) {
+        SWIG_JAVA_CALLBACKS
+            .lock()
+            .unwrap()
+            .get_or_insert_with(Default::default)
+            .insert(self as *const JavaCallback as usize);
+    }
+
     fn get_jni_env(&self) -> JniEnvHolder {
         assert!(!self.java_vm.is_null());
         let mut env: *mut JNIEnv = ::std::ptr::null_mut();
@@ -344,6 +385,9 @@ impl JavaCallback {
 #[allow(dead_code)]
 impl Drop for JavaCallback {
     fn drop(&mut self) {
+        if let Some(callbacks) = SWIG_JAVA_CALLBACKS.lock().unwrap().as_mut() {
+            callbacks.remove(&(self as *const JavaCallback as usize));
+        }
         let env = self.get_jni_env();
         if let Some(env) = env.env {
             assert!(!env.is_null());
@@ -354,6 +398,105 @@ impl Drop for JavaCallback {
     }
 }
 
+/// Addresses of `JavaCallback` owned by `Box<dyn Trait>` or `Arc<dyn Trait>`,
+/// to return Java object back instead of wrapping it into Rust implementation
+#[allow(dead_code)]
+static SWIG_JAVA_CALLBACKS: ::std::sync::Mutex<Option<::std::collections::HashSet<usize>>> =
+    ::std::sync::Mutex::new(None);
+
+/// If `obj` is address of registered `JavaCallback`,
+/// return local reference to Java object that it wraps
+#[allow(dead_code)]
+fn java_callback_object(env: *mut JNIEnv, obj: *const ::std::os::raw::c_void) -> Option<jobject> {
+    let registered = SWIG_JAVA_CALLBACKS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or(false, |callbacks| callbacks.contains(&(obj as usize)));
+    if !registered {
+        return None;
+    }
+    let callback: &JavaCallback = unsafe { &*(obj as *const JavaCallback) };
+    let ret: jobject = unsafe { (**env).NewLocalRef.unwrap()(env, callback.this) };
+    assert!(!ret.is_null(), "java_callback_object: NewLocalRef failed");
+    Some(ret)
+}
+
+/// Rust implementation of callback trait, owned by Java object
+#[allow(dead_code)]
+enum JavaRustImpl<T: ?Sized> {
+    Boxed(Box<T>),
+    Shared(::std::sync::Arc<T>),
+}
+
+#[allow(dead_code)]
+impl<T: ?Sized> JavaRustImpl<T> {
+    fn get(&self) -> &T {
+        match self {
+            JavaRustImpl::Boxed(x) => &**x,
+            JavaRustImpl::Shared(x) => &**x,
+        }
+    }
+    fn get_mut(&mut self) -> &mut T {
+        match self {
+            JavaRustImpl::Boxed(x) => &mut **x,
+            JavaRustImpl::Shared(_) => panic!("JavaRustImpl: shared implementation is immutable"),
+        }
+    }
+    /// Java object of class `class`, created via `constructor` with pointer to `self`,
+    /// or Java object itself if `self` wraps Java implementation
+    fn into_jobject(self, env: *mut JNIEnv, class: jclass, constructor: jmethodID) -> jobject {
+        let data = self.get() as *const T as *const ::std::os::raw::c_void;
+        if let Some(obj) = java_callback_object(env, data) {
+            return obj;
+        }
+        assert!(!class.is_null());
+        assert!(!constructor.is_null());
+        let ptr = Box::into_raw(Box::new(self)) as jlong;
+        let obj: jobject = unsafe { (**env).NewObject.unwrap()(env, class, constructor, ptr) };
+        assert!(!obj.is_null(), "JavaRustImpl: can not create Java object");
+        obj
+    }
+    /// Take Rust implementation from Java object created by `into_jobject`,
+    /// `Box` is moved out via `release` method, `Arc` is cloned.
+    /// Return `None` if `obj` is not instance of `class`
+    fn from_jobject(
+        env: *mut JNIEnv,
+        obj: jobject,
+        class: jclass,
+        field: jfieldID,
+        release: jmethodID,
+    ) -> Option<Self> {
+        assert!(!class.is_null());
+        assert!(!field.is_null());
+        assert!(!release.is_null());
+        if unsafe { (**env).IsInstanceOf.unwrap()(env, obj, class) } == 0 {
+            return None;
+        }
+        // the same lock as `synchronized` methods of Java object use
+        let res = unsafe { (**env).MonitorEnter.unwrap()(env, obj) };
+        assert_eq!(0, res, "JavaRustImpl: MonitorEnter failed");
+        let ptr: jlong = unsafe { (**env).GetLongField.unwrap()(env, obj, field) };
+        let ret = if ptr == 0 {
+            None
+        } else {
+            let this: &JavaRustImpl<T> = unsafe { jlong_to_pointer::<Self>(ptr).as_ref().unwrap() };
+            match this {
+                JavaRustImpl::Shared(x) => Some(JavaRustImpl::Shared(x.clone())),
+                JavaRustImpl::Boxed(_) => {
+                    let ptr: jlong = unsafe { (**env).CallLongMethod.unwrap()(env, obj, release) };
+                    if unsafe { (**env).ExceptionCheck.unwrap()(env) } != 0 {
+                        panic!("JavaRustImpl: can not release Java object: catch exception");
+                    }
+                    Some(*unsafe { Box::from_raw(jlong_to_pointer::<Self>(ptr)) })
+                }
+            }
+        };
+        unsafe { (**env).MonitorExit.unwrap()(env, obj) };
+        Some(ret.expect("JavaRustImpl: Java object already deleted"))
+    }
+}
+
 #[allow(dead_code)]
 fn jni_throw(env: *mut JNIEnv, ex_class: jclass, message: &str) {
     let c_message = ::std::ffi::CString::new(message).unwrap();
@@ -373,6 +516,188 @@ fn jni_throw_exception(env: *mut JNIEnv, message: &str) {
     jni_throw(env, exception_class, message)
 }
 
+#[allow(dead_code)]
+fn jni_throw_null_pointer_exception(env: *mut JNIEnv, message: &str) {
+    let exception_class = swig_jni_find_class!(
+        JAVA_LANG_NULL_POINTER_EXCEPTION,
+        "java/lang/NullPointerException"
+    );
+    jni_throw(env, exception_class, message)
+}
+
+/// Rust error that can be thrown as Java exception,
+/// implemented for types registered via `JavaConfig::register_exception`
+#[allow(dead_code)]
+pub trait SwigJavaException: ::std::error::Error {
+    /// Java class for this error and its constructor `(String, Throwable)`,
+    /// or `(String, Throwable, int)` if there is error code
+    fn java_exception_class(&self) -> (jclass, jmethodID);
+    /// Value for `getErrorCode()` of exception
+    fn java_error_code(&self) -> Option<jint> {
+        None
+    }
+}
+
+#[allow(dead_code)]
+fn jni_new_exception(
+    env: *mut JNIEnv,
+    ex_class: jclass,
+    ex_constructor: jmethodID,
+    err: &dyn ::std::error::Error,
+    cause: jthrowable,
+    error_code: Option<jint>,
+) -> jthrowable {
+    assert!(!ex_class.is_null());
+    assert!(!ex_constructor.is_null());
+    let message = from_std_string_jstring(err.to_string(), env);
+    let ex = match error_code {
+        Some(code) => unsafe {
+            (**env).NewObject.unwrap()(env, ex_class, ex_constructor, message, cause, code)
+        },
+        None => unsafe {
+            (**env).NewObject.unwrap()(env, ex_class, ex_constructor, message, cause)
+        },
+    };
+    unsafe { (**env).DeleteLocalRef.unwrap()(env, message) };
+    ex
+}
+
+/// Convert chain of `Error::source` to chain of `java.lang.Exception`
+#[allow(dead_code)]
+fn jni_error_source_to_throwable(
+    env: *mut JNIEnv,
+    err: Option<&(dyn ::std::error::Error + 'static)>,
+) -> jthrowable {
+    let err = match err {
+        Some(err) => err,
+        None => return ::std::ptr::null_mut(),
+    };
+    let cause = jni_error_source_to_throwable(env, err.source());
+    let ex_class = swig_jni_find_class!(JAVA_LANG_EXCEPTION, "java/lang/Exception");
+    let ex_constructor = swig_jni_get_method_id!(
+        JAVA_LANG_EXCEPTION_CONSTRUCTOR,
+        JAVA_LANG_EXCEPTION,
+        "<init>",
+        "(Ljava/lang/String;Ljava/lang/Throwable;)V"
+    );
+    let ex = jni_new_exception(env, ex_class, ex_constructor, err, cause, None);
+    if !cause.is_null() {
+        unsafe { (**env).DeleteLocalRef.unwrap()(env, cause) };
+    }
+    ex
+}
+
+#[allow(dead_code)]
+fn jni_throw_error<E: SwigJavaException>(env: *mut JNIEnv, err: E) {
+    let (ex_class, ex_constructor) = err.java_exception_class();
+    let cause = jni_error_source_to_throwable(env, err.source());
+    let ex = jni_new_exception(
+        env,
+        ex_class,
+        ex_constructor,
+        &err,
+        cause,
+        err.java_error_code(),
+    );
+    if !cause.is_null() {
+        unsafe { (**env).DeleteLocalRef.unwrap()(env, cause) };
+    }
+    if ex.is_null() {
+        log::error!("JNI NewObject failed for exception of error '{}'", err);
+        return;
+    }
+    let res = unsafe { (**env).Throw.unwrap()(env, ex) };
+    if res != 0 {
+        log::error!("JNI Throw failed for exception of error '{}'", err);
+    }
+    unsafe { (**env).DeleteLocalRef.unwrap()(env, ex) };
+}
+
+/// Java exception thrown by callback method,
+/// callback methods that return `Result<T, E>` return it as `Err(E::from(exception))`
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct JavaCallbackException {
+    /// Name of exception class, like `java.lang.IllegalStateException`
+    pub class_name: String,
+    /// Result of `Throwable.getMessage`
+    pub message: Option<String>,
+}
+
+impl ::std::fmt::Display for JavaCallbackException {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        match self.message {
+            Some(ref message) => write!(f, "{}: {}", self.class_name, message),
+            None => write!(f, "{}", self.class_name),
+        }
+    }
+}
+
+impl ::std::error::Error for JavaCallbackException {}
+
+impl From<JavaCallbackException> for String {
+    fn from(ex: JavaCallbackException) -> String {
+        ex.to_string()
+    }
+}
+
+#[allow(dead_code)]
+fn jni_call_string_method(env: *mut JNIEnv, obj: jobject, method_id: jmethodID) -> Option<String> {
+    assert!(!method_id.is_null());
+    let js = unsafe { (**env).CallObjectMethod.unwrap()(env, obj, method_id) };
+    if unsafe { (**env).ExceptionCheck.unwrap()(env) } != 0 {
+        unsafe { (**env).ExceptionClear.unwrap()(env) };
+        return None;
+    }
+    if js.is_null() {
+        return None;
+    }
+    let ret = JavaString::new(env, js).to_str().to_string();
+    unsafe { (**env).DeleteLocalRef.unwrap()(env, js) };
+    Some(ret)
+}
+
+/// If there is pending Java exception, clear it and return its description
+#[allow(dead_code)]
+fn jni_take_pending_exception(env: *mut JNIEnv) -> Option<JavaCallbackException> {
+    let ex = unsafe {
+        if (**env).ExceptionCheck.unwrap()(env) == 0 {
+            return None;
+        }
+        let ex = (**env).ExceptionOccurred.unwrap()(env);
+        (**env).ExceptionClear.unwrap()(env);
+        ex
+    };
+    assert!(!ex.is_null());
+    let class_class: jclass = swig_jni_find_class!(JAVA_LANG_CLASS, "java/lang/Class");
+    assert!(!class_class.is_null());
+    let class_get_name = swig_jni_get_method_id!(
+        JAVA_LANG_CLASS_GET_NAME,
+        JAVA_LANG_CLASS,
+        "getName",
+        "()Ljava/lang/String;"
+    );
+    let throwable_class: jclass = swig_jni_find_class!(JAVA_LANG_THROWABLE, "java/lang/Throwable");
+    assert!(!throwable_class.is_null());
+    let throwable_get_message = swig_jni_get_method_id!(
+        JAVA_LANG_THROWABLE_GET_MESSAGE,
+        JAVA_LANG_THROWABLE,
+        "getMessage",
+        "()Ljava/lang/String;"
+    );
+    let ex_class = unsafe { (**env).GetObjectClass.unwrap()(env, ex) };
+    let class_name = jni_call_string_method(env, ex_class, class_get_name);
+    let message = jni_call_string_method(env, ex, throwable_get_message);
+    unsafe {
+        (**env).DeleteLocalRef.unwrap()(env, ex_class);
+        (**env).DeleteLocalRef.unwrap()(env, ex);
+    }
+    Some(JavaCallbackException {
+        class_name: class_name.unwrap_or_else(|| "java.lang.Throwable".to_string()),
+        message,
+    })
+}
+
 #[allow(dead_code)]
 fn object_to_jobject<T: SwigForeignClass>(env: *mut JNIEnv, obj: T) -> jobject {
     let jcls = <T>::jni_class();
@@ -390,6 +715,7 @@ fn object_to_jobject<T: SwigForeignClass>(env: *mut JNIEnv, obj: T) -> jobject {
             panic!("object_to_jobject: Can not set mNativeObj field: catch exception");
         }
     }
+    <T>::jni_init_object(env, jobj);
     jobj
 }
 
@@ -397,8 +723,10 @@ foreign_typemap!(
     ($p:r_type) <T: SwigForeignClass> Vec<T> => internal_aliases::JForeignObjectsArray<T> {
         $out = vec_of_objects_to_jobject_array(env, $p);
     };
-    ($p:f_type, option = "NoNullAnnotations") => "swig_f_type!(T) []";
-    ($p:f_type, option = "NullAnnotations") => "@NonNull swig_f_type!(T, NoNullAnnotations) []";
+    ($p:f_type, option = "NoNullAnnotations, NoJavaList") => "swig_f_type!(T) []";
+    ($p:f_type, option = "NullAnnotations, NoJavaList")
+                  => "@NonNull swig_f_type!(T, NoNullAnnotations) []";
+    ($p:f_type, option = "Kotlin, NoJavaList") => "@NonNull swig_f_type!(T, NoNullAnnotations) []";
 );
 
 #[allow(dead_code)]
@@ -433,9 +761,10 @@ foreign_typemap!(
     ($p:r_type) <T: SwigForeignClass + Clone> Vec<T> <= internal_aliases::JForeignObjectsArray<T> {
         $out = jobject_array_to_vec_of_objects(env, $p);
     };
-    ($p:f_type, option = "NoNullAnnotations") <= "swig_f_type!(T) []";
-    ($p:f_type, option = "NullAnnotations")
+    ($p:f_type, option = "NoNullAnnotations, NoJavaList") <= "swig_f_type!(T) []";
+    ($p:f_type, option = "NullAnnotations, NoJavaList")
                   <= "@NonNull swig_f_type!(T, NoNullAnnotations) []";
+    ($p:f_type, option = "Kotlin, NoJavaList") <= "@NonNull swig_f_type!(T, NoNullAnnotations) []";
 );
 //ANCHOR_END: foreign_typemap_generic_example
 
@@ -465,6 +794,7 @@ fn vec_of_objects_to_jobject_array<T: SwigForeignClass>(
             if (**env).ExceptionCheck.unwrap()(env) != 0 {
                 panic!("Can not mNativeObj field: catch exception");
             }
+            <T>::jni_init_object(env, jobj);
             (**env).SetObjectArrayElement.unwrap()(env, obj_arr, i as jsize, jobj);
             if (**env).ExceptionCheck.unwrap()(env) != 0 {
                 panic!("SetObjectArrayElement({}) failed", i);
@@ -518,8 +848,17 @@ macro_rules! impl_jni_jni_invalid_value {
     )*)
 }
 
+impl<T: SwigForeignClass> JniInvalidValue for internal_aliases::JForeignObjectsList<T> {
+    fn jni_invalid_value() -> Self {
+        Self {
+            inner: ::std::ptr::null_mut(),
+            _marker: ::std::marker::PhantomData,
+        }
+    }
+}
+
 impl_jni_jni_invalid_value! {
-    jbyte jshort jint jlong jfloat jdouble
+    jboolean jbyte jchar jshort jint jlong jfloat jdouble
 }
 
 foreign_typemap!(
@@ -556,6 +895,23 @@ foreign_typemap!(
         "swig_foreign_from_i_type!(T, $p)";
 );
 
+foreign_typemap!(
+    ($p:r_type) <T, E: SwigJavaException> Result<T, E> => swig_i_type!(T) {
+        $out = match $p {
+            Ok(x) => {
+                swig_from_rust_to_i_type!(T, x, ret)
+                ret
+            }
+            Err(err) => {
+                jni_throw_error(env, err);
+                return <swig_i_type!(T)>::jni_invalid_value();
+            }
+        };
+    };
+    ($p:f_type, unique_prefix="/*Result<swig_subst_type!(T), swig_subst_type!(E)>*/") => "/*Result<swig_subst_type!(T), swig_subst_type!(E)>*/swig_f_type!(T)"
+        "swig_foreign_from_i_type!(T, $p)";
+);
+
 foreign_typemap!(
     ($p:r_type) bool => jboolean {
         $out = if $p { 1 as jboolean } else { 0 as jboolean };
@@ -579,6 +935,7 @@ foreign_typemap!(
     };
     ($p:f_type, option = "NoNullAnnotations") => "java.util.Date" "$out = new java.util.Date($p);";
     ($p:f_type, option = "NullAnnotations") => "@NonNull java.util.Date" "$out = new java.util.Date($p);";
+    ($p:f_type, option = "Kotlin") => "@NonNull java.util.Date" "$out = java.util.Date($p)";
 );
 
 foreign_typemap!(
@@ -590,18 +947,6 @@ foreign_typemap!(
     };
 );
 
-foreign_typemap!(
-    ($p:r_type) u8 => jshort {
-        $out = jshort::from($p);
-    };
-);
-foreign_typemap!(
-    ($p:r_type) u8 <= jshort {
-        $out = <u8 as ::std::convert::TryFrom<jshort>>::try_from($p)
-            .expect("invalid jshort, in jshort => u8 conversation");
-    };
-);
-
 foreign_typemap!(
     ($p:r_type) i16 => jshort {
         $out = $p;
@@ -611,16 +956,6 @@ foreign_typemap!(
     };
 );
 
-foreign_typemap!(
-    ($p:r_type) u16 => jint {
-        $out = jint::from($p);
-    };
-    ($p:r_type) u16 <= jint {
-        $out = <u16 as ::std::convert::TryFrom<jint>>::try_from($p)
-            .expect("invalid jint, in jint => u16 conversation");
-    };
-);
-
 foreign_typemap!(
     ($p:r_type) jint => i32 {
         $out = $p;
@@ -630,16 +965,6 @@ foreign_typemap!(
     };
 );
 
-foreign_typemap!(
-    ($p:r_type) u32 => jlong {
-        $out = jlong::from($p);
-    };
-    ($p:r_type) u32 <= jlong {
-        $out = <u32 as ::std::convert::TryFrom<jlong>>::try_from($p)
-            .expect("invalid jlong, in jlong => u32 conversation");
-    };
-);
-
 foreign_typemap!(
     ($p:r_type) i64 => jlong {
         $out = $p;
@@ -649,17 +974,6 @@ foreign_typemap!(
     };
 );
 
-foreign_typemap!(
-    ($p:r_type) u64 => jlong {
-        $out = <jlong as ::std::convert::TryFrom<u64>>::try_from($p)
-            .expect("invalid u64, in u64 => jlong conversation");
-    };
-    ($p:r_type) u64 <= jlong {
-        $out = <u64 as ::std::convert::TryFrom<jlong>>::try_from($p)
-            .expect("invalid jlong, in jlong => u64 conversation");
-    };
-);
-
 #[allow(dead_code)]
 pub fn u64_to_jlong_checked(x: u64) -> jlong {
     <jlong as ::std::convert::TryFrom<u64>>::try_from(x)
@@ -714,13 +1028,6 @@ fn from_std_string_jstring(x: String, env: *mut JNIEnv) -> jstring {
     }
 }
 
-foreign_typemap!(
-    ($p:r_type) usize <= jlong {
-        $out = <usize as ::std::convert::TryFrom<jlong>>::try_from($p)
-            .expect("invalid jlong, in jlong => usize conversation");
-    };
-);
-
 foreign_typemap!(
     ($p:r_type) &Path <= internal_aliases::JStringPath {
         let jstr = JavaString::new(env, $p);
@@ -728,6 +1035,7 @@ foreign_typemap!(
     };
     ($p:f_type, option = "NoNullAnnotations", unique_prefix="/*Path*/") <= "/*Path*/String";
     ($p:f_type, option = "NullAnnotations", unique_prefix="/*Path*/") <= "/*Path*/@NonNull String";
+    ($p:f_type, option = "Kotlin", unique_prefix="/*Path*/") <= "/*Path*/@NonNull String";
 );
 
 #[allow(dead_code)]
@@ -757,8 +1065,9 @@ foreign_typemap!(
     ($p:r_type) Vec<String> => internal_aliases::JStringObjectsArray {
         $out = vec_string_to_jobject_array($p, env);
     };
-    ($p:f_type, option = "NoNullAnnotations") => "java.lang.String []";
-    ($p:f_type, option = "NullAnnotations") => "@NonNull java.lang.String []";
+    ($p:f_type, option = "NoNullAnnotations, NoJavaList") => "java.lang.String []";
+    ($p:f_type, option = "NullAnnotations, NoJavaList") => "@NonNull java.lang.String []";
+    ($p:f_type, option = "Kotlin, NoJavaList") => "@NonNull java.lang.String []";
 );
 
 macro_rules! define_array_handling_code {
@@ -776,6 +1085,7 @@ macro_rules! define_array_handling_code {
                 array: $jni_arr_type,
                 data: *mut $jni_elem_type,
                 env: *mut JNIEnv,
+                release_mode: jint,
             }
             #[allow(dead_code)]
             impl $rust_arr_wrapper {
@@ -784,14 +1094,26 @@ macro_rules! define_array_handling_code {
                     let data =
                         unsafe { (**env).$jni_get_array_elements.unwrap()(env, array,
                                                                           ::std::ptr::null_mut()) };
-                    $rust_arr_wrapper { array, data, env }
+                    $rust_arr_wrapper { array, data, env, release_mode: JNI_ABORT as jint }
+                }
+                /// Changes made via `to_slice_mut` copied back to Java array on drop
+                fn new_mut(env: *mut JNIEnv, array: $jni_arr_type) -> $rust_arr_wrapper {
+                    let mut arr = $rust_arr_wrapper::new(env, array);
+                    arr.release_mode = 0;
+                    arr
+                }
+                fn len(&self) -> usize {
+                    let len: jsize = unsafe {
+                        (**self.env).GetArrayLength.unwrap()(self.env, self.array)
+                    };
+                    assert!((len as u64) <= (usize::max_value() as u64));
+                    len as usize
                 }
                 fn to_slice(&self) -> &[$rust_elem_type] {
-                    unsafe {
-                        let len: jsize = (**self.env).GetArrayLength.unwrap()(self.env, self.array);
-                        assert!((len as u64) <= (usize::max_value() as u64));
-                        ::std::slice::from_raw_parts(self.data, len as usize)
-                    }
+                    unsafe { ::std::slice::from_raw_parts(self.data, self.len()) }
+                }
+                fn to_slice_mut(&mut self) -> &mut [$rust_elem_type] {
+                    unsafe { ::std::slice::from_raw_parts_mut(self.data, self.len()) }
                 }
                 fn from_slice_to_raw(arr: &[$rust_elem_type], env: *mut JNIEnv) -> $jni_arr_type {
                     assert!((arr.len() as u64) <= (jsize::max_value() as u64));
@@ -821,7 +1143,7 @@ macro_rules! define_array_handling_code {
                             self.env,
                             self.array,
                             self.data,
-                            JNI_ABORT as jint,
+                            self.release_mode,
                         )
                     };
                 }
@@ -917,6 +1239,12 @@ foreign_typemap!(
         $out = $p.to_slice();
     };
 );
+foreign_typemap!(
+    ($p:r_type) &mut [i32] <= jintArray {
+        let mut arr = JavaIntArray::new_mut(env, $p);
+        $out = arr.to_slice_mut();
+    };
+);
 
 foreign_typemap!(
     ($p:r_type) &[i64] => jlongArray {
@@ -936,6 +1264,12 @@ foreign_typemap!(
         $out = $p.to_slice();
     };
 );
+foreign_typemap!(
+    ($p:r_type) &mut [i64] <= jlongArray {
+        let mut arr = JavaLongArray::new_mut(env, $p);
+        $out = arr.to_slice_mut();
+    };
+);
 
 foreign_typemap!(
     ($p:r_type) &[f32] => jfloatArray {
@@ -955,6 +1289,12 @@ foreign_typemap!(
         $out = $p.to_slice();
     };
 );
+foreign_typemap!(
+    ($p:r_type) &mut [f32] <= jfloatArray {
+        let mut arr = JavaFloatArray::new_mut(env, $p);
+        $out = arr.to_slice_mut();
+    };
+);
 
 foreign_typemap!(
     ($p:r_type) &[f64] => jdoubleArray {
@@ -974,6 +1314,12 @@ foreign_typemap!(
         $out = $p.to_slice();
     };
 );
+foreign_typemap!(
+    ($p:r_type) &mut [f64] <= jdoubleArray {
+        let mut arr = JavaDoubleArray::new_mut(env, $p);
+        $out = arr.to_slice_mut();
+    };
+);
 
 foreign_typemap!(
     ($p:r_type) &[i8] => jbyteArray {
@@ -993,6 +1339,264 @@ foreign_typemap!(
         $out = $p.to_slice();
     };
 );
+foreign_typemap!(
+    ($p:r_type) &mut [i8] <= jbyteArray {
+        let mut arr = JavaByteArray::new_mut(env, $p);
+        $out = arr.to_slice_mut();
+    };
+);
+
+/// `byte []` as `&[u8]`/`&mut [u8]`, changes made via `&mut [u8]`
+/// copied back to Java array on drop
+#[allow(dead_code)]
+struct JavaU8Array {
+    array: jbyteArray,
+    data: *mut jbyte,
+    env: *mut JNIEnv,
+    release_mode: jint,
+}
+#[allow(dead_code)]
+impl JavaU8Array {
+    fn new(env: *mut JNIEnv, array: jbyteArray, commit_changes: bool) -> JavaU8Array {
+        assert!(!array.is_null());
+        let data =
+            unsafe { (**env).GetByteArrayElements.unwrap()(env, array, ::std::ptr::null_mut()) };
+        assert!(!data.is_null());
+        let release_mode = if commit_changes { 0 } else { JNI_ABORT as jint };
+        JavaU8Array {
+            array,
+            data,
+            env,
+            release_mode,
+        }
+    }
+    fn len(&self) -> usize {
+        let len: jsize = unsafe { (**self.env).GetArrayLength.unwrap()(self.env, self.array) };
+        <usize as ::std::convert::TryFrom<jsize>>::try_from(len)
+            .expect("invalid jsize, in jsize => usize conversation")
+    }
+    fn to_slice(&self) -> &[u8] {
+        unsafe { ::std::slice::from_raw_parts(self.data as *const u8, self.len()) }
+    }
+    fn to_slice_mut(&mut self) -> &mut [u8] {
+        unsafe { ::std::slice::from_raw_parts_mut(self.data as *mut u8, self.len()) }
+    }
+    fn from_slice_to_raw(arr: &[u8], env: *mut JNIEnv) -> jbyteArray {
+        let arr: &[i8] =
+            unsafe { ::std::slice::from_raw_parts(arr.as_ptr() as *const i8, arr.len()) };
+        JavaByteArray::from_slice_to_raw(arr, env)
+    }
+}
+
+#[allow(dead_code)]
+impl Drop for JavaU8Array {
+    fn drop(&mut self) {
+        assert!(!self.env.is_null());
+        assert!(!self.array.is_null());
+        unsafe {
+            (**self.env).ReleaseByteArrayElements.unwrap()(
+                self.env,
+                self.array,
+                self.data,
+                self.release_mode,
+            )
+        };
+    }
+}
+
+foreign_typemap!(
+    ($p:r_type) &[u8] => jbyteArray {
+        $out = JavaU8Array::from_slice_to_raw($p, env);
+    };
+);
+
+foreign_typemap!(
+    ($p:r_type) &[u8] <= internal_aliases::JByteArrayU8 {
+        let arr = JavaU8Array::new(env, $p, false);
+        $out = arr.to_slice();
+    };
+    ($p:f_type, option = "NoDirectByteBuffer", unique_prefix = "/*u8*/") <= "/*u8*/byte []";
+);
+foreign_typemap!(
+    ($p:r_type) &mut [u8] <= internal_aliases::JByteArrayU8 {
+        let mut arr = JavaU8Array::new(env, $p, true);
+        $out = arr.to_slice_mut();
+    };
+);
+foreign_typemap!(
+    ($p:r_type) Vec<u8> <= internal_aliases::JByteArrayU8 {
+        $out = JavaU8Array::new(env, $p, false).to_slice().to_vec();
+    };
+);
+
+/// Primitive Java array accessed via `GetPrimitiveArrayCritical`,
+/// there should be no JNI calls while it is alive, so length of all arrays
+/// should be queried via `len` before the first `acquire`
+#[allow(dead_code)]
+struct JavaPrimitiveArrayCritical<T> {
+    array: jarray,
+    data: *mut T,
+    len: usize,
+    env: *mut JNIEnv,
+    release_mode: jint,
+}
+#[allow(dead_code)]
+impl<T> JavaPrimitiveArrayCritical<T> {
+    fn len(env: *mut JNIEnv, array: jarray) -> usize {
+        assert!(!array.is_null());
+        let len: jsize = unsafe { (**env).GetArrayLength.unwrap()(env, array) };
+        <usize as ::std::convert::TryFrom<jsize>>::try_from(len)
+            .expect("invalid jsize, in jsize => usize conversation")
+    }
+    /// Return `None` if JVM can not give access to array,
+    /// in this case `acquire_failed` should be called after
+    /// release of all other critical arrays
+    fn acquire(
+        env: *mut JNIEnv,
+        array: jarray,
+        len: usize,
+        commit_changes: bool,
+    ) -> Option<JavaPrimitiveArrayCritical<T>> {
+        let data = unsafe {
+            (**env).GetPrimitiveArrayCritical.unwrap()(env, array, ::std::ptr::null_mut())
+        };
+        if data.is_null() {
+            return None;
+        }
+        let release_mode = if commit_changes { 0 } else { JNI_ABORT as jint };
+        Some(JavaPrimitiveArrayCritical {
+            array,
+            data: data as *mut T,
+            len,
+            env,
+            release_mode,
+        })
+    }
+    /// Slices of the same array can not be passed to Rust, if one of them is mutable,
+    /// so throw `IllegalArgumentException` and return false in this case
+    fn check_not_same(env: *mut JNIEnv, array: jarray, other: jarray, message: &str) -> bool {
+        if unsafe { (**env).IsSameObject.unwrap()(env, array, other) } == 0 {
+            return true;
+        }
+        let ex_class = swig_jni_find_class!(
+            JAVA_LANG_ILLEGAL_ARGUMENT_EXCEPTION,
+            "java/lang/IllegalArgumentException"
+        );
+        jni_throw(env, ex_class, message);
+        false
+    }
+    fn acquire_failed(env: *mut JNIEnv) {
+        if unsafe { (**env).ExceptionCheck.unwrap()(env) } == 0 {
+            let ex_class =
+                swig_jni_find_class!(JAVA_LANG_OUT_OF_MEMORY_ERROR, "java/lang/OutOfMemoryError");
+            jni_throw(env, ex_class, "GetPrimitiveArrayCritical failed");
+        }
+    }
+    fn to_slice(&self) -> &[T] {
+        unsafe { ::std::slice::from_raw_parts(self.data, self.len) }
+    }
+    fn to_slice_mut(&mut self) -> &mut [T] {
+        unsafe { ::std::slice::from_raw_parts_mut(self.data, self.len) }
+    }
+}
+
+#[allow(dead_code)]
+impl<T> Drop for JavaPrimitiveArrayCritical<T> {
+    fn drop(&mut self) {
+        assert!(!self.env.is_null());
+        assert!(!self.array.is_null());
+        unsafe {
+            (**self.env).ReleasePrimitiveArrayCritical.unwrap()(
+                self.env,
+                self.array,
+                self.data as *mut ::std::os::raw::c_void,
+                self.release_mode,
+            )
+        };
+    }
+}
+
+/// Memory of direct `java.nio.ByteBuffer`, valid only during JNI call,
+/// while we hold local reference to buffer.
+/// The whole memory of buffer `[0, capacity)` is used,
+/// position and limit of buffer are ignored
+#[allow(dead_code)]
+struct JavaDirectByteBuffer {
+    data: *mut u8,
+    len: usize,
+}
+#[allow(dead_code)]
+impl JavaDirectByteBuffer {
+    /// Throw `IllegalArgumentException` and return `None`
+    /// if `buf` is not direct buffer
+    fn new(
+        env: *mut JNIEnv,
+        buf: internal_aliases::JDirectByteBuffer,
+    ) -> Option<JavaDirectByteBuffer> {
+        let (data, capacity) = if buf.is_null() {
+            (::std::ptr::null_mut(), -1)
+        } else {
+            unsafe {
+                (
+                    (**env).GetDirectBufferAddress.unwrap()(env, buf),
+                    (**env).GetDirectBufferCapacity.unwrap()(env, buf),
+                )
+            }
+        };
+        match <usize as ::std::convert::TryFrom<jlong>>::try_from(capacity) {
+            Ok(len) if !data.is_null() => Some(JavaDirectByteBuffer {
+                data: data as *mut u8,
+                len,
+            }),
+            _ => {
+                let ex_class = swig_jni_find_class!(
+                    JAVA_LANG_ILLEGAL_ARGUMENT_EXCEPTION,
+                    "java/lang/IllegalArgumentException"
+                );
+                jni_throw(
+                    env,
+                    ex_class,
+                    "java.nio.ByteBuffer is null or not direct, or JVM not support direct buffers",
+                );
+                None
+            }
+        }
+    }
+    fn to_slice(&self) -> &[u8] {
+        unsafe { ::std::slice::from_raw_parts(self.data, self.len) }
+    }
+    fn to_slice_mut(&mut self) -> &mut [u8] {
+        unsafe { ::std::slice::from_raw_parts_mut(self.data, self.len) }
+    }
+}
+
+foreign_typemap!(
+    ($p:r_type) &[u8] <= internal_aliases::JDirectByteBuffer {
+        let buf = match JavaDirectByteBuffer::new(env, $p) {
+            Some(buf) => buf,
+            None => return JniInvalidValue::jni_invalid_value(),
+        };
+        $out = buf.to_slice();
+    };
+    ($p:f_type, option = "DirectByteBuffer") <= "java.nio.ByteBuffer";
+);
+foreign_typemap!(
+    ($p:r_type) &mut [u8] <= internal_aliases::JDirectByteBuffer {
+        let mut buf = match JavaDirectByteBuffer::new(env, $p) {
+            Some(buf) => buf,
+            None => return JniInvalidValue::jni_invalid_value(),
+        };
+        $out = buf.to_slice_mut();
+    };
+);
+foreign_typemap!(
+    ($p:r_type) Vec<u8> <= internal_aliases::JDirectByteBuffer {
+        $out = match JavaDirectByteBuffer::new(env, $p) {
+            Some(buf) => buf.to_slice().to_vec(),
+            None => return JniInvalidValue::jni_invalid_value(),
+        };
+    };
+);
 
 foreign_typemap!(
     ($p:r_type) &[i16] => jshortArray {
@@ -1012,6 +1616,12 @@ foreign_typemap!(
         $out = $p.to_slice();
     };
 );
+foreign_typemap!(
+    ($p:r_type) &mut [i16] <= jshortArray {
+        let mut arr = JavaShortArray::new_mut(env, $p);
+        $out = arr.to_slice_mut();
+    };
+);
 
 foreign_typemap!(
     ($p:r_type) String => &str {
@@ -1093,21 +1703,6 @@ impl SwigFrom<isize> for jlong {
     }
 }
 
-#[cfg(target_pointer_width = "32")]
-impl SwigFrom<usize> for jlong {
-    fn swig_from(x: usize, _: *mut JNIEnv) -> Self {
-        x as jlong
-    }
-}
-
-#[cfg(target_pointer_width = "64")]
-impl SwigFrom<usize> for jlong {
-    fn swig_from(x: usize, _: *mut JNIEnv) -> Self {
-        let x = x as u64;
-        u64_to_jlong_checked(x)
-    }
-}
-
 foreign_typemap!(
     ($p:r_type) &str => String {
         $out = $p.to_string();
@@ -1202,6 +1797,41 @@ foreign_typemap!(
     };
     (f_type, option = "NoNullAnnotations") <= "Double";
     (f_type, option = "NullAnnotations") <= "@Nullable Double";
+    (f_type, option = "Kotlin") <= "@Nullable Double";
+);
+
+#[allow(dead_code)]
+fn from_rust_to_java_lang_double(env: *mut JNIEnv, x: Option<f64>) -> internal_aliases::JDouble {
+    match x {
+        Some(val) => {
+            let class: jclass = swig_jni_find_class!(JAVA_LANG_DOUBLE, "java/lang/Double");
+            assert!(!class.is_null());
+            let value_of_m: jmethodID = swig_jni_get_static_method_id!(
+                JAVA_LANG_DOUBLE_VALUE_OF,
+                JAVA_LANG_DOUBLE,
+                "valueOf",
+                "(D)Ljava/lang/Double;"
+            );
+            assert!(!value_of_m.is_null());
+            let ret = unsafe {
+                let ret = (**env).CallStaticObjectMethod.unwrap()(env, class, value_of_m, val);
+                if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                    panic!("Double.valueOf failed: catch exception");
+                }
+                ret
+            };
+            assert!(!ret.is_null());
+            ret
+        }
+        None => ::std::ptr::null_mut(),
+    }
+}
+
+foreign_typemap!(
+    ($p:r_type) Option<f64> => internal_aliases::JDouble {
+        $out = from_rust_to_java_lang_double(env, $p);
+    };
+    (f_type, option = "Kotlin") => "@Nullable Double";
 );
 
 foreign_typemap!(
@@ -1250,6 +1880,41 @@ foreign_typemap!(
     };
     (f_type, option = "NoNullAnnotations") <= "Float";
     (f_type, option = "NullAnnotations") <= "@Nullable Float";
+    (f_type, option = "Kotlin") <= "@Nullable Float";
+);
+
+#[allow(dead_code)]
+fn from_rust_to_java_lang_float(env: *mut JNIEnv, x: Option<f32>) -> internal_aliases::JFloat {
+    match x {
+        Some(val) => {
+            let class: jclass = swig_jni_find_class!(JAVA_LANG_FLOAT, "java/lang/Float");
+            assert!(!class.is_null());
+            let value_of_m: jmethodID = swig_jni_get_static_method_id!(
+                JAVA_LANG_FLOAT_VALUE_OF,
+                JAVA_LANG_FLOAT,
+                "valueOf",
+                "(F)Ljava/lang/Float;"
+            );
+            assert!(!value_of_m.is_null());
+            let ret = unsafe {
+                let ret = (**env).CallStaticObjectMethod.unwrap()(env, class, value_of_m, f64::from(val));
+                if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                    panic!("Float.valueOf failed: catch exception");
+                }
+                ret
+            };
+            assert!(!ret.is_null());
+            ret
+        }
+        None => ::std::ptr::null_mut(),
+    }
+}
+
+foreign_typemap!(
+    ($p:r_type) Option<f32> => internal_aliases::JFloat {
+        $out = from_rust_to_java_lang_float(env, $p);
+    };
+    (f_type, option = "Kotlin") => "@Nullable Float";
 );
 
 foreign_typemap!(
@@ -1343,6 +2008,41 @@ foreign_typemap!(
     };
     (f_type, option = "NoNullAnnotations") <= "Long";
     (f_type, option = "NullAnnotations") <= "@Nullable Long";
+    (f_type, option = "Kotlin") <= "@Nullable Long";
+);
+
+#[allow(dead_code)]
+fn from_rust_to_java_lang_long(env: *mut JNIEnv, x: Option<i64>) -> internal_aliases::JLong {
+    match x {
+        Some(val) => {
+            let class: jclass = swig_jni_find_class!(JAVA_LANG_LONG, "java/lang/Long");
+            assert!(!class.is_null());
+            let value_of_m: jmethodID = swig_jni_get_static_method_id!(
+                JAVA_LANG_LONG_VALUE_OF,
+                JAVA_LANG_LONG,
+                "valueOf",
+                "(J)Ljava/lang/Long;"
+            );
+            assert!(!value_of_m.is_null());
+            let ret = unsafe {
+                let ret = (**env).CallStaticObjectMethod.unwrap()(env, class, value_of_m, val);
+                if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                    panic!("Long.valueOf failed: catch exception");
+                }
+                ret
+            };
+            assert!(!ret.is_null());
+            ret
+        }
+        None => ::std::ptr::null_mut(),
+    }
+}
+
+foreign_typemap!(
+    ($p:r_type) Option<i64> => internal_aliases::JLong {
+        $out = from_rust_to_java_lang_long(env, $p);
+    };
+    (f_type, option = "Kotlin") => "@Nullable Long";
 );
 
 foreign_typemap!(
@@ -1455,6 +2155,41 @@ foreign_typemap!(
     };
     (f_type, option = "NoNullAnnotations") <= "Integer";
     (f_type, option = "NullAnnotations") <= "@Nullable Integer";
+    (f_type, option = "Kotlin") <= "@Nullable Integer";
+);
+
+#[allow(dead_code)]
+fn from_rust_to_java_lang_int(env: *mut JNIEnv, x: Option<i32>) -> internal_aliases::JInteger {
+    match x {
+        Some(val) => {
+            let class: jclass = swig_jni_find_class!(JAVA_LANG_INTEGER, "java/lang/Integer");
+            assert!(!class.is_null());
+            let value_of_m: jmethodID = swig_jni_get_static_method_id!(
+                JAVA_LANG_INTEGER_VALUE_OF,
+                JAVA_LANG_INTEGER,
+                "valueOf",
+                "(I)Ljava/lang/Integer;"
+            );
+            assert!(!value_of_m.is_null());
+            let ret = unsafe {
+                let ret = (**env).CallStaticObjectMethod.unwrap()(env, class, value_of_m, val);
+                if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                    panic!("Integer.valueOf failed: catch exception");
+                }
+                ret
+            };
+            assert!(!ret.is_null());
+            ret
+        }
+        None => ::std::ptr::null_mut(),
+    }
+}
+
+foreign_typemap!(
+    ($p:r_type) Option<i32> => internal_aliases::JInteger {
+        $out = from_rust_to_java_lang_int(env, $p);
+    };
+    (f_type, option = "Kotlin") => "@Nullable Integer";
 );
 
 #[allow(dead_code)]
@@ -1518,6 +2253,41 @@ foreign_typemap!(
     };
     (f_type, option = "NoNullAnnotations") <= "Byte";
     (f_type, option = "NullAnnotations") <= "@Nullable Byte";
+    (f_type, option = "Kotlin") <= "@Nullable Byte";
+);
+
+#[allow(dead_code)]
+fn from_rust_to_java_lang_byte(env: *mut JNIEnv, x: Option<i8>) -> internal_aliases::JByte {
+    match x {
+        Some(val) => {
+            let class: jclass = swig_jni_find_class!(JAVA_LANG_BYTE, "java/lang/Byte");
+            assert!(!class.is_null());
+            let value_of_m: jmethodID = swig_jni_get_static_method_id!(
+                JAVA_LANG_BYTE_VALUE_OF,
+                JAVA_LANG_BYTE,
+                "valueOf",
+                "(B)Ljava/lang/Byte;"
+            );
+            assert!(!value_of_m.is_null());
+            let ret = unsafe {
+                let ret = (**env).CallStaticObjectMethod.unwrap()(env, class, value_of_m, i32::from(val));
+                if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                    panic!("Byte.valueOf failed: catch exception");
+                }
+                ret
+            };
+            assert!(!ret.is_null());
+            ret
+        }
+        None => ::std::ptr::null_mut(),
+    }
+}
+
+foreign_typemap!(
+    ($p:r_type) Option<i8> => internal_aliases::JByte {
+        $out = from_rust_to_java_lang_byte(env, $p);
+    };
+    (f_type, option = "Kotlin") => "@Nullable Byte";
 );
 
 foreign_typemap!(
@@ -1532,6 +2302,41 @@ foreign_typemap!(
     };
     (f_type, option = "NoNullAnnotations") <= "Short";
     (f_type, option = "NullAnnotations") <= "@Nullable Short";
+    (f_type, option = "Kotlin") <= "@Nullable Short";
+);
+
+#[allow(dead_code)]
+fn from_rust_to_java_lang_short(env: *mut JNIEnv, x: Option<i16>) -> internal_aliases::JShort {
+    match x {
+        Some(val) => {
+            let class: jclass = swig_jni_find_class!(JAVA_LANG_SHORT, "java/lang/Short");
+            assert!(!class.is_null());
+            let value_of_m: jmethodID = swig_jni_get_static_method_id!(
+                JAVA_LANG_SHORT_VALUE_OF,
+                JAVA_LANG_SHORT,
+                "valueOf",
+                "(S)Ljava/lang/Short;"
+            );
+            assert!(!value_of_m.is_null());
+            let ret = unsafe {
+                let ret = (**env).CallStaticObjectMethod.unwrap()(env, class, value_of_m, i32::from(val));
+                if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                    panic!("Short.valueOf failed: catch exception");
+                }
+                ret
+            };
+            assert!(!ret.is_null());
+            ret
+        }
+        None => ::std::ptr::null_mut(),
+    }
+}
+
+foreign_typemap!(
+    ($p:r_type) Option<i16> => internal_aliases::JShort {
+        $out = from_rust_to_java_lang_short(env, $p);
+    };
+    (f_type, option = "Kotlin") => "@Nullable Short";
 );
 
 foreign_typemap!(
@@ -1566,6 +2371,9 @@ foreign_typemap!(
         } else {
             $out = java.util.Optional.empty();
         }
+"#;
+    ($p:f_type, option = "Kotlin") => "@Nullable swig_f_type!(T, NoNullAnnotations)" r#"
+        $out = if ($p != 0L) swig_f_type!(T, NoNullAnnotations)(InternalPointerMarker.RAW_PTR, $p) else null
 "#;
 );
 
@@ -1581,16 +2389,17 @@ foreign_typemap!(
     ($p:f_type, option = "NoNullAnnotations", unique_prefix = "/*opt*/") <= "/*opt*/swig_f_type!(T)" r#"
         $out = 0;//TODO: use ptr::null() for corresponding constant
         if ($p != null) {
-            $out = $p.mNativeObj;
-            $p.mNativeObj = 0;
+            $out = $p.releaseNativeObj();
         }
 "#;
     ($p:f_type, option = "NullAnnotations", unique_prefix = "/*opt*/") <= "/*opt*/@Nullable swig_f_type!(T, NoNullAnnotations)" r#"
         $out = 0;//TODO: use ptr::null() for corresponding constant
         if ($p != null) {
-            $out = $p.mNativeObj;
-            $p.mNativeObj = 0;
+            $out = $p.releaseNativeObj();
         }
+"#;
+    ($p:f_type, option = "Kotlin", unique_prefix = "/*opt*/") <= "/*opt*/@Nullable swig_f_type!(T, NoNullAnnotations)" r#"
+        $out = $p?.releaseNativeObj() ?: 0L
 "#;
 );
 
@@ -1616,6 +2425,9 @@ foreign_typemap!(
         if ($p != null) {
             $out = $p.mNativeObj;
         }
+"#;
+    ($p:f_type, option = "Kotlin", unique_prefix = "/*opt ref*/") <= "/*opt ref*/@Nullable swig_f_type!(T, NoNullAnnotations)" r#"
+        $out = $p?.mNativeObj ?: 0L
 "#;
 );
 
@@ -1632,12 +2444,14 @@ foreign_typemap!(
     ($p:f_type, option = "NullAnnotations") => "@NonNull java.util.Optional<String>" r#"
         $out = java.util.Optional.ofNullable($p);
 "#;
+    ($p:f_type, option = "Kotlin", unique_prefix = "/*opt str*/") => "/*opt str*/@Nullable String";
 );
 
 foreign_typemap!(
     (r_type) internal_aliases::JStringOptStr;
     (f_type, option = "NoNullAnnotations", unique_prefix = "/*opt*/") "/*opt*/String";
     (f_type, option = "NullAnnotations", unique_prefix = "/*opt*/") "/*opt*/@Nullable String";
+    (f_type, option = "Kotlin", unique_prefix = "/*opt*/") "/*opt*/@Nullable String";
 );
 
 foreign_typemap!(
@@ -1674,6 +2488,9 @@ foreign_typemap!(
         } else {
             $out = java.util.Optional.empty();
         }
+"#;
+    ($p:f_type, option = "Kotlin") => "@Nullable swig_f_type!(T)" r#"
+        $out = if ($p != -1) swig_f_type!(T).fromInt($p) else null
 "#;
 );
 
@@ -1690,6 +2507,9 @@ foreign_typemap!(
 "#;
     ($p:f_type, option = "NullAnnotations", unique_prefix = "/*opt*/") <= "/*opt*/@Nullable swig_f_type!(T)" r#"
         $out = ($p != null) ? $p.getValue() : -1;
+"#;
+    ($p:f_type, option = "Kotlin", unique_prefix = "/*opt*/") <= "/*opt*/@Nullable swig_f_type!(T)" r#"
+        $out = $p?.value ?: -1
 "#;
 );
 
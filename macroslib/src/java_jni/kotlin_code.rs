@@ -0,0 +1,198 @@
+use std::borrow::Cow;
+
+use super::{
+    java_code::{filter_null_annotation, format_doc_comments, is_primitive_type, ArgsFormatFlags},
+    JniForeignMethodSignature, NullAnnotation,
+};
+use crate::{JavaConfig, JvmLanguage, WRITE_TO_MEM_FAILED_MSG};
+
+pub(in crate::java_jni) fn use_kotlin(cfg: &JavaConfig) -> bool {
+    cfg.jvm_language == JvmLanguage::Kotlin
+}
+
+/// Extension of generated source files
+/// KDoc is markdown, so Rust doc comments are used as is
+pub(in crate::java_jni) fn doc_comments_to_kdoc(
+    doc_comments: &[String],
+    class_comments: bool,
+) -> String {
+    let lines: Vec<String> = doc_comments
+        .iter()
+        .map(|x| x.strip_prefix(' ').unwrap_or(x).trim_end().to_string())
+        .collect();
+    format_doc_comments(&lines, class_comments)
+}
+
+pub(in crate::java_jni) fn src_file_ext(cfg: &JavaConfig) -> &'static str {
+    if use_kotlin(cfg) {
+        "kt"
+    } else {
+        "java"
+    }
+}
+
+/// Convert name of Java type from typemap to Kotlin type,
+/// `@Nullable`/`@NonNull` from typemap have priority over `annotation`
+pub(in crate::java_jni) fn kotlin_type(
+    java_type: &str,
+    annotation: Option<NullAnnotation>,
+) -> String {
+    let nullable = if java_type.contains("@Nullable") {
+        true
+    } else if java_type.contains("@NonNull") {
+        false
+    } else {
+        match annotation {
+            Some(NullAnnotation::Nullable) => true,
+            Some(NullAnnotation::NonNull) | None => false,
+        }
+    };
+    let java_type = filter_null_annotation(java_type);
+    let java_type = remove_comments(&java_type);
+    let java_type = java_type.trim();
+    let nullable_mark = if nullable { "?" } else { "" };
+
+    if let Some(elem_type) = java_type.strip_suffix("[]") {
+        let elem_type = elem_type.trim();
+        let array_type = match elem_type {
+            "boolean" => "BooleanArray",
+            "byte" => "ByteArray",
+            "char" => "CharArray",
+            "short" => "ShortArray",
+            "int" => "IntArray",
+            "long" => "LongArray",
+            "float" => "FloatArray",
+            "double" => "DoubleArray",
+            _ => {
+                return format!(
+                    "Array<{}>{}",
+                    kotlin_type(elem_type, Some(NullAnnotation::NonNull)),
+                    nullable_mark
+                )
+            }
+        };
+        return format!("{}{}", array_type, nullable_mark);
+    }
+    if let Some(elem_type) = java_type
+        .strip_prefix("java.util.List<")
+        .and_then(|x| x.strip_suffix('>'))
+    {
+        // Kotlin's `List` is covariant, and its elements are not nullable
+        let elem_type = elem_type.trim();
+        let elem_type = elem_type.strip_prefix("? extends ").unwrap_or(elem_type);
+        let elem_type = kotlin_type(elem_type, Some(NullAnnotation::NonNull));
+        return format!("List<{}>{}", elem_type.trim_end_matches('?'), nullable_mark);
+    }
+
+    let (name, boxed) = match java_type {
+        "void" => return "Unit".into(),
+        "boolean" => ("Boolean", false),
+        "byte" => ("Byte", false),
+        "char" => ("Char", false),
+        "short" => ("Short", false),
+        "int" => ("Int", false),
+        "long" => ("Long", false),
+        "float" => ("Float", false),
+        "double" => ("Double", false),
+        "Boolean" | "java.lang.Boolean" => ("Boolean", true),
+        "Byte" | "java.lang.Byte" => ("Byte", true),
+        "Character" | "java.lang.Character" => ("Char", true),
+        "Short" | "java.lang.Short" => ("Short", true),
+        "Integer" | "java.lang.Integer" => ("Int", true),
+        "Long" | "java.lang.Long" => ("Long", true),
+        "Float" | "java.lang.Float" => ("Float", true),
+        "Double" | "java.lang.Double" => ("Double", true),
+        "String" | "java.lang.String" => ("String", false),
+        "Object" | "java.lang.Object" => ("Any", false),
+        _ => (java_type, false),
+    };
+    // boxed types should be nullable, to have the same JNI signature
+    if boxed || (nullable && !is_primitive_type(java_type)) {
+        format!("{}?", name)
+    } else {
+        name.into()
+    }
+}
+
+/// Return type of function in Kotlin syntax, nothing for `Unit`
+pub(in crate::java_jni) fn ret_type_decl(kotlin_type: &str) -> String {
+    if kotlin_type == "Unit" {
+        String::new()
+    } else {
+        format!(": {}", kotlin_type)
+    }
+}
+
+/// Escape name, if it is Kotlin's hard keyword
+pub(in crate::java_jni) fn escape_name(name: &str) -> Cow<'_, str> {
+    match name {
+        "as" | "break" | "class" | "continue" | "do" | "else" | "false" | "for" | "fun" | "if"
+        | "in" | "interface" | "is" | "null" | "object" | "package" | "return" | "super"
+        | "this" | "throw" | "true" | "try" | "typealias" | "typeof" | "val" | "var" | "when"
+        | "while" => Cow::Owned(format!("`{}`", name)),
+        _ => Cow::Borrowed(name),
+    }
+}
+
+pub(in crate::java_jni) fn args_with_kotlin_types<'a, NI: Iterator<Item = &'a str>>(
+    method: &JniForeignMethodSignature,
+    arg_name_iter: NI,
+    flags: ArgsFormatFlags,
+) -> String {
+    use std::fmt::Write;
+
+    assert!(flags.contains(ArgsFormatFlags::INTERNAL) || flags.contains(ArgsFormatFlags::EXTERNAL));
+
+    let mut res = String::new();
+    if flags.contains(ArgsFormatFlags::USE_COMMA_IF_NEED) && !method.input.is_empty() {
+        res.push_str(", ");
+    }
+    for (i, (arg, arg_name)) in method.input.iter().zip(arg_name_iter).enumerate() {
+        let type_name = match arg.java_converter.as_ref() {
+            Some(converter) if flags.contains(ArgsFormatFlags::INTERNAL) => kotlin_type(
+                converter.java_transition_type.display(),
+                converter.annotation,
+            ),
+            _ => kotlin_type(arg.as_ref().name.display(), arg.annotation),
+        };
+        if i == (method.input.len() - 1) {
+            write!(&mut res, "{}: {}", arg_name, type_name)
+        } else {
+            write!(&mut res, "{}: {}, ", arg_name, type_name)
+        }
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    res
+}
+
+/// Shift code to place it into `companion object`
+pub(in crate::java_jni) fn indent_for_companion(code: &str) -> String {
+    let mut ret = String::with_capacity(code.len() + code.len() / 8);
+    for (i, line) in code.split('\n').enumerate() {
+        if i != 0 {
+            ret.push('\n');
+        }
+        if !line.is_empty() {
+            ret.push_str("    ");
+        }
+        ret.push_str(line);
+    }
+    ret
+}
+
+fn remove_comments(type_name: &str) -> Cow<'_, str> {
+    if !type_name.contains("/*") {
+        return Cow::Borrowed(type_name);
+    }
+    let mut ret = String::with_capacity(type_name.len());
+    let mut rest = type_name;
+    while let Some(start) = rest.find("/*") {
+        ret.push_str(&rest[..start]);
+        rest = match rest[start..].find("*/") {
+            Some(end) => &rest[start + end + 2..],
+            None => "",
+        };
+    }
+    ret.push_str(rest);
+    Cow::Owned(ret)
+}
@@ -1,8 +1,8 @@
 use log::debug;
-use std::{fmt::Write, rc::Rc};
+use std::rc::Rc;
 use syn::spanned::Spanned;
 
-use super::{JavaContext, INTERNAL_PTR_MARKER, JAVA_RUST_SELF_NAME};
+use super::{kotlin_code, JavaContext, INTERNAL_PTR_MARKER, JAVA_RUST_SELF_NAME};
 use crate::{
     error::{invalid_src_id_span, Result},
     source_registry::SourceId,
@@ -13,7 +13,6 @@ use crate::{
         RustTypeIdx, TypeConvCode, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
     },
     types::{ForeignClassInfo, SelfTypeDesc},
-    WRITE_TO_MEM_FAILED_MSG,
 };
 
 pub(in crate::java_jni) fn register_typemap_for_self_type(
@@ -287,23 +286,64 @@ fn register_main_foreign_types(
         })?;
     }
 
-    let mut java_code_in_val_to_long = format!(
-        r#"
-        long {to_var} = {from_var}.{class_raw_ptr};
+    let moved_out = !class.copy_derived() && !class.smart_ptr_copy_derived();
+    let use_kotlin = kotlin_code::use_kotlin(ctx.cfg);
+    // objects moved into Rust are taken under lock of Java object,
+    // so concurrent `delete` can not free them the second time
+    let get_ptr = if moved_out {
+        "releaseNativeObj()"
+    } else {
+        JAVA_RUST_SELF_NAME
+    };
+    let java_code_in_val_to_long = if use_kotlin {
+        format!(
+            "        val {to_var}: Long = {from_var}.{get_ptr}",
+            to_var = TO_VAR_TEMPLATE,
+            from_var = FROM_VAR_TEMPLATE,
+            get_ptr = get_ptr,
+        )
+    } else {
+        format!(
+            r#"
+        long {to_var} = {from_var}.{get_ptr};
 "#,
-        to_var = TO_VAR_TEMPLATE,
-        from_var = FROM_VAR_TEMPLATE,
-        class_raw_ptr = JAVA_RUST_SELF_NAME,
-    );
-    if !class.copy_derived() && !class.smart_ptr_copy_derived() {
-        writeln!(
-            &mut java_code_in_val_to_long,
-            "        {from_var}.{class_raw_ptr} = 0;",
+            to_var = TO_VAR_TEMPLATE,
             from_var = FROM_VAR_TEMPLATE,
-            class_raw_ptr = JAVA_RUST_SELF_NAME,
+            get_ptr = get_ptr,
         )
-        .expect(WRITE_TO_MEM_FAILED_MSG);
-    }
+    };
+    let java_code_long_to_val = if use_kotlin {
+        format!(
+            "        val {out}: {class_name} = {class_name}({internal_ptr_marker}.RAW_PTR, {var})",
+            class_name = class.name,
+            var = FROM_VAR_TEMPLATE,
+            out = TO_VAR_TEMPLATE,
+            internal_ptr_marker = INTERNAL_PTR_MARKER,
+        )
+    } else {
+        format!(
+            "        {class_name} {out} = new {class_name}({internal_ptr_marker}.RAW_PTR, {var});",
+            class_name = class.name,
+            var = FROM_VAR_TEMPLATE,
+            out = TO_VAR_TEMPLATE,
+            internal_ptr_marker = INTERNAL_PTR_MARKER,
+        )
+    };
+    let java_code_ref_to_long = if use_kotlin {
+        format!(
+            "        val {out}: Long = {from}.{self_raw_ptr}",
+            from = FROM_VAR_TEMPLATE,
+            out = TO_VAR_TEMPLATE,
+            self_raw_ptr = JAVA_RUST_SELF_NAME,
+        )
+    } else {
+        format!(
+            "        long {out} = {from}.{self_raw_ptr};",
+            from = FROM_VAR_TEMPLATE,
+            out = TO_VAR_TEMPLATE,
+            self_raw_ptr = JAVA_RUST_SELF_NAME,
+        )
+    };
 
     let null_annot = if ctx.cfg.null_annotation_package.is_some() {
         "@NonNull "
@@ -312,7 +352,10 @@ fn register_main_foreign_types(
     };
 
     let class_ftype = ForeignTypeS {
-        name: ForeignTypeName::new(format!("{}{}", null_annot, class.name), (class.src_id, class.name.span())),
+        name: ForeignTypeName::new(
+            format!("{}{}", null_annot, class.name),
+            (class.src_id, class.name.span()),
+        ),
         provides_by_module: vec![],
         into_from_rust: Some(ForeignConversationRule {
             rust_ty: this_type,
@@ -320,13 +363,7 @@ fn register_main_foreign_types(
                 input_to_output: false,
                 intermediate_ty: jlong_out_val_rty.to_idx(),
                 conv_code: Rc::new(TypeConvCode::new(
-                    format!(
-                        "        {class_name} {out} = new {class_name}({internal_ptr_marker}.RAW_PTR, {var});",
-                        class_name = class.name,
-                        var = FROM_VAR_TEMPLATE,
-                        out = TO_VAR_TEMPLATE,
-                        internal_ptr_marker = INTERNAL_PTR_MARKER,
-                    ),
+                    java_code_long_to_val,
                     invalid_src_id_span(),
                 )),
             }),
@@ -363,12 +400,7 @@ fn register_main_foreign_types(
                 input_to_output: false,
                 intermediate_ty: jlong_ty.to_idx(),
                 conv_code: Rc::new(TypeConvCode::new(
-                    format!(
-                        "        long {out} = {from}.{self_raw_ptr};",
-                        from = FROM_VAR_TEMPLATE,
-                        out = TO_VAR_TEMPLATE,
-                        self_raw_ptr = JAVA_RUST_SELF_NAME,
-                    ),
+                    java_code_ref_to_long.clone(),
                     invalid_src_id_span(),
                 )),
             }),
@@ -394,12 +426,7 @@ fn register_main_foreign_types(
                 input_to_output: false,
                 intermediate_ty: jlong_ty.to_idx(),
                 conv_code: Rc::new(TypeConvCode::new(
-                    format!(
-                        "        long {out} = {from}.{self_raw_ptr};",
-                        from = FROM_VAR_TEMPLATE,
-                        out = TO_VAR_TEMPLATE,
-                        self_raw_ptr = JAVA_RUST_SELF_NAME,
-                    ),
+                    java_code_ref_to_long.clone(),
                     invalid_src_id_span(),
                 )),
             }),
@@ -437,12 +464,7 @@ fn register_main_foreign_types(
                         input_to_output: false,
                         intermediate_ty: jlong_ty.to_idx(),
                         conv_code: Rc::new(TypeConvCode::new(
-                            format!(
-                                "        long {out} = {from}.{self_raw_ptr};",
-                                from = FROM_VAR_TEMPLATE,
-                                out = TO_VAR_TEMPLATE,
-                                self_raw_ptr = JAVA_RUST_SELF_NAME,
-                            ),
+                            java_code_ref_to_long.clone(),
                             invalid_src_id_span(),
                         )),
                     }),
@@ -468,12 +490,7 @@ fn register_main_foreign_types(
                         input_to_output: false,
                         intermediate_ty: jlong_ty.to_idx(),
                         conv_code: Rc::new(TypeConvCode::new(
-                            format!(
-                                "        long {out} = {from}.{self_raw_ptr};",
-                                from = FROM_VAR_TEMPLATE,
-                                out = TO_VAR_TEMPLATE,
-                                self_raw_ptr = JAVA_RUST_SELF_NAME,
-                            ),
+                            java_code_ref_to_long.clone(),
                             invalid_src_id_span(),
                         )),
                     }),
@@ -3,14 +3,18 @@ use petgraph::Direction;
 use std::rc::Rc;
 
 use super::{
-    calc_this_type_for_method, java_code, merge_rule, JavaContext, JavaConverter,
-    JavaForeignTypeInfo, NullAnnotation,
+    calc_this_type_for_method, ftype_rules_cfg_options, java_code, merge_rule, unsigned,
+    JavaContext, JavaConverter, JavaForeignTypeInfo, NullAnnotation,
 };
 use crate::{
     error::{invalid_src_id_span, DiagnosticError, Result, SourceIdSpan},
     typemap::{
-        ast::{if_option_return_some_type, DisplayToTokens, TyParamsSubstList},
+        ast::{
+            check_if_smart_pointer_return_inner_type, if_option_return_some_type, DisplayToTokens,
+            TyParamsSubstList,
+        },
         ty::{ForeignType, RustType, TraitNamesSet},
+        utils::is_generic_rule_enabled,
         ExpandedFType, ForeignTypeInfo, MapToForeignFlag, TypeMapConvRuleInfoExpanderHelper,
         FROM_VAR_TEMPLATE,
     },
@@ -105,6 +109,7 @@ pub(in crate::java_jni) fn map_type(
         base_rt = rule.rust_ty;
     }
     let annotation = type_annotation(base_ft_name.display());
+    let unsigned_check = unsigned::unsigned_check(ctx, arg_ty, base_ft_name.display(), direction);
     let mut fti = JavaForeignTypeInfo {
         base: ForeignTypeInfo {
             name: base_ft_name,
@@ -112,6 +117,7 @@ pub(in crate::java_jni) fn map_type(
         },
         java_converter,
         annotation,
+        unsigned_check,
     };
     if fti.annotation.is_none() && !java_code::is_primitive_type(fti.base.name.display()) {
         fti.annotation = Some(if if_option_return_some_type(arg_ty).is_none() {
@@ -141,8 +147,22 @@ fn do_map_type(
         return Ok(ftype);
     }
 
-    let idx_subst_map: Option<(Rc<_>, TyParamsSubstList)> =
-        ctx.conv_map.generic_rules().iter().find_map(|grule| {
+    let cfg = ctx.cfg;
+    let idx_subst_map: Option<(Rc<_>, TyParamsSubstList)> = ctx
+        .conv_map
+        .generic_rules()
+        .iter()
+        .filter(|grule| {
+            let f_type_rules = match direction {
+                Direction::Outgoing => &grule.ftype_left_to_right,
+                Direction::Incoming => &grule.ftype_right_to_left,
+            };
+            // if options are invalid, `merge_rule` reports it
+            ftype_rules_cfg_options(cfg, f_type_rules, grule.src_id)
+                .map(|options| is_generic_rule_enabled(grule, direction, &options))
+                .unwrap_or(true)
+        })
+        .find_map(|grule| {
             grule
                 .is_ty_subst_of_my_generic_rtype(&arg_ty.ty, direction, |ty, traits| -> bool {
                     is_ty_implement_traits(ctx.conv_map, ty, traits)
@@ -200,14 +220,31 @@ fn do_map_type(
     }
 
     match direction {
-        Direction::Outgoing => Err(DiagnosticError::new2(
-            arg_ty_span,
-            format!(
-                "Do not know conversation from \
-                 such rust type '{}' to Java type",
-                arg_ty
-            ),
-        )),
+        Direction::Outgoing => {
+            let err = DiagnosticError::new2(
+                arg_ty_span,
+                format!(
+                    "Do not know conversation from \
+                     such rust type '{}' to Java type",
+                    arg_ty
+                ),
+            );
+            let is_trait_object = ["Box", "Arc"].iter().any(|smart_ptr| {
+                matches!(
+                    check_if_smart_pointer_return_inner_type(arg_ty, smart_ptr),
+                    Some(syn::Type::TraitObject(_))
+                )
+            });
+            if is_trait_object {
+                Err(err.add_span_note(
+                    arg_ty_span,
+                    "trait object can be returned only if types of all methods of callback \
+                     can be converted in both directions, and `Arc` requires `&self` methods",
+                ))
+            } else {
+                Err(err)
+            }
+        }
 
         Direction::Incoming => Err(DiagnosticError::new2(
             arg_ty_span,
@@ -1,11 +1,19 @@
+mod byte_buffer;
 mod fclass;
 mod fenum;
+mod fexception;
 mod find_cache;
 mod finterface;
 mod java_code;
+mod java_list;
+mod kotlin_code;
 mod map_class_self_type;
 mod map_type;
+mod object_cleanup;
+mod proguard;
+mod register_natives;
 mod rust_code;
+mod unsigned;
 
 use log::debug;
 use proc_macro2::{Span, TokenStream};
@@ -19,6 +27,7 @@ use crate::{
     error::{invalid_src_id_span, DiagnosticError, Result},
     extension::{ClassExtHandlers, ExtHandlers, MethodExtHandlers},
     file_cache::FileWriteCache,
+    source_registry::SourceId,
     typemap::{
         ast::{
             check_if_smart_pointer_return_inner_type, if_result_return_ok_err_types,
@@ -26,10 +35,10 @@ use crate::{
         },
         ty::RustType,
         utils::{
-            configure_ftype_rule, remove_files_if, validate_cfg_options, ForeignMethodSignature,
-            ForeignTypeInfoT,
+            cfg_option_enabled, configure_ftype_rule, remove_files_if, validate_cfg_options,
+            ForeignMethodSignature, ForeignTypeInfoT,
         },
-        ForeignTypeInfo, TypeMapConvRuleInfo,
+        FTypeConvRule, ForeignTypeInfo, TypeMapConvRuleInfo,
     },
     types::{ForeignClassInfo, ForeignMethod, ItemToExpand, MethodVariant},
     JavaConfig, JavaReachabilityFence, LanguageGenerator, SourceCode, TypeMap,
@@ -48,6 +57,12 @@ struct JavaContext<'a> {
     rust_code: &'a mut Vec<TokenStream>,
     generated_foreign_files: &'a mut FxHashSet<PathBuf>,
     java_type_to_jni_sig_map: FxHashMap<SmolStr, SmolStr>,
+    /// Rust error type -> name of Java exception class
+    exceptions: FxHashMap<SmolStr, String>,
+    /// native methods to register in `JNI_OnLoad`, if `register_natives` enabled
+    native_methods: Vec<register_natives::NativeMethod>,
+    /// classes and members, that Rust code accesses by name
+    keep_rules: proguard::KeepRules,
     class_ext_handlers: &'a ClassExtHandlers,
     method_ext_handlers: &'a MethodExtHandlers,
 }
@@ -63,6 +78,8 @@ struct JavaForeignTypeInfo {
     pub base: ForeignTypeInfo,
     pub java_converter: Option<JavaConverter>,
     annotation: Option<NullAnnotation>,
+    /// range check in Java code for unsigned types
+    unsigned_check: Option<unsigned::UnsignedCheck>,
 }
 
 impl ForeignTypeInfoT for JavaForeignTypeInfo {
@@ -96,6 +113,7 @@ impl From<ForeignTypeInfo> for JavaForeignTypeInfo {
             },
             java_converter: None,
             annotation: None,
+            unsigned_check: None,
         }
     }
 }
@@ -194,6 +212,9 @@ impl LanguageGenerator for JavaConfig {
             rust_code: &mut ret,
             generated_foreign_files: &mut generated_foreign_files,
             java_type_to_jni_sig_map: rust_code::predefined_java_type_to_jni_sig(),
+            exceptions: FxHashMap::default(),
+            native_methods: Vec::new(),
+            keep_rules: proguard::KeepRules::default(),
             class_ext_handlers: ext_handlers.class_ext_handlers,
             method_ext_handlers: ext_handlers.method_ext_handlers,
         };
@@ -216,11 +237,19 @@ impl LanguageGenerator for JavaConfig {
                 }
             }
         }
+        if self.register_natives {
+            register_natives::generate_register_natives(&mut ctx)?;
+        }
+        if self.generate_keep_rules {
+            proguard::generate_keep_rules(&mut ctx)?;
+        }
 
         if remove_not_generated_files {
             remove_files_if(&self.output_dir, |path| {
                 if let Some(ext) = path.extension() {
-                    if ext == "java" && !generated_foreign_files.contains(path) {
+                    if ext == kotlin_code::src_file_ext(self)
+                        && !generated_foreign_files.contains(path)
+                    {
                         return true;
                     }
                 }
@@ -237,7 +266,7 @@ impl LanguageGenerator for JavaConfig {
         _pointer_target_width: usize,
         mut generated_code: Vec<u8>,
     ) -> Result<Vec<u8>> {
-        rust_code::generate_load_unload_jni_funcs(&mut generated_code)?;
+        rust_code::generate_load_unload_jni_funcs(&mut generated_code, self)?;
         Ok(generated_code)
     }
 }
@@ -248,12 +277,14 @@ fn method_name(method: &ForeignMethod, f_method: &JniForeignMethodSignature) ->
             .as_ref()
             .map(|x| !x.converter.is_empty())
             .unwrap_or(false)
+            || v.unsigned_check.is_some()
     }) || f_method
         .output
         .java_converter
         .as_ref()
         .map(|x| !x.converter.is_empty())
-        .unwrap_or(false);
+        .unwrap_or(false)
+        || f_method.output.unsigned_check.is_some();
     match method.variant {
         MethodVariant::StaticMethod if !need_conv => method.short_name().as_str().to_string(),
         MethodVariant::Method(_) | MethodVariant::StaticMethod => {
@@ -263,6 +294,20 @@ fn method_name(method: &ForeignMethod, f_method: &JniForeignMethodSignature) ->
     }
 }
 
+/// Java code checks that non-nullable argument is not null before passing it to Rust,
+/// Kotlin compiler generates such checks itself
+fn need_null_check(cfg: &JavaConfig, arg: &JavaForeignTypeInfo) -> bool {
+    !kotlin_code::use_kotlin(cfg) && matches!(arg.annotation, Some(NullAnnotation::NonNull))
+}
+
+/// Static method without conversations is declared as `native` itself,
+/// there is no Java code around it, so arguments are checked in Rust.
+/// This keeps name of native method, and so JNI symbol, the same
+fn null_check_in_rust(method: &ForeignMethod, f_method: &JniForeignMethodSignature) -> bool {
+    method.variant == MethodVariant::StaticMethod
+        && method_name(method, f_method) == method.short_name().as_str()
+}
+
 fn java_class_full_name(package_name: &str, class_name: &str) -> String {
     let mut ret: String = package_name.into();
     ret.push('.');
@@ -295,6 +340,102 @@ fn calc_this_type_for_method(tm: &TypeMap, class: &ForeignClassInfo) -> Option<T
     }
 }
 
+/// All options, that can be used in typemaps and `foreign_code`
+fn all_cfg_options() -> FxHashSet<&'static str> {
+    let mut opts = FxHashSet::<&'static str>::default();
+    opts.insert("Java");
+    opts.insert("Kotlin");
+    opts.insert("NullAnnotations");
+    opts.insert("NoNullAnnotations");
+    opts.insert("DirectByteBuffer");
+    opts.insert("NoDirectByteBuffer");
+    opts.insert("Finalize");
+    opts.insert("Cleaner");
+    opts.insert("JavaList");
+    opts.insert("NoJavaList");
+    opts
+}
+
+/// Options enabled for this configuration,
+/// Kotlin has own nullable types, so there are no null annotations options for it
+fn cfg_options(cfg: &JavaConfig) -> FxHashSet<&'static str> {
+    let mut opts = FxHashSet::<&'static str>::default();
+    if kotlin_code::use_kotlin(cfg) {
+        opts.insert("Kotlin");
+    } else {
+        opts.insert("Java");
+        if cfg.null_annotation_package.is_some() {
+            opts.insert("NullAnnotations");
+        } else {
+            opts.insert("NoNullAnnotations");
+        }
+    }
+    if cfg.use_direct_byte_buffer {
+        opts.insert("DirectByteBuffer");
+    } else {
+        opts.insert("NoDirectByteBuffer");
+    }
+    if object_cleanup::use_cleaner(cfg) {
+        opts.insert("Cleaner");
+    } else {
+        opts.insert("Finalize");
+    }
+    if java_list::use_java_list(cfg) {
+        opts.insert("JavaList");
+    } else {
+        opts.insert("NoJavaList");
+    }
+    opts
+}
+
+/// Options to select one of `f_type` rules of typemap.
+/// If in Kotlin mode there is no rule for `Kotlin`, rule for `NullAnnotations`
+/// or `NoNullAnnotations` is used, so typemaps written only for Java still work,
+/// `@NonNull`/`@Nullable` from them are converted to Kotlin nullability.
+/// Rule with conversation code can not be reused, because of code is in Java
+fn ftype_rules_cfg_options(
+    cfg: &JavaConfig,
+    f_type_rules: &[FTypeConvRule],
+    rule_src_id: SourceId,
+) -> Result<FxHashSet<&'static str>> {
+    let options = cfg_options(cfg);
+    let find_enabled = |opts: &FxHashSet<&'static str>| {
+        f_type_rules.iter().find(|r| {
+            r.cfg_option
+                .as_ref()
+                .map(|opt| cfg_option_enabled(opt.as_str(), opts))
+                .unwrap_or(true)
+        })
+    };
+    if !kotlin_code::use_kotlin(cfg) || find_enabled(&options).is_some() {
+        return Ok(options);
+    }
+    for null_annotations_opt in &["NullAnnotations", "NoNullAnnotations"] {
+        let mut java_options = options.clone();
+        java_options.remove("Kotlin");
+        java_options.insert(null_annotations_opt);
+        if let Some(java_rule) = find_enabled(&java_options) {
+            if java_rule.code.is_some() {
+                let opt = java_rule
+                    .cfg_option
+                    .as_ref()
+                    .expect("Internal error: rule without option should be enabled");
+                return Err(DiagnosticError::new(
+                    rule_src_id,
+                    opt.sp,
+                    format!(
+                        "f_type rule for option '{}' contains Java code, so it can not be used for Kotlin,\n\
+                         add rule with option = \"Kotlin\"",
+                        opt.as_str()
+                    ),
+                ));
+            }
+            return Ok(java_options);
+        }
+    }
+    Ok(options)
+}
+
 fn merge_rule(ctx: &mut JavaContext, mut rule: TypeMapConvRuleInfo) -> Result<()> {
     debug!("merge_rule begin {:?}", rule);
     if rule.is_empty() {
@@ -304,22 +445,8 @@ fn merge_rule(ctx: &mut JavaContext, mut rule: TypeMapConvRuleInfo) -> Result<()
             format!("rule {:?} is empty", rule),
         ));
     }
-    let all_options = {
-        let mut opts = FxHashSet::<&'static str>::default();
-        opts.insert("NullAnnotations");
-        opts.insert("NoNullAnnotations");
-        opts
-    };
+    let all_options = all_cfg_options();
     validate_cfg_options(&rule, &all_options)?;
-    let options = {
-        let mut opts = FxHashSet::<&'static str>::default();
-        if ctx.cfg.null_annotation_package.is_some() {
-            opts.insert("NullAnnotations");
-        } else {
-            opts.insert("NoNullAnnotations");
-        }
-        opts
-    };
     if rule.c_types.is_some() {
         return Err(DiagnosticError::new(
             rule.src_id,
@@ -330,7 +457,9 @@ fn merge_rule(ctx: &mut JavaContext, mut rule: TypeMapConvRuleInfo) -> Result<()
     if !rule.f_code.is_empty() {
         unimplemented!();
     }
+    let options = ftype_rules_cfg_options(ctx.cfg, &rule.ftype_left_to_right, rule.src_id)?;
     configure_ftype_rule(&mut rule.ftype_left_to_right, "=>", rule.src_id, &options)?;
+    let options = ftype_rules_cfg_options(ctx.cfg, &rule.ftype_right_to_left, rule.src_id)?;
     configure_ftype_rule(&mut rule.ftype_right_to_left, "<=", rule.src_id, &options)?;
     ctx.conv_map.merge_conv_rule(rule.src_id, rule)?;
     Ok(())
@@ -355,23 +484,53 @@ fn init(ctx: &mut JavaContext, _code: &[SourceCode]) -> Result<()> {
     for rule in not_merged_data {
         merge_rule(ctx, rule)?;
     }
-    let src_path = ctx
-        .cfg
-        .output_dir
-        .join(&format!("{}.java", INTERNAL_PTR_MARKER));
+    unsigned::generate_unsigned_support(ctx)?;
+    let cfg = ctx.cfg;
+    for exception in &cfg.exceptions {
+        fexception::generate_exception(ctx, exception)?;
+    }
+    if object_cleanup::need_rust_cleaner(cfg) {
+        object_cleanup::generate_rust_cleaner(ctx)?;
+    }
+    if cfg.use_direct_byte_buffer {
+        byte_buffer::generate_rust_byte_buffer(ctx)?;
+    }
+    if java_list::use_java_list(cfg) {
+        java_list::generate_java_list_support(ctx)?;
+    }
+    let src_path = ctx.cfg.output_dir.join(&format!(
+        "{}.{}",
+        INTERNAL_PTR_MARKER,
+        kotlin_code::src_file_ext(ctx.cfg)
+    ));
     let mut src_file = FileWriteCache::new(&src_path, ctx.generated_foreign_files);
-    writeln!(
-        src_file,
-        r#"
+    if kotlin_code::use_kotlin(ctx.cfg) {
+        writeln!(
+            src_file,
+            r#"
+// Automatically generated by flapigen
+package {package}
+
+internal enum class {enum_name} {{
+    RAW_PTR
+}}"#,
+            package = ctx.cfg.package_name,
+            enum_name = INTERNAL_PTR_MARKER,
+        )
+    } else {
+        writeln!(
+            src_file,
+            r#"
 // Automatically generated by flapigen
 package {package};
 
 /*package*/ enum {enum_name} {{
     RAW_PTR;
 }}"#,
-        package = ctx.cfg.package_name,
-        enum_name = INTERNAL_PTR_MARKER,
-    )
+            package = ctx.cfg.package_name,
+            enum_name = INTERNAL_PTR_MARKER,
+        )
+    }
     .expect(WRITE_TO_MEM_FAILED_MSG);
     src_file.update_file_if_necessary().map_err(|err| {
         DiagnosticError::new2(
@@ -382,22 +541,37 @@ package {package};
     match ctx.cfg.reachability_fence {
         JavaReachabilityFence::Std => {}
         JavaReachabilityFence::GenerateFence(max_args) => {
-            let src_path = ctx
-                .cfg
-                .output_dir
-                .join(&format!("{}.java", REACHABILITY_FENCE_CLASS));
+            let src_path = ctx.cfg.output_dir.join(&format!(
+                "{}.{}",
+                REACHABILITY_FENCE_CLASS,
+                kotlin_code::src_file_ext(ctx.cfg)
+            ));
             let mut src_file = FileWriteCache::new(&src_path, ctx.generated_foreign_files);
-            write!(
-                src_file,
-                r#"
+            let use_kotlin = kotlin_code::use_kotlin(ctx.cfg);
+            if use_kotlin {
+                write!(
+                    src_file,
+                    r#"
+// Automatically generated by flapigen
+package {package}
+
+internal object {class_name} {{"#,
+                    package = ctx.cfg.package_name,
+                    class_name = REACHABILITY_FENCE_CLASS,
+                )
+            } else {
+                write!(
+                    src_file,
+                    r#"
 // Automatically generated by flapigen
 package {package};
 
 /*package*/ final class {class_name} {{
     private {class_name}() {{}}"#,
-                package = ctx.cfg.package_name,
-                class_name = REACHABILITY_FENCE_CLASS,
-            )
+                    package = ctx.cfg.package_name,
+                    class_name = REACHABILITY_FENCE_CLASS,
+                )
+            }
             .expect(WRITE_TO_MEM_FAILED_MSG);
 
             let mut f_method = JniForeignMethodSignature {
@@ -408,6 +582,7 @@ package {package};
                     },
                     java_converter: None,
                     annotation: None,
+                    unsigned_check: None,
                 },
                 input: vec![],
             };
@@ -416,16 +591,29 @@ package {package};
 
             for i in 1..=max_args {
                 let java_method_name = format!("reachabilityFence{}", i);
-                write!(
-                    src_file,
-                    "\n    /*package*/ static native void {}(Object ref1",
-                    java_method_name
-                )
-                .expect(WRITE_TO_MEM_FAILED_MSG);
-                for j in 2..=i {
-                    write!(src_file, ", Object ref{}", j).expect(WRITE_TO_MEM_FAILED_MSG);
+                if use_kotlin {
+                    write!(
+                        src_file,
+                        "\n    @JvmStatic\n    external fun {}(ref1: Any?",
+                        java_method_name
+                    )
+                    .expect(WRITE_TO_MEM_FAILED_MSG);
+                    for j in 2..=i {
+                        write!(src_file, ", ref{}: Any?", j).expect(WRITE_TO_MEM_FAILED_MSG);
+                    }
+                    src_file.write_all(b")").expect(WRITE_TO_MEM_FAILED_MSG);
+                } else {
+                    write!(
+                        src_file,
+                        "\n    /*package*/ static native void {}(Object ref1",
+                        java_method_name
+                    )
+                    .expect(WRITE_TO_MEM_FAILED_MSG);
+                    for j in 2..=i {
+                        write!(src_file, ", Object ref{}", j).expect(WRITE_TO_MEM_FAILED_MSG);
+                    }
+                    src_file.write_all(b");").expect(WRITE_TO_MEM_FAILED_MSG);
                 }
-                src_file.write_all(b");").expect(WRITE_TO_MEM_FAILED_MSG);
 
                 f_method.input.push(JavaForeignTypeInfo {
                     base: ForeignTypeInfo {
@@ -434,6 +622,7 @@ package {package};
                     },
                     java_converter: None,
                     annotation: None,
+                    unsigned_check: None,
                 });
                 let jni_func_name = rust_code::generate_jni_func_name(
                     ctx,
@@ -444,13 +633,21 @@ package {package};
                     &f_method,
                     false,
                 )?;
+                register_natives::remember_native_method(
+                    ctx,
+                    REACHABILITY_FENCE_CLASS,
+                    &java_method_name,
+                    MethodVariant::StaticMethod,
+                    &f_method,
+                    &jni_func_name,
+                );
                 let jni_func_name = syn::Ident::new(&jni_func_name, Span::call_site());
                 jni_args.push(quote!(_: jobject));
                 let jni_args = &jni_args;
+                let linkage = register_natives::jni_func_linkage_tokens(ctx.cfg);
                 ctx.rust_code.push(quote! {
                     #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
-                    #[no_mangle]
-                    pub extern "C" fn #jni_func_name(_env: *mut JNIEnv, _: jclass, #(#jni_args),*) {
+                    #linkage extern "C" fn #jni_func_name(_env: *mut JNIEnv, _: jclass, #(#jni_args),*) {
                     }
                 });
             }
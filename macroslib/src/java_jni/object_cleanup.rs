@@ -0,0 +1,418 @@
+use std::io::Write;
+
+use super::{kotlin_code, map_write_err, JavaContext, JAVA_RUST_SELF_NAME};
+use crate::{
+    error::{DiagnosticError, Result},
+    file_cache::FileWriteCache,
+    JavaConfig, JavaObjectCleanup, WRITE_TO_MEM_FAILED_MSG,
+};
+
+/// Java method that registers object in cleaner,
+/// called from constructors and from Rust after `AllocObject`
+pub(in crate::java_jni) const REGISTER_CLEANUP_METHOD: &str = "registerCleanup";
+pub(in crate::java_jni) const RUST_CLEANER_CLASS: &str = "RustCleaner";
+
+pub(in crate::java_jni) fn use_cleaner(cfg: &JavaConfig) -> bool {
+    cfg.object_cleanup != JavaObjectCleanup::Finalize
+}
+
+/// Interfaces that Java class, that owns Rust object, should implement
+pub(in crate::java_jni) fn class_implements(cfg: &JavaConfig) -> &'static str {
+    if use_cleaner(cfg) {
+        " implements AutoCloseable"
+    } else {
+        ""
+    }
+}
+
+/// Code to insert after `mNativeObj` initialization
+pub(in crate::java_jni) fn register_cleanup_call(cfg: &JavaConfig) -> String {
+    if !use_cleaner(cfg) {
+        String::new()
+    } else if kotlin_code::use_kotlin(cfg) {
+        format!("\n        {}()", REGISTER_CLEANUP_METHOD)
+    } else {
+        format!("\n        {}();", REGISTER_CLEANUP_METHOD)
+    }
+}
+
+/// Code to insert at the beginning of methods, to not pass pointer
+/// to already deleted object into Rust
+pub(in crate::java_jni) fn deleted_object_check(cfg: &JavaConfig, class_name: &str) -> String {
+    if !cfg.check_deleted_objects {
+        String::new()
+    } else if kotlin_code::use_kotlin(cfg) {
+        format!(
+            "        check({rust_self_name} != 0L) {{ \"{class_name} already deleted\" }}\n",
+            rust_self_name = JAVA_RUST_SELF_NAME,
+            class_name = class_name,
+        )
+    } else {
+        format!(
+            r#"
+        if ({rust_self_name} == 0)
+            throw new IllegalStateException("{class_name} already deleted");"#,
+            rust_self_name = JAVA_RUST_SELF_NAME,
+            class_name = class_name,
+        )
+    }
+}
+
+/// Generate `delete`, and `finalize` or `close` plus cleaner's machinery.
+/// `before_delete` is Java code executed in `delete` before freeing of Rust object
+pub(in crate::java_jni) fn generate_delete_methods(
+    cfg: &JavaConfig,
+    before_delete: &str,
+) -> String {
+    if !use_cleaner(cfg) {
+        return format!(
+            r#"
+    public synchronized void delete() {{
+        if ({rust_self_name} != 0) {{{before_delete}
+            do_delete({rust_self_name});
+            {rust_self_name} = 0;
+       }}
+    }}
+    @Override
+    protected void finalize() throws Throwable {{
+        try {{
+            delete();
+        }}
+        finally {{
+             super.finalize();
+        }}
+    }}
+    /*package*/ synchronized long releaseNativeObj() {{
+        long ptr = {rust_self_name};
+        {rust_self_name} = 0;
+        return ptr;
+    }}
+    private static native void do_delete(long me);"#,
+            rust_self_name = JAVA_RUST_SELF_NAME,
+            before_delete = before_delete,
+        );
+    }
+    format!(
+        r#"
+    public synchronized void delete() {{
+        if ({rust_self_name} != 0) {{{before_delete}
+            {rust_self_name} = 0;
+            mCleanable.clean();
+       }}
+    }}
+    @Override
+    public void close() {{
+        delete();
+    }}
+    /*package*/ synchronized long releaseNativeObj() {{
+        long ptr = {rust_self_name};
+        if (ptr != 0) {{
+            {rust_self_name} = 0;
+            mDeleter.forget();
+            mCleanable.clean();
+        }}
+        return ptr;
+    }}
+    private void {register_cleanup}() {{
+        mDeleter = new Deleter({rust_self_name});
+        mCleanable = {cleaner}.register(this, mDeleter);
+    }}
+    private static final class Deleter implements Runnable {{
+        private long ptr;
+        Deleter(long ptr) {{
+            this.ptr = ptr;
+        }}
+        @Override
+        public synchronized void run() {{
+            if (ptr != 0) {{
+                do_delete(ptr);
+                ptr = 0;
+            }}
+        }}
+        synchronized void forget() {{
+            ptr = 0;
+        }}
+    }}
+    private Deleter mDeleter;
+    private {cleaner}.Cleanable mCleanable;
+    private static native void do_delete(long me);"#,
+        rust_self_name = JAVA_RUST_SELF_NAME,
+        before_delete = before_delete,
+        register_cleanup = REGISTER_CLEANUP_METHOD,
+        cleaner = RUST_CLEANER_CLASS,
+    )
+}
+
+/// The same as `generate_delete_methods`, but for Kotlin.
+/// Return class members and members of `companion object`.
+/// Kotlin classes always implement `java.io.Closeable`
+/// and have `releaseNativeObj` to move object into Rust
+pub(in crate::java_jni) fn generate_kotlin_delete_methods(
+    cfg: &JavaConfig,
+    before_delete: &str,
+) -> (String, String) {
+    let companion = r#"
+    @JvmStatic
+    private external fun do_delete(me: Long)
+"#
+    .to_string();
+    if !use_cleaner(cfg) {
+        let members = format!(
+            r#"
+    @Synchronized
+    fun delete() {{
+        if ({rust_self_name} != 0L) {{{before_delete}
+            do_delete({rust_self_name})
+            {rust_self_name} = 0L
+        }}
+    }}
+    override fun close() {{
+        delete()
+    }}
+    @Synchronized
+    internal fun releaseNativeObj(): Long {{
+        val ptr = {rust_self_name}
+        {rust_self_name} = 0L
+        return ptr
+    }}
+    protected fun finalize() {{
+        delete()
+    }}"#,
+            rust_self_name = JAVA_RUST_SELF_NAME,
+            before_delete = before_delete,
+        );
+        return (members, companion);
+    }
+    let members = format!(
+        r#"
+    @Synchronized
+    fun delete() {{
+        if ({rust_self_name} != 0L) {{{before_delete}
+            {rust_self_name} = 0L
+            mCleanable?.clean()
+        }}
+    }}
+    override fun close() {{
+        delete()
+    }}
+    @Synchronized
+    internal fun releaseNativeObj(): Long {{
+        val ptr = {rust_self_name}
+        if (ptr != 0L) {{
+            {rust_self_name} = 0L
+            mDeleter?.forget()
+            mCleanable?.clean()
+        }}
+        return ptr
+    }}
+    private fun {register_cleanup}() {{
+        val deleter = Deleter({rust_self_name})
+        mDeleter = deleter
+        mCleanable = {cleaner}.register(this, deleter)
+    }}
+    private class Deleter(private var ptr: Long) : Runnable {{
+        @Synchronized
+        override fun run() {{
+            if (ptr != 0L) {{
+                do_delete(ptr)
+                ptr = 0L
+            }}
+        }}
+        @Synchronized
+        fun forget() {{
+            ptr = 0L
+        }}
+    }}
+    private var mDeleter: Deleter? = null
+    private var mCleanable: {cleaner}.Cleanable? = null"#,
+        rust_self_name = JAVA_RUST_SELF_NAME,
+        before_delete = before_delete,
+        register_cleanup = REGISTER_CLEANUP_METHOD,
+        cleaner = RUST_CLEANER_CLASS,
+    );
+    (members, companion)
+}
+
+/// Shared cleaner is used by classes, and to free memory of direct `ByteBuffer`
+pub(in crate::java_jni) fn need_rust_cleaner(cfg: &JavaConfig) -> bool {
+    use_cleaner(cfg) || cfg.use_direct_byte_buffer
+}
+
+/// Generate Java class with shared cleaner, that frees Rust objects
+/// if user forgot to call `close`
+pub(in crate::java_jni) fn generate_rust_cleaner(ctx: &mut JavaContext) -> Result<()> {
+    if kotlin_code::use_kotlin(ctx.cfg) {
+        return generate_kotlin_rust_cleaner(ctx);
+    }
+    let path = ctx
+        .cfg
+        .output_dir
+        .join(format!("{}.java", RUST_CLEANER_CLASS));
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+    let implementation = match ctx.cfg.object_cleanup {
+        JavaObjectCleanup::Cleaner => {
+            r#"
+    private static final java.lang.ref.Cleaner CLEANER = java.lang.ref.Cleaner.create();
+
+    /*package*/ static Cleanable register(Object obj, Runnable action) {
+        final java.lang.ref.Cleaner.Cleanable cleanable = CLEANER.register(obj, action);
+        return new Cleanable() {
+            @Override
+            public void clean() {
+                cleanable.clean();
+            }
+        };
+    }"#
+        }
+        // without `Cleaner` it is used only for memory of direct `ByteBuffer`
+        JavaObjectCleanup::PhantomReferenceCleaner | JavaObjectCleanup::Finalize => {
+            r#"
+    private static final java.lang.ref.ReferenceQueue<Object> QUEUE =
+        new java.lang.ref.ReferenceQueue<Object>();
+    private static final java.util.Set<Ref> REFS =
+        java.util.Collections.synchronizedSet(new java.util.HashSet<Ref>());
+
+    private static final class Ref extends java.lang.ref.PhantomReference<Object> implements Cleanable {
+        private final Runnable action;
+
+        Ref(Object obj, Runnable action) {
+            super(obj, QUEUE);
+            this.action = action;
+        }
+        @Override
+        public void clean() {
+            if (REFS.remove(this)) {
+                clear();
+                action.run();
+            }
+        }
+    }
+
+    static {
+        Thread thread = new Thread(new Runnable() {
+            @Override
+            public void run() {
+                while (true) {
+                    try {
+                        ((Ref) QUEUE.remove()).clean();
+                    } catch (InterruptedException e) {
+                        // ignore, continue to wait references
+                    }
+                }
+            }
+        }, "RustCleaner");
+        thread.setDaemon(true);
+        thread.start();
+    }
+
+    /*package*/ static Cleanable register(Object obj, Runnable action) {
+        Ref ref = new Ref(obj, action);
+        REFS.add(ref);
+        return ref;
+    }"#
+        }
+    };
+    write!(
+        file,
+        r#"// Automatically generated by flapigen
+package {package_name};
+
+/*package*/ final class {class_name} {{
+    private {class_name}() {{}}
+
+    /*package*/ interface Cleanable {{
+        void clean();
+    }}
+{implementation}
+}}
+"#,
+        package_name = ctx.cfg.package_name,
+        class_name = RUST_CLEANER_CLASS,
+        implementation = implementation,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    file.update_file_if_necessary()
+        .map_err(&map_write_err)
+        .map_err(DiagnosticError::map_any_err_to_our_err)?;
+    Ok(())
+}
+
+fn generate_kotlin_rust_cleaner(ctx: &mut JavaContext) -> Result<()> {
+    let path = ctx
+        .cfg
+        .output_dir
+        .join(format!("{}.kt", RUST_CLEANER_CLASS));
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+    let implementation = match ctx.cfg.object_cleanup {
+        JavaObjectCleanup::Cleaner => {
+            r#"
+    private val CLEANER: java.lang.ref.Cleaner = java.lang.ref.Cleaner.create()
+
+    fun register(obj: Any, action: Runnable): Cleanable {
+        val cleanable = CLEANER.register(obj, action)
+        return object : Cleanable {
+            override fun clean() {
+                cleanable.clean()
+            }
+        }
+    }"#
+        }
+        // without `Cleaner` it is used only for memory of direct `ByteBuffer`
+        JavaObjectCleanup::PhantomReferenceCleaner | JavaObjectCleanup::Finalize => {
+            r#"
+    private val QUEUE = java.lang.ref.ReferenceQueue<Any>()
+    private val REFS: MutableSet<Ref> = java.util.Collections.synchronizedSet(HashSet<Ref>())
+
+    private class Ref(obj: Any, private val action: Runnable) :
+        java.lang.ref.PhantomReference<Any>(obj, QUEUE), Cleanable {
+        override fun clean() {
+            if (REFS.remove(this)) {
+                clear()
+                action.run()
+            }
+        }
+    }
+
+    init {
+        val thread = Thread({
+            while (true) {
+                try {
+                    (QUEUE.remove() as Ref).clean()
+                } catch (e: InterruptedException) {
+                    // ignore, continue to wait references
+                }
+            }
+        }, "RustCleaner")
+        thread.isDaemon = true
+        thread.start()
+    }
+
+    fun register(obj: Any, action: Runnable): Cleanable {
+        val ref = Ref(obj, action)
+        REFS.add(ref)
+        return ref
+    }"#
+        }
+    };
+    write!(
+        file,
+        r#"// Automatically generated by flapigen
+package {package_name}
+
+internal object {class_name} {{
+    interface Cleanable {{
+        fun clean()
+    }}
+{implementation}
+}}
+"#,
+        package_name = ctx.cfg.package_name,
+        class_name = RUST_CLEANER_CLASS,
+        implementation = implementation,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    file.update_file_if_necessary()
+        .map_err(&map_write_err)
+        .map_err(DiagnosticError::map_any_err_to_our_err)?;
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use std::io::Write;
+
+use super::{java_class_full_name, map_write_err, JavaContext};
+use crate::{
+    error::{DiagnosticError, Result},
+    file_cache::FileWriteCache,
+    WRITE_TO_MEM_FAILED_MSG,
+};
+
+const KEEP_RULES_FILE: &str = "proguard-rules.pro";
+
+/// Classes and members of classes, that Rust code reaches by name
+/// (`FindClass`, `GetFieldID`, `GetMethodID` and names of native methods),
+/// so they should survive minification
+#[derive(Default)]
+pub(in crate::java_jni) struct KeepRules {
+    classes: Vec<KeepClass>,
+}
+
+struct KeepClass {
+    name: String,
+    is_interface: bool,
+    /// members that accessed via JNI, in ProGuard syntax
+    members: Vec<String>,
+    /// members that should be kept in classes that implement interface
+    impl_members: Vec<String>,
+}
+
+impl KeepRules {
+    fn class_mut(&mut self, class_name: &str, is_interface: bool) -> &mut KeepClass {
+        let idx = match self.classes.iter().position(|x| x.name == class_name) {
+            Some(idx) => idx,
+            None => {
+                self.classes.push(KeepClass {
+                    name: class_name.into(),
+                    is_interface,
+                    members: vec![],
+                    impl_members: vec![],
+                });
+                self.classes.len() - 1
+            }
+        };
+        &mut self.classes[idx]
+    }
+}
+
+impl KeepClass {
+    fn add_member(&mut self, member: String) {
+        if !self.members.contains(&member) {
+            self.members.push(member);
+        }
+    }
+}
+
+/// Class should be found by name via `FindClass`
+pub(in crate::java_jni) fn keep_class(ctx: &mut JavaContext, class_name: &str) {
+    ctx.keep_rules.class_mut(class_name, false);
+}
+
+/// Class has native methods, implemented by Rust code
+pub(in crate::java_jni) fn keep_native_methods(ctx: &mut JavaContext, class_name: &str) {
+    ctx.keep_rules
+        .class_mut(class_name, false)
+        .add_member("native <methods>;".into());
+}
+
+/// Rust code gets field via `GetFieldID` or `GetStaticFieldID`
+pub(in crate::java_jni) fn keep_field(
+    ctx: &mut JavaContext,
+    class_name: &str,
+    field_name: &str,
+    jni_sig: &str,
+    is_static: bool,
+) {
+    let field_type = jni_sig_to_java_types(jni_sig)
+        .and_then(|mut x| x.pop())
+        .unwrap_or_else(|| {
+            panic!(
+                "Internal error: invalid JNI signature of field '{}'",
+                jni_sig
+            )
+        });
+    ctx.keep_rules
+        .class_mut(class_name, false)
+        .add_member(format!(
+            "{}{} {};",
+            if is_static { "static " } else { "" },
+            field_type,
+            field_name
+        ));
+}
+
+/// Rust code calls method (or constructor for `<init>`) via `GetMethodID`
+pub(in crate::java_jni) fn keep_method(
+    ctx: &mut JavaContext,
+    class_name: &str,
+    method_name: &str,
+    jni_sig: &str,
+) {
+    let method = method_spec(method_name, jni_sig);
+    ctx.keep_rules
+        .class_mut(class_name, false)
+        .add_member(method);
+}
+
+/// Rust code calls method of callback interface, implemented by user's classes
+pub(in crate::java_jni) fn keep_interface_method(
+    ctx: &mut JavaContext,
+    interface_name: &str,
+    method_name: &str,
+    jni_sig: &str,
+) {
+    let method = method_spec(method_name, jni_sig);
+    let interface = ctx.keep_rules.class_mut(interface_name, true);
+    interface.add_member(method.clone());
+    if !interface.impl_members.contains(&method) {
+        interface.impl_members.push(method);
+    }
+}
+
+/// Generate ProGuard/R8 rules to keep classes and members,
+/// reachable from Rust code by name
+pub(in crate::java_jni) fn generate_keep_rules(ctx: &mut JavaContext) -> Result<()> {
+    let path = ctx.cfg.output_dir.join(KEEP_RULES_FILE);
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+    file.write_all(
+        b"# Automatically generated by flapigen\n\
+          # classes and members, that Rust code accesses via JNI by name\n",
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    for class in &ctx.keep_rules.classes {
+        let full_name = java_class_full_name(&ctx.cfg.package_name, &class.name);
+        write!(
+            file,
+            "-keep {} {}",
+            if class.is_interface {
+                "interface"
+            } else {
+                "class"
+            },
+            full_name
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        write_members(&mut file, &class.members);
+        if !class.impl_members.is_empty() {
+            write!(file, "-keepclassmembers class * implements {}", full_name)
+                .expect(WRITE_TO_MEM_FAILED_MSG);
+            write_members(&mut file, &class.impl_members);
+        }
+    }
+    file.update_file_if_necessary()
+        .map_err(&map_write_err)
+        .map_err(DiagnosticError::map_any_err_to_our_err)?;
+    Ok(())
+}
+
+fn write_members(file: &mut FileWriteCache, members: &[String]) {
+    if members.is_empty() {
+        file.write_all(b"\n").expect(WRITE_TO_MEM_FAILED_MSG);
+        return;
+    }
+    file.write_all(b" {\n").expect(WRITE_TO_MEM_FAILED_MSG);
+    for member in members {
+        writeln!(file, "    {}", member).expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    file.write_all(b"}\n").expect(WRITE_TO_MEM_FAILED_MSG);
+}
+
+fn method_spec(method_name: &str, jni_sig: &str) -> String {
+    let (args, ret) = jni_method_sig_to_java_types(jni_sig).unwrap_or_else(|| {
+        panic!(
+            "Internal error: invalid JNI signature of method {}: '{}'",
+            method_name, jni_sig
+        )
+    });
+    if method_name == "<init>" {
+        format!("<init>({});", args.join(", "))
+    } else {
+        format!("{} {}({});", ret, method_name, args.join(", "))
+    }
+}
+
+fn jni_method_sig_to_java_types(jni_sig: &str) -> Option<(Vec<String>, String)> {
+    let args_end = jni_sig.find(')')?;
+    let args = jni_sig_to_java_types(jni_sig.strip_prefix('(')?.get(..args_end - 1)?)?;
+    let ret = jni_sig_to_java_types(&jni_sig[args_end + 1..])?.pop()?;
+    Some((args, ret))
+}
+
+/// Convert sequence of JNI type signatures to Java type names,
+/// for example `ILjava/lang/String;[J` to `int`, `java.lang.String`, `long[]`
+fn jni_sig_to_java_types(sig: &str) -> Option<Vec<String>> {
+    let mut ret = vec![];
+    let mut rest = sig;
+    while !rest.is_empty() {
+        let mut dims = 0;
+        while rest.starts_with('[') {
+            dims += 1;
+            rest = &rest[1..];
+        }
+        let mut type_name = match rest.as_bytes().first()? {
+            b'Z' => "boolean".to_string(),
+            b'B' => "byte".into(),
+            b'C' => "char".into(),
+            b'S' => "short".into(),
+            b'I' => "int".into(),
+            b'J' => "long".into(),
+            b'F' => "float".into(),
+            b'D' => "double".into(),
+            b'V' => "void".into(),
+            b'L' => {
+                let end = rest.find(';')?;
+                let name = rest[1..end].replace('/', ".");
+                rest = &rest[end..];
+                name
+            }
+            _ => return None,
+        };
+        rest = &rest[1..];
+        for _ in 0..dims {
+            type_name.push_str("[]");
+        }
+        ret.push(type_name);
+    }
+    Some(ret)
+}
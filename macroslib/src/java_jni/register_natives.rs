@@ -0,0 +1,311 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+
+use super::{
+    java_class_full_name, java_class_name_to_jni,
+    java_code::{filter_null_annotation, is_primitive_type},
+    proguard, JavaContext, JniForeignMethodSignature,
+};
+use crate::{
+    error::{invalid_src_id_span, DiagnosticError, Result},
+    typemap::ast::if_ty_result_return_ok_type,
+    types::{ForeignClassInfo, ForeignMethod, MethodVariant},
+    JavaConfig, CRITICAL_NATIVE, FAST_NATIVE,
+};
+
+pub(in crate::java_jni) const REGISTER_NATIVES_FUNC: &str = "swig_jni_register_natives";
+
+/// Java native method implemented by Rust function,
+/// that should be registered via `RegisterNatives`
+pub(in crate::java_jni) struct NativeMethod {
+    class_name: String,
+    java_method_name: String,
+    args: Vec<String>,
+    ret: String,
+    rust_func_name: String,
+}
+
+/// Attributes and visibility for Rust function that implements Java native method
+pub(in crate::java_jni) fn jni_func_linkage(cfg: &JavaConfig) -> &'static str {
+    if cfg.register_natives {
+        ""
+    } else {
+        "#[no_mangle]\npub "
+    }
+}
+
+pub(in crate::java_jni) fn jni_func_linkage_tokens(cfg: &JavaConfig) -> TokenStream {
+    if cfg.register_natives {
+        TokenStream::new()
+    } else {
+        quote!(#[no_mangle] pub)
+    }
+}
+
+/// How Android Runtime calls native method,
+/// selected via `#[FastNative]` or `#[CriticalNative]` method attribute
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(in crate::java_jni) enum NativeKind {
+    Normal,
+    Fast,
+    Critical,
+}
+
+impl NativeKind {
+    /// Annotation for Java/Kotlin declaration of native method
+    pub(in crate::java_jni) fn annotation(self) -> &'static str {
+        match self {
+            NativeKind::Normal => "",
+            NativeKind::Fast => "@dalvik.annotation.optimization.FastNative\n    ",
+            NativeKind::Critical => "@dalvik.annotation.optimization.CriticalNative\n    ",
+        }
+    }
+    /// Leading arguments of Rust function that implements native method,
+    /// critical natives get neither `JNIEnv` nor `jclass`
+    pub(in crate::java_jni) fn jni_env_args(self) -> &'static str {
+        match self {
+            NativeKind::Normal | NativeKind::Fast => "env: *mut JNIEnv, _: jclass, ",
+            NativeKind::Critical => "",
+        }
+    }
+}
+
+/// Find out how native method should be called, and check
+/// that method is eligible for `@CriticalNative`:
+/// only primitive types, without conversation in Java code and without exceptions
+pub(in crate::java_jni) fn native_kind(
+    cfg: &JavaConfig,
+    class: &ForeignClassInfo,
+    method: &ForeignMethod,
+    f_method: &JniForeignMethodSignature,
+) -> Result<NativeKind> {
+    let fast = method.unknown_attrs.iter().any(|x| x == FAST_NATIVE);
+    let critical = method.unknown_attrs.iter().any(|x| x == CRITICAL_NATIVE);
+    let kind = match (fast, critical) {
+        (false, false) => return Ok(NativeKind::Normal),
+        (true, false) => NativeKind::Fast,
+        (false, true) => NativeKind::Critical,
+        (true, true) => {
+            return Err(DiagnosticError::new(
+                class.src_id,
+                method.span(),
+                format!(
+                    "class {}, method {}: {} and {} can not be used together",
+                    class.name,
+                    method.short_name(),
+                    FAST_NATIVE,
+                    CRITICAL_NATIVE
+                ),
+            ));
+        }
+    };
+    let not_eligible = |reason: &str| {
+        DiagnosticError::new(
+            class.src_id,
+            method.span(),
+            format!(
+                "class {}, method {}: can not be {}, {}",
+                class.name,
+                method.short_name(),
+                if kind == NativeKind::Fast {
+                    FAST_NATIVE
+                } else {
+                    CRITICAL_NATIVE
+                },
+                reason
+            ),
+        )
+    };
+    if !cfg.register_natives {
+        return Err(not_eligible(
+            "it requires registration via RegisterNatives, see JavaConfig::register_natives",
+        ));
+    }
+    if method.is_dummy_constructor() {
+        return Err(not_eligible(
+            "there is no native method for empty constructor",
+        ));
+    }
+    if kind == NativeKind::Critical {
+        if let syn::ReturnType::Type(_, ref ret_ty) = method.fn_decl.output {
+            if if_ty_result_return_ok_type(ret_ty).is_some() {
+                return Err(not_eligible("it throws exception via JNIEnv"));
+            }
+        }
+        let output_is_primitive = match method.variant {
+            MethodVariant::Constructor => true,
+            MethodVariant::Method(_) | MethodVariant::StaticMethod => {
+                f_method.output.java_converter.is_none()
+                    && is_primitive_type(f_method.output.base.name.display())
+            }
+        };
+        if !output_is_primitive
+            || f_method.input.iter().any(|arg| {
+                arg.java_converter.is_some() || !is_primitive_type(arg.base.name.display())
+            })
+        {
+            return Err(not_eligible(
+                "arguments and return type should be mapped to primitive Java types",
+            ));
+        }
+    }
+    Ok(kind)
+}
+
+/// Remember Java native method, if we should register it in `JNI_OnLoad`
+pub(in crate::java_jni) fn remember_native_method(
+    ctx: &mut JavaContext,
+    class_name: &str,
+    java_method_name: &str,
+    method_type: MethodVariant,
+    f_method: &JniForeignMethodSignature,
+    rust_func_name: &str,
+) {
+    proguard::keep_native_methods(ctx, class_name);
+    if !ctx.cfg.register_natives {
+        return;
+    }
+    let mut args = Vec::with_capacity(f_method.input.len() + 1);
+    if let MethodVariant::Method(_) = method_type {
+        args.push("long".to_string());
+    }
+    for arg in &f_method.input {
+        let type_name = arg
+            .java_converter
+            .as_ref()
+            .map(|x| x.java_transition_type.display())
+            .unwrap_or_else(|| arg.as_ref().name.display());
+        args.push(type_name.to_string());
+    }
+    let ret = match method_type {
+        MethodVariant::Constructor => "long",
+        MethodVariant::Method(_) | MethodVariant::StaticMethod => f_method
+            .output
+            .java_converter
+            .as_ref()
+            .map(|x| x.java_transition_type.display())
+            .unwrap_or_else(|| f_method.output.base.name.display()),
+    };
+    let ret = if ret.is_empty() { "void" } else { ret };
+    ctx.native_methods.push(NativeMethod {
+        class_name: class_name.into(),
+        java_method_name: java_method_name.into(),
+        args,
+        ret: ret.into(),
+        rust_func_name: rust_func_name.into(),
+    });
+}
+
+/// Generate function that registers all remembered native methods
+pub(in crate::java_jni) fn generate_register_natives(ctx: &mut JavaContext) -> Result<()> {
+    let mut classes = Vec::<(&str, Vec<&NativeMethod>)>::new();
+    for m in &ctx.native_methods {
+        match classes.iter_mut().find(|x| x.0 == m.class_name) {
+            Some(class) => class.1.push(m),
+            None => classes.push((&m.class_name, vec![m])),
+        }
+    }
+
+    let mut register_code = Vec::with_capacity(classes.len());
+    for (class_name, methods) in &classes {
+        let class_name_for_jni =
+            java_class_name_to_jni(&java_class_full_name(&ctx.cfg.package_name, class_name));
+        let mut natives = Vec::with_capacity(methods.len());
+        for m in methods {
+            let method_name = &m.java_method_name;
+            let method_sig = native_method_signature(ctx, m)?;
+            let func_name = syn::Ident::new(&m.rust_func_name, Span::call_site());
+            natives.push(quote! {
+                JNINativeMethod {
+                    name: swig_c_str!(#method_name) as *mut ::std::os::raw::c_char,
+                    signature: swig_c_str!(#method_sig) as *mut ::std::os::raw::c_char,
+                    fnPtr: #func_name as *mut ::std::os::raw::c_void,
+                }
+            });
+        }
+        register_code.push(quote! {
+            unsafe {
+                let class: jclass = (**env).FindClass.unwrap()(env, swig_c_str!(#class_name_for_jni));
+                assert!(!class.is_null(), concat!("FindClass failed for ", #class_name_for_jni));
+                let methods = [#(#natives),*];
+                let res = (**env).RegisterNatives.unwrap()(
+                    env,
+                    class,
+                    methods.as_ptr(),
+                    methods.len() as jint,
+                );
+                assert!(res == (JNI_OK as jint),
+                        concat!("RegisterNatives failed for ", #class_name_for_jni));
+                (**env).DeleteLocalRef.unwrap()(env, class);
+            }
+        });
+    }
+    let func_name = syn::Ident::new(REGISTER_NATIVES_FUNC, Span::call_site());
+    ctx.rust_code.push(quote! {
+        fn #func_name(env: *mut JNIEnv) {
+            #(#register_code)*
+        }
+    });
+
+    Ok(())
+}
+
+fn native_method_signature(ctx: &JavaContext, m: &NativeMethod) -> Result<String> {
+    let type_sig = |java_type: &str| {
+        java_type_to_jni_type_sig(ctx, java_type).ok_or_else(|| {
+            DiagnosticError::new2(
+                invalid_src_id_span(),
+                format!(
+                    "Can not register native method {}.{}, unknown java type '{}'",
+                    m.class_name, m.java_method_name, java_type
+                ),
+            )
+        })
+    };
+    let mut ret = String::from("(");
+    for arg in &m.args {
+        ret.push_str(&type_sig(arg)?);
+    }
+    ret.push(')');
+    ret.push_str(&type_sig(&m.ret)?);
+    Ok(ret)
+}
+
+fn java_type_to_jni_type_sig(ctx: &JavaContext, java_type: &str) -> Option<String> {
+    let java_type = filter_null_annotation(java_type);
+    let java_type = java_type.trim();
+    if let Some(elem_type) = java_type.strip_suffix("[]") {
+        return java_type_to_jni_type_sig(ctx, elem_type).map(|x| format!("[{}", x));
+    }
+    let java_type = match java_type.find('<') {
+        Some(pos) => java_type[..pos].trim_end(),
+        None => java_type,
+    };
+    let sig = match java_type {
+        "void" => "V",
+        "boolean" => "Z",
+        "byte" => "B",
+        "char" => "C",
+        "short" => "S",
+        "int" => "I",
+        "long" => "J",
+        "float" => "F",
+        "double" => "D",
+        _ if java_type.contains('.') => {
+            return Some(format!("L{};", java_class_name_to_jni(java_type)));
+        }
+        _ => match ctx.java_type_to_jni_sig_map.get(java_type) {
+            Some(sig) if sig.starts_with('L') && sig.ends_with(';') => {
+                return Some(java_class_name_to_jni(sig));
+            }
+            _ => match java_type {
+                "Object" | "String" | "Boolean" | "Byte" | "Character" | "Short" | "Integer"
+                | "Long" | "Float" | "Double" => {
+                    return Some(format!("Ljava/lang/{};", java_type));
+                }
+                _ => return None,
+            },
+        },
+    };
+    Some(sig.into())
+}
@@ -7,13 +7,14 @@ use syn::{parse_quote, visit::Visit};
 use super::{
     find_cache::{JniCacheMacroCalls, JniCacheMacroCallsVisitor},
     java_code::filter_null_annotation,
+    register_natives::REGISTER_NATIVES_FUNC,
     JavaContext, JniForeignMethodSignature,
 };
 use crate::{
     error::{invalid_src_id_span, panic_on_syn_error, DiagnosticError, Result, SourceIdSpan},
     typemap::ast::DisplayToTokens,
     types::MethodVariant,
-    WRITE_TO_MEM_FAILED_MSG,
+    JavaConfig, WRITE_TO_MEM_FAILED_MSG,
 };
 
 pub(in crate::java_jni) fn predefined_java_type_to_jni_sig() -> FxHashMap<SmolStr, SmolStr> {
@@ -25,6 +26,7 @@ pub(in crate::java_jni) fn predefined_java_type_to_jni_sig() -> FxHashMap<SmolSt
     m.insert("Long".into(), "Ljava.lang.Long".into());
     m.insert("Float".into(), "Ljava.lang.Float".into());
     m.insert("Double".into(), "Ljava.lang.Double".into());
+    m.insert("java.nio.ByteBuffer".into(), "Ljava.nio.ByteBuffer;".into());
     m.insert("boolean".into(), "Z".into());
     m.insert("byte".into(), "B".into());
     m.insert("char".into(), "C".into());
@@ -46,16 +48,16 @@ pub(in crate::java_jni) fn predefined_java_type_to_jni_sig() -> FxHashMap<SmolSt
 }
 
 fn java_type_to_jni_signature<'a>(ctx: &'a JavaContext, java_type: &str) -> Option<&'a str> {
-    if java_type.contains("@NonNull") || java_type.contains("@Nullable") {
-        let java_type = filter_null_annotation(java_type);
-        ctx.java_type_to_jni_sig_map
-            .get(java_type.trim())
-            .map(SmolStr::as_str)
-    } else {
-        ctx.java_type_to_jni_sig_map
-            .get(java_type)
-            .map(SmolStr::as_str)
-    }
+    let java_type = filter_null_annotation(java_type);
+    let java_type = java_type.trim();
+    // type arguments of generic types are erased in JNI signatures
+    let java_type = match java_type.find('<') {
+        Some(pos) => java_type[..pos].trim_end(),
+        None => java_type,
+    };
+    ctx.java_type_to_jni_sig_map
+        .get(java_type)
+        .map(SmolStr::as_str)
 }
 
 pub(in crate::java_jni) fn generate_jni_func_name(
@@ -147,8 +149,21 @@ pub(in crate::java_jni) fn jni_method_signature(
     ret
 }
 
+fn parse_hook_path(func_path: &str) -> Result<syn::Path> {
+    syn::parse_str(func_path).map_err(|err| {
+        DiagnosticError::new2(
+            invalid_src_id_span(),
+            format!(
+                "Invalid path '{}' of JNI_OnLoad/JNI_OnUnload hook: {}",
+                func_path, err
+            ),
+        )
+    })
+}
+
 pub(in crate::java_jni) fn generate_load_unload_jni_funcs(
     generated_code: &mut Vec<u8>,
+    cfg: &JavaConfig,
 ) -> Result<()> {
     let code = str::from_utf8(&generated_code).map_err(|err| {
         DiagnosticError::new2(
@@ -274,6 +289,58 @@ pub(in crate::java_jni) fn generate_load_unload_jni_funcs(
         });
     }
 
+    let register_natives_call = if cfg.register_natives {
+        let func_name = syn::Ident::new(REGISTER_NATIVES_FUNC, proc_macro2::Span::call_site());
+        quote!(#func_name(env);)
+    } else {
+        quote!()
+    };
+
+    let on_load_hook_calls = cfg
+        .on_load_hooks
+        .iter()
+        .map(|func_path| {
+            let func = parse_hook_path(func_path)?;
+            Ok(quote! {
+                #func(java_vm, env).map_err(|err| format!("{}: {}", #func_path, err))?;
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let on_load_hooks_call = if on_load_hook_calls.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            let hooks_res: Result<(), String> = (|| {
+                #(#on_load_hook_calls)*
+                Ok(())
+            })();
+            if let Err(msg) = hooks_res {
+                log::error!("JNI_OnLoad hook failed: {}", msg);
+                let c_msg = ::std::ffi::CString::new(msg).unwrap_or_default();
+                unsafe {
+                    let ex_class = (**env).FindClass.unwrap()(
+                        env,
+                        swig_c_str!("java/lang/UnsatisfiedLinkError"),
+                    );
+                    if !ex_class.is_null() {
+                        (**env).ThrowNew.unwrap()(env, ex_class, c_msg.as_ptr());
+                        (**env).DeleteLocalRef.unwrap()(env, ex_class);
+                    }
+                }
+                return JNI_ERR as jint;
+            }
+        }
+    };
+    let on_unload_hook_calls = cfg
+        .on_unload_hooks
+        .iter()
+        .rev()
+        .map(|func_path| {
+            let func = parse_hook_path(func_path)?;
+            Ok(quote!(#func(java_vm, env);))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
     let jni_load_func: syn::Item = parse_quote! {
         #[no_mangle]
         pub extern "system" fn JNI_OnLoad(java_vm: *mut JavaVM, _reserved: *mut ::std::os::raw::c_void) -> jint {
@@ -292,6 +359,8 @@ pub(in crate::java_jni) fn generate_load_unload_jni_funcs(
             }
             assert!(!env.is_null());
             #(#find_calls)*
+            #register_natives_call
+            #on_load_hooks_call
 
             SWIG_JNI_VERSION
         }
@@ -314,6 +383,7 @@ pub(in crate::java_jni) fn generate_load_unload_jni_funcs(
                 panic!("JNI GetEnv in JNI_OnLoad failed, return code {}", res);
             }
             assert!(!env.is_null());
+            #(#on_unload_hook_calls)*
             #(#free_find_calls)*
         }
     };
@@ -0,0 +1,652 @@
+use petgraph::Direction;
+use rustc_hash::FxHashSet;
+use smol_str::SmolStr;
+use std::fmt::Write;
+
+use super::{java_code, kotlin_code, merge_rule, JavaContext};
+use crate::{
+    error::{panic_on_syn_error, Result},
+    namegen::new_unique_name,
+    source_registry::SourceId,
+    typemap::{ast::if_ty_result_return_ok_type, ty::RustType},
+    JavaConfig, JavaUnsignedMapping, WRITE_TO_MEM_FAILED_MSG,
+};
+
+/// Rust unsigned type, wider JNI type and Rust type for it
+const WIDEN_TYPES: [(&str, &str, &str); 5] = [
+    ("u8", "jshort", "i16"),
+    ("u16", "jint", "i32"),
+    ("u32", "jlong", "i64"),
+    ("u64", "jlong", "i64"),
+    ("usize", "jlong", "i64"),
+];
+
+/// Rust unsigned type, JNI type of the same width and Rust type for it
+const SAME_WIDTH_TYPES: [(&str, &str, &str); 5] = [
+    ("u8", "jbyte", "i8"),
+    ("u16", "jshort", "i16"),
+    ("u32", "jint", "i32"),
+    ("u64", "jlong", "i64"),
+    ("usize", "jlong", "i64"),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Conv {
+    /// `From` implementation exists
+    Lossless,
+    /// `TryFrom` with panic on failure
+    Checked,
+    /// `as`, bits are kept as is
+    Bits,
+    /// `as` to `u64` and then `TryFrom`
+    BitsChecked,
+}
+
+/// Generate typemaps for `u8`, `u16`, `u32`, `u64` and `usize`
+/// according to `JavaConfig::unsigned_mapping`
+pub(in crate::java_jni) fn generate_unsigned_support(ctx: &mut JavaContext) -> Result<()> {
+    let mode = ctx.cfg.unsigned_mapping;
+    let mut conv_code = String::new();
+    if mode == JavaUnsignedMapping::SameWidth {
+        for (rust_ty, jni_ty, signed_ty) in &SAME_WIDTH_TYPES {
+            let input_conv = if *rust_ty == "usize" {
+                Conv::BitsChecked
+            } else {
+                Conv::Bits
+            };
+            primitive_rules(
+                &mut conv_code,
+                (rust_ty, jni_ty, signed_ty),
+                Conv::Bits,
+                input_conv,
+            );
+            match *rust_ty {
+                "u8" => {}
+                "usize" => {
+                    copy_slice_rules(&mut conv_code, (rust_ty, signed_ty), Conv::Bits, input_conv)
+                }
+                _ => reinterpret_slice_rules(&mut conv_code, rust_ty, signed_ty),
+            }
+        }
+    } else {
+        for (rust_ty, jni_ty, signed_ty) in &WIDEN_TYPES {
+            let big = *rust_ty == "u64" || *rust_ty == "usize";
+            if big && mode == JavaUnsignedMapping::BigInteger {
+                continue;
+            }
+            let output_conv = match (big, mode) {
+                (false, _) => Conv::Lossless,
+                (true, JavaUnsignedMapping::Checked) => Conv::Bits,
+                (true, _) => Conv::Checked,
+            };
+            primitive_rules(
+                &mut conv_code,
+                (rust_ty, jni_ty, signed_ty),
+                output_conv,
+                Conv::Checked,
+            );
+            // slices of `u8` mapped to `byte []` independent of settings
+            if *rust_ty != "u8" {
+                copy_slice_rules(
+                    &mut conv_code,
+                    (rust_ty, signed_ty),
+                    output_conv,
+                    Conv::Checked,
+                );
+            }
+        }
+        if mode == JavaUnsignedMapping::BigInteger {
+            conv_code.push_str(BIG_INTEGER_TYPEMAPS);
+            ctx.java_type_to_jni_sig_map.insert(
+                "java.math.BigInteger".into(),
+                "Ljava.math.BigInteger;".into(),
+            );
+            ctx.java_type_to_jni_sig_map.insert(
+                "java.math.BigInteger []".into(),
+                "[Ljava.math.BigInteger;".into(),
+            );
+            let helper_code = BIG_INTEGER_HELPERS_CODE.to_string();
+            ctx.rust_code
+                .push(syn::parse_str(&helper_code).unwrap_or_else(|err| {
+                    panic_on_syn_error("java/jni internal java.math.BigInteger", helper_code, err)
+                }));
+        }
+    }
+    ctx.conv_map
+        .merge(SourceId::none(), &conv_code, ctx.pointer_target_width)?;
+    for rule in ctx.conv_map.take_not_merged_not_generic_rules() {
+        merge_rule(ctx, rule)?;
+    }
+    Ok(())
+}
+
+fn conv_expr(conv: Conv, from: &str, to: &str, expr: &str) -> String {
+    match conv {
+        Conv::Lossless => format!("{}::from({})", to, expr),
+        Conv::Checked => format!(
+            r#"<{to} as ::std::convert::TryFrom<{from}>>::try_from({expr})
+            .expect("invalid {from}, in {from} => {to} conversation")"#,
+            to = to,
+            from = from,
+            expr = expr
+        ),
+        Conv::Bits => format!("{} as {}", expr, to),
+        Conv::BitsChecked => format!(
+            r#"<{to} as ::std::convert::TryFrom<u64>>::try_from({expr} as u64)
+            .expect("invalid {from}, in {from} => {to} conversation")"#,
+            to = to,
+            from = from,
+            expr = expr
+        ),
+    }
+}
+
+/// Rules for scalar and `Option`, `Option<ixx>` already has mapping to Java
+fn primitive_rules(
+    code: &mut String,
+    (rust_ty, jni_ty, signed_ty): (&str, &str, &str),
+    output_conv: Conv,
+    input_conv: Conv,
+) {
+    write!(
+        code,
+        r#"
+foreign_typemap!(
+    ($p:r_type) {rust_ty} => {jni_ty} {{
+        $out = {to_jni};
+    }};
+    ($p:r_type) {rust_ty} <= {jni_ty} {{
+        $out = {from_jni};
+    }};
+);
+foreign_typemap!(
+    ($p:r_type) Option<{rust_ty}> => Option<{signed_ty}> {{
+        $out = $p.map(|x| {to_signed});
+    }};
+    ($p:r_type) Option<{rust_ty}> <= Option<{signed_ty}> {{
+        $out = $p.map(|x| {from_signed});
+    }};
+);
+"#,
+        rust_ty = rust_ty,
+        jni_ty = jni_ty,
+        signed_ty = signed_ty,
+        to_jni = conv_expr(output_conv, rust_ty, jni_ty, "$p"),
+        from_jni = conv_expr(input_conv, jni_ty, rust_ty, "$p"),
+        to_signed = conv_expr(output_conv, rust_ty, signed_ty, "x"),
+        from_signed = conv_expr(input_conv, signed_ty, rust_ty, "x"),
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+}
+
+/// Slices and `Vec` converted element by element
+/// to/from slice of signed type that has mapping to Java array
+fn copy_slice_rules(
+    code: &mut String,
+    (rust_ty, signed_ty): (&str, &str),
+    output_conv: Conv,
+    input_conv: Conv,
+) {
+    write!(
+        code,
+        r#"
+foreign_typemap!(
+    ($p:r_type) &[{rust_ty}] => Vec<{signed_ty}> {{
+        $out = $p.iter().map(|x| {to_signed}).collect();
+    }};
+);
+foreign_typemap!(
+    ($p:r_type) Vec<{rust_ty}> <= &[{signed_ty}] {{
+        $out = $p.iter().map(|x| {from_signed}).collect();
+    }};
+);
+"#,
+        rust_ty = rust_ty,
+        signed_ty = signed_ty,
+        to_signed = conv_expr(output_conv, rust_ty, signed_ty, "*x"),
+        from_signed = conv_expr(input_conv, signed_ty, rust_ty, "*x"),
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+}
+
+/// Slices of types with the same size and alignment reinterpreted without copying
+fn reinterpret_slice_rules(code: &mut String, rust_ty: &str, signed_ty: &str) {
+    write!(
+        code,
+        r#"
+foreign_typemap!(
+    ($p:r_type) &[{rust_ty}] => &[{signed_ty}] {{
+        $out = unsafe {{
+            ::std::slice::from_raw_parts($p.as_ptr() as *const {signed_ty}, $p.len())
+        }};
+    }};
+);
+foreign_typemap!(
+    ($p:r_type) &[{rust_ty}] <= &[{signed_ty}] {{
+        $out = unsafe {{
+            ::std::slice::from_raw_parts($p.as_ptr() as *const {rust_ty}, $p.len())
+        }};
+    }};
+);
+foreign_typemap!(
+    ($p:r_type) Vec<{rust_ty}> <= &[{signed_ty}] {{
+        $out = $p.iter().map(|x| *x as {rust_ty}).collect();
+    }};
+);
+"#,
+        rust_ty = rust_ty,
+        signed_ty = signed_ty,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+}
+
+const BIG_INTEGER_TYPEMAPS: &str = r#"
+foreign_typemap!(
+    (r_type) internal_aliases::JBigInteger;
+    (f_type, option = "NoNullAnnotations") "java.math.BigInteger";
+    (f_type, option = "NullAnnotations") "@NonNull java.math.BigInteger";
+    (f_type, option = "Kotlin") "@NonNull java.math.BigInteger";
+);
+foreign_typemap!(
+    (r_type) internal_aliases::JBigIntegerOpt;
+    (f_type, option = "NoNullAnnotations", unique_prefix = "/*opt*/") "/*opt*/java.math.BigInteger";
+    (f_type, option = "NullAnnotations", unique_prefix = "/*opt*/")
+        "/*opt*/@Nullable java.math.BigInteger";
+    (f_type, option = "Kotlin", unique_prefix = "/*opt*/") "/*opt*/@Nullable java.math.BigInteger";
+);
+foreign_typemap!(
+    (r_type) internal_aliases::JBigIntegerArray;
+    (f_type, option = "NoNullAnnotations") "java.math.BigInteger []";
+    (f_type, option = "NullAnnotations") "@NonNull java.math.BigInteger []";
+    (f_type, option = "Kotlin") "@NonNull java.math.BigInteger []";
+);
+foreign_typemap!(
+    ($p:r_type) u64 => internal_aliases::JBigInteger {
+        $out = u64_to_java_big_integer(env, $p);
+    };
+    ($p:r_type) u64 <= internal_aliases::JBigInteger {
+        $out = java_big_integer_to_u64(env, $p);
+    };
+);
+foreign_typemap!(
+    ($p:r_type) usize => internal_aliases::JBigInteger {
+        $out = u64_to_java_big_integer(env, $p as u64);
+    };
+    ($p:r_type) usize <= internal_aliases::JBigInteger {
+        $out = <usize as ::std::convert::TryFrom<u64>>::try_from(java_big_integer_to_u64(env, $p))
+            .expect("invalid u64, in u64 => usize conversation");
+    };
+);
+foreign_typemap!(
+    ($p:r_type) Option<u64> => internal_aliases::JBigIntegerOpt {
+        $out = match $p {
+            Some(x) => u64_to_java_big_integer(env, x),
+            None => ::std::ptr::null_mut(),
+        };
+    };
+    ($p:r_type) Option<u64> <= internal_aliases::JBigIntegerOpt {
+        $out = if !$p.is_null() {
+            Some(java_big_integer_to_u64(env, $p))
+        } else {
+            None
+        };
+    };
+);
+foreign_typemap!(
+    ($p:r_type) Option<usize> => Option<u64> {
+        $out = $p.map(|x| x as u64);
+    };
+    ($p:r_type) Option<usize> <= Option<u64> {
+        $out = $p.map(|x| <usize as ::std::convert::TryFrom<u64>>::try_from(x)
+            .expect("invalid u64, in u64 => usize conversation"));
+    };
+);
+foreign_typemap!(
+    ($p:r_type) &[u64] => internal_aliases::JBigIntegerArray {
+        $out = u64_slice_to_java_big_integer_array(env, $p);
+    };
+);
+foreign_typemap!(
+    ($p:r_type) Vec<u64> <= internal_aliases::JBigIntegerArray {
+        $out = java_big_integer_array_to_u64_vec(env, $p);
+    };
+);
+foreign_typemap!(
+    ($p:r_type) &[usize] => Vec<u64> {
+        $out = $p.iter().map(|x| *x as u64).collect();
+    };
+);
+foreign_typemap!(
+    ($p:r_type) Vec<usize> <= Vec<u64> {
+        $out = $p
+            .into_iter()
+            .map(|x| {
+                <usize as ::std::convert::TryFrom<u64>>::try_from(x)
+                    .expect("invalid u64, in u64 => usize conversation")
+            })
+            .collect();
+    };
+);
+"#;
+
+const BIG_INTEGER_HELPERS_CODE: &str = r#"
+#[allow(dead_code)]
+fn u64_to_java_big_integer(env: *mut JNIEnv, x: u64) -> internal_aliases::JBigInteger {
+    let class: jclass = swig_jni_find_class!(JAVA_MATH_BIG_INTEGER, "java/math/BigInteger");
+    assert!(!class.is_null());
+    let constructor: jmethodID = swig_jni_get_method_id!(
+        JAVA_MATH_BIG_INTEGER_CONSTRUCTOR,
+        JAVA_MATH_BIG_INTEGER,
+        "<init>",
+        "(I[B)V"
+    );
+    assert!(!constructor.is_null());
+    let magnitude: jbyteArray = JavaU8Array::from_slice_to_raw(&x.to_be_bytes(), env);
+    let signum: jint = if x == 0 { 0 } else { 1 };
+    let ret: jobject = unsafe {
+        let ret = (**env).NewObject.unwrap()(env, class, constructor, signum, magnitude);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            panic!("java.math.BigInteger constructor failed: catch exception");
+        }
+        (**env).DeleteLocalRef.unwrap()(env, magnitude);
+        ret
+    };
+    assert!(!ret.is_null());
+    ret
+}
+
+#[allow(dead_code)]
+fn java_big_integer_to_u64(env: *mut JNIEnv, x: internal_aliases::JBigInteger) -> u64 {
+    assert!(!x.is_null(), "null instead of java.math.BigInteger");
+    let signum_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_MATH_BIG_INTEGER_SIGNUM,
+        JAVA_MATH_BIG_INTEGER,
+        "signum",
+        "()I"
+    );
+    assert!(!signum_m.is_null());
+    let bit_length_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_MATH_BIG_INTEGER_BIT_LENGTH,
+        JAVA_MATH_BIG_INTEGER,
+        "bitLength",
+        "()I"
+    );
+    assert!(!bit_length_m.is_null());
+    let long_value_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_MATH_BIG_INTEGER_LONG_VALUE,
+        JAVA_MATH_BIG_INTEGER,
+        "longValue",
+        "()J"
+    );
+    assert!(!long_value_m.is_null());
+    unsafe {
+        let signum: jint = (**env).CallIntMethod.unwrap()(env, x, signum_m);
+        let bit_length: jint = (**env).CallIntMethod.unwrap()(env, x, bit_length_m);
+        let long_value: jlong = (**env).CallLongMethod.unwrap()(env, x, long_value_m);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            panic!("java.math.BigInteger to u64 conversation failed: catch exception");
+        }
+        assert!(
+            signum >= 0 && bit_length <= 64,
+            "invalid java.math.BigInteger, in java.math.BigInteger => u64 conversation"
+        );
+        long_value as u64
+    }
+}
+
+#[allow(dead_code)]
+fn u64_slice_to_java_big_integer_array(
+    env: *mut JNIEnv,
+    arr: &[u64],
+) -> internal_aliases::JBigIntegerArray {
+    let jcls: jclass = swig_jni_find_class!(JAVA_MATH_BIG_INTEGER, "java/math/BigInteger");
+    assert!(!jcls.is_null());
+    let arr_len = <jsize as ::std::convert::TryFrom<usize>>::try_from(arr.len())
+        .expect("invalid usize, in usize => to jsize conversation");
+    let obj_arr: jobjectArray =
+        unsafe { (**env).NewObjectArray.unwrap()(env, arr_len, jcls, ::std::ptr::null_mut()) };
+    assert!(!obj_arr.is_null());
+    for (i, x) in arr.iter().enumerate() {
+        let jobj = u64_to_java_big_integer(env, *x);
+        unsafe {
+            (**env).SetObjectArrayElement.unwrap()(env, obj_arr, i as jsize, jobj);
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                panic!("SetObjectArrayElement({}) failed", i);
+            }
+            (**env).DeleteLocalRef.unwrap()(env, jobj);
+        }
+    }
+    obj_arr
+}
+
+#[allow(dead_code)]
+fn java_big_integer_array_to_u64_vec(
+    env: *mut JNIEnv,
+    arr: internal_aliases::JBigIntegerArray,
+) -> Vec<u64> {
+    assert!(!arr.is_null(), "null instead of java.math.BigInteger []");
+    let length = unsafe { (**env).GetArrayLength.unwrap()(env, arr) };
+    let len = <usize as ::std::convert::TryFrom<jsize>>::try_from(length)
+        .expect("invalid jsize, in jsize => usize conversation");
+    let mut result = Vec::with_capacity(len);
+    for i in 0..length {
+        let x = unsafe {
+            let obj = (**env).GetObjectArrayElement.unwrap()(env, arr, i);
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                panic!("Failed to retrieve element {} from this `jobjectArray'", i);
+            }
+            let x = java_big_integer_to_u64(env, obj);
+            (**env).DeleteLocalRef.unwrap()(env, obj);
+            x
+        };
+        result.push(x);
+    }
+    result
+}
+"#;
+
+/// Check of value range in Java code, before passing argument to Rust
+/// or after getting result from Rust
+#[derive(Debug)]
+pub(in crate::java_jni) struct UnsignedCheck {
+    rust_ty: &'static str,
+    shape: CheckShape,
+    /// condition for invalid value, `{v}` replaced with variable name
+    invalid_cond: &'static str,
+    /// Java type of element, for arrays and lists
+    elem_ty: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CheckShape {
+    Value,
+    Nullable,
+    OptionalLong,
+    Array,
+}
+
+/// Find out should Java code check range of value of type `arg_ty`,
+/// mapped to Java type `java_ty`
+pub(in crate::java_jni) fn unsigned_check(
+    ctx: &JavaContext,
+    arg_ty: &RustType,
+    java_ty: &str,
+    direction: Direction,
+) -> Option<UnsignedCheck> {
+    let mode = ctx.cfg.unsigned_mapping;
+    if mode == JavaUnsignedMapping::Widen || mode == JavaUnsignedMapping::SameWidth {
+        return None;
+    }
+    let ty = if_ty_result_return_ok_type(&arg_ty.ty).unwrap_or_else(|| arg_ty.ty.clone());
+    let (shape, rust_ty) = unsigned_type_shape(&ty)?;
+    let java_ty = java_code::filter_null_annotation(java_ty);
+    let shape = if shape == CheckShape::Nullable && java_ty.contains("OptionalLong") {
+        CheckShape::OptionalLong
+    } else {
+        shape
+    };
+    let big = rust_ty == "u64" || (rust_ty == "usize" && ctx.pointer_target_width == 64);
+    let big_integer =
+        mode == JavaUnsignedMapping::BigInteger && (rust_ty == "u64" || rust_ty == "usize");
+    let invalid_cond = match direction {
+        Direction::Incoming => match rust_ty {
+            "u8" if shape == CheckShape::Array => return None,
+            "u8" => "{v} < 0 || {v} > 0xFF",
+            "u16" => "{v} < 0 || {v} > 0xFFFF",
+            "u32" => "{v} < 0 || {v} > 0xFFFFFFFFL",
+            _ if big_integer && big => "{v}.signum() < 0 || {v}.bitLength() > 64",
+            _ if big_integer => "{v}.signum() < 0 || {v}.bitLength() > 32",
+            _ if big => "{v} < 0",
+            _ => "{v} < 0 || {v} > 0xFFFFFFFFL",
+        },
+        Direction::Outgoing => {
+            if mode != JavaUnsignedMapping::Checked || !big {
+                return None;
+            }
+            "{v} < 0"
+        }
+    };
+    let elem_ty = match rust_ty {
+        "u8" => "short",
+        "u16" => "int",
+        _ if big_integer => "java.math.BigInteger",
+        _ => "long",
+    };
+    Some(UnsignedCheck {
+        rust_ty,
+        shape,
+        invalid_cond,
+        elem_ty,
+    })
+}
+
+fn unsigned_type_shape(ty: &syn::Type) -> Option<(CheckShape, &'static str)> {
+    fn unsigned_name(ty: &syn::Type) -> Option<&'static str> {
+        if let syn::Type::Path(syn::TypePath { qself: None, path }) = ty {
+            if let Some(ident) = path.get_ident() {
+                return ["u8", "u16", "u32", "u64", "usize"]
+                    .iter()
+                    .find(|x| ident == *x)
+                    .copied();
+            }
+        }
+        None
+    }
+    fn generic_arg(ty: &syn::Type, name: &str) -> Option<syn::Type> {
+        if let syn::Type::Path(syn::TypePath { qself: None, path }) = ty {
+            let last = path.segments.last()?;
+            if last.ident != name {
+                return None;
+            }
+            if let syn::PathArguments::AngleBracketed(ref args) = last.arguments {
+                if args.args.len() == 1 {
+                    if let syn::GenericArgument::Type(ref ty) = args.args[0] {
+                        return Some(ty.clone());
+                    }
+                }
+            }
+        }
+        None
+    }
+    if let Some(name) = unsigned_name(ty) {
+        return Some((CheckShape::Value, name));
+    }
+    if let Some(elem) = generic_arg(ty, "Option") {
+        return unsigned_name(&elem).map(|name| (CheckShape::Nullable, name));
+    }
+    if let Some(elem) = generic_arg(ty, "Vec") {
+        return unsigned_name(&elem).map(|name| (CheckShape::Array, name));
+    }
+    if let syn::Type::Reference(syn::TypeReference { ref elem, .. }) = ty {
+        if let syn::Type::Slice(syn::TypeSlice { ref elem, .. }) = **elem {
+            return unsigned_name(elem).map(|name| (CheckShape::Array, name));
+        }
+    }
+    None
+}
+
+/// Java/Kotlin code to check range of argument
+pub(in crate::java_jni) fn arg_check_code(
+    cfg: &JavaConfig,
+    check: &UnsignedCheck,
+    arg_name: &str,
+    known_names: &mut FxHashSet<SmolStr>,
+) -> String {
+    check_code(
+        cfg,
+        check,
+        arg_name,
+        "IllegalArgumentException",
+        &format!("{} is out of range of {}", arg_name, check.rust_ty),
+        known_names,
+    )
+}
+
+/// Java/Kotlin code to check that result fits into Java type
+pub(in crate::java_jni) fn ret_check_code(
+    cfg: &JavaConfig,
+    check: &UnsignedCheck,
+    ret_name: &str,
+    known_names: &mut FxHashSet<SmolStr>,
+) -> String {
+    check_code(
+        cfg,
+        check,
+        ret_name,
+        "ArithmeticException",
+        &format!("result of type {} is out of range of long", check.rust_ty),
+        known_names,
+    )
+}
+
+fn check_code(
+    cfg: &JavaConfig,
+    check: &UnsignedCheck,
+    var_name: &str,
+    exception: &str,
+    msg: &str,
+    known_names: &mut FxHashSet<SmolStr>,
+) -> String {
+    let kotlin = kotlin_code::use_kotlin(cfg);
+    let throw = if kotlin {
+        format!("throw {}(\"{}\")", exception, msg)
+    } else {
+        format!("throw new {}(\"{}\");", exception, msg)
+    };
+    match check.shape {
+        CheckShape::Value => format!(
+            "\n        if ({})\n            {}",
+            check.invalid_cond.replace("{v}", var_name),
+            throw
+        ),
+        CheckShape::Nullable => format!(
+            "\n        if ({v} != null && ({cond}))\n            {throw}",
+            v = var_name,
+            cond = check.invalid_cond.replace("{v}", var_name),
+            throw = throw
+        ),
+        CheckShape::OptionalLong => format!(
+            "\n        if ({v}.isPresent() && ({cond}))\n            {throw}",
+            v = var_name,
+            cond = check
+                .invalid_cond
+                .replace("{v}", &format!("{}.getAsLong()", var_name)),
+            throw = throw
+        ),
+        CheckShape::Array => {
+            let elem = new_unique_name(known_names, "elem");
+            known_names.insert(elem.clone());
+            let for_header = if kotlin {
+                format!("for ({} in {})", elem, var_name)
+            } else {
+                format!("for ({} {} : {})", check.elem_ty, elem, var_name)
+            };
+            format!(
+                "\n        {for_header} {{\n            if ({cond})\n                {throw}\n        }}",
+                for_header = for_header,
+                cond = check.invalid_cond.replace("{v}", &elem),
+                throw = throw
+            )
+        }
+    }
+}
@@ -32,6 +32,7 @@ macro_rules! parse_type_spanned_checked {
 
 mod code_parse;
 mod cpp;
+mod doc_comments;
 mod error;
 mod extension;
 pub mod file_cache;
@@ -71,12 +72,20 @@ pub(crate) static SMART_PTR_COPY_TRAIT: &str = "SmartPtrCopy";
 pub(crate) static COPY_TRAIT: &str = "Copy";
 pub(crate) static CLONE_TRAIT: &str = "Clone";
 pub(crate) static PLAIN_CLASS: &str = "PlainClass";
+pub(crate) static SHARED_PTR_CLASS: &str = "SharedPtr";
 pub(crate) static CAMEL_CASE_ALIASES: &str = "camelCaseAliases";
-pub(crate) static KNOWN_CLASS_DERIVES: [&str; 5] = [
+pub(crate) static RESULT_AS_VARIANT: &str = "ResultAsVariant";
+pub(crate) static FAST_NATIVE: &str = "FastNative";
+pub(crate) static CRITICAL_NATIVE: &str = "CriticalNative";
+pub(crate) static KNOWN_METHOD_ATTRS: [&str; 3] = [RESULT_AS_VARIANT, FAST_NATIVE, CRITICAL_NATIVE];
+pub(crate) static PRIMITIVE_ARRAY_CRITICAL: &str = "PrimitiveArrayCritical";
+pub(crate) static KNOWN_JAVA_ARG_ATTRS: [&str; 1] = [PRIMITIVE_ARRAY_CRITICAL];
+pub(crate) static KNOWN_CLASS_DERIVES: [&str; 6] = [
     CLONE_TRAIT,
     COPY_TRAIT,
     SMART_PTR_COPY_TRAIT,
     PLAIN_CLASS,
+    SHARED_PTR_CLASS,
     CAMEL_CASE_ALIASES,
 ];
 
@@ -111,6 +120,18 @@ pub struct JavaConfig {
     null_annotation_package: Option<String>,
     optional_package: String,
     reachability_fence: JavaReachabilityFence,
+    exceptions: Vec<JavaException>,
+    callback_exception_policy: JavaCallbackExceptionPolicy,
+    use_direct_byte_buffer: bool,
+    register_natives: bool,
+    generate_keep_rules: bool,
+    object_cleanup: JavaObjectCleanup,
+    jvm_language: JvmLanguage,
+    check_deleted_objects: bool,
+    vec_mapping: JavaVecMapping,
+    unsigned_mapping: JavaUnsignedMapping,
+    on_load_hooks: Vec<String>,
+    on_unload_hooks: Vec<String>,
 }
 
 impl JavaConfig {
@@ -125,6 +146,18 @@ impl JavaConfig {
             null_annotation_package: None,
             optional_package: "java.util".to_string(),
             reachability_fence: JavaReachabilityFence::GenerateFence(8),
+            exceptions: Vec::new(),
+            callback_exception_policy: JavaCallbackExceptionPolicy::Log,
+            use_direct_byte_buffer: false,
+            register_natives: false,
+            generate_keep_rules: false,
+            object_cleanup: JavaObjectCleanup::Finalize,
+            jvm_language: JvmLanguage::Java,
+            check_deleted_objects: true,
+            vec_mapping: JavaVecMapping::Array,
+            unsigned_mapping: JavaUnsignedMapping::Widen,
+            on_load_hooks: Vec::new(),
+            on_unload_hooks: Vec::new(),
         }
     }
     /// Use @NonNull for types where appropriate
@@ -172,6 +205,241 @@ impl JavaConfig {
         self.reachability_fence = reachability_fence;
         self
     }
+    /// Generate Java exception class (and subclasses for enum variants)
+    /// for Rust error type. Methods that return `Result<T, ErrorType>`
+    /// throw this exception instead of `java.lang.Exception`
+    /// and declare it in `throws`
+    pub fn register_exception(mut self, exception: JavaException) -> JavaConfig {
+        self.exceptions.push(exception);
+        self
+    }
+    /// What to do if Java implementation of callback method throws exception,
+    /// and method doesn't return `Result<T, E>`, default is
+    /// `JavaCallbackExceptionPolicy::Log`.
+    /// For methods that return `Result<T, E>` exception converted
+    /// to `Err` via `From<JavaCallbackException>`
+    pub fn callback_exception_policy(
+        mut self,
+        callback_exception_policy: JavaCallbackExceptionPolicy,
+    ) -> JavaConfig {
+        self.callback_exception_policy = callback_exception_policy;
+        self
+    }
+    /// Map `&[u8]`, `&mut [u8]` and `Vec<u8>` arguments to direct `java.nio.ByteBuffer`
+    /// instead of `byte []`, so Rust code works with memory of buffer without copying.
+    /// The whole memory of argument buffer, from zero to capacity, is passed to Rust,
+    /// position and limit are ignored. If buffer is null or not direct
+    /// `IllegalArgumentException` is thrown.
+    /// `Vec<u8>` returned from Rust mapped to direct `java.nio.ByteBuffer`
+    /// on top of memory allocated by Rust, this memory is freed
+    /// after buffer becomes unreachable, so keep reference to buffer
+    /// while you use its `slice()` or `duplicate()`.
+    /// Default is false
+    pub fn use_direct_byte_buffer(mut self, use_direct_byte_buffer: bool) -> JavaConfig {
+        self.use_direct_byte_buffer = use_direct_byte_buffer;
+        self
+    }
+    /// Do not export `Java_package_Class_method` symbols for native methods,
+    /// instead register them via `RegisterNatives` inside generated `JNI_OnLoad`.
+    /// Default is false
+    pub fn register_natives(mut self, register_natives: bool) -> JavaConfig {
+        self.register_natives = register_natives;
+        self
+    }
+    /// Generate `proguard-rules.pro` in `output_dir` with ProGuard/R8 rules
+    /// to keep classes and members, that Rust code accesses via JNI by name.
+    /// Default is false
+    pub fn generate_keep_rules(mut self, generate_keep_rules: bool) -> JavaConfig {
+        self.generate_keep_rules = generate_keep_rules;
+        self
+    }
+    /// Call Rust function at the end of generated `JNI_OnLoad`,
+    /// when classes and methods are cached and natives are registered.
+    /// Function should have signature
+    /// `fn(java_vm: *mut JavaVM, env: *mut JNIEnv) -> Result<(), E>`,
+    /// where `E: Display`. Hooks are called in order of registration,
+    /// if hook returns `Err` the rest of hooks are not called,
+    /// `JNI_OnLoad` throws `UnsatisfiedLinkError` with error message
+    /// and returns `JNI_ERR`, so `System.loadLibrary` fails
+    /// # Arguments
+    /// * `func_path` - path to function, for example `crate::jni_init::init_logger`
+    pub fn on_load_hook(mut self, func_path: String) -> JavaConfig {
+        self.on_load_hooks.push(func_path);
+        self
+    }
+    /// Call Rust function at the beginning of generated `JNI_OnUnload`,
+    /// before cached classes are released.
+    /// Function should have signature `fn(java_vm: *mut JavaVM, env: *mut JNIEnv)`.
+    /// Hooks are called in reverse order of registration
+    /// # Arguments
+    /// * `func_path` - path to function, for example `crate::jni_init::shutdown`
+    pub fn on_unload_hook(mut self, func_path: String) -> JavaConfig {
+        self.on_unload_hooks.push(func_path);
+        self
+    }
+    /// How generated Java classes free memory allocated by Rust,
+    /// default is `JavaObjectCleanup::Finalize`
+    pub fn object_cleanup(mut self, object_cleanup: JavaObjectCleanup) -> JavaConfig {
+        self.object_cleanup = object_cleanup;
+        self
+    }
+    /// Generated methods check that object was not deleted yet,
+    /// and throw `IllegalStateException` otherwise, instead of passing
+    /// null pointer to Rust. You can disable it for release builds.
+    /// Default is true
+    pub fn check_deleted_objects(mut self, check_deleted_objects: bool) -> JavaConfig {
+        self.check_deleted_objects = check_deleted_objects;
+        self
+    }
+    /// Language of generated code, the same Rust code is used for JNI.
+    /// Typemaps and `foreign_code` can be selected for specific
+    /// language via `option = "Java"` or `option = "Kotlin"`.
+    /// Default is `JvmLanguage::Java`
+    pub fn jvm_language(mut self, jvm_language: JvmLanguage) -> JavaConfig {
+        self.jvm_language = jvm_language;
+        self
+    }
+    /// How to map `Vec<T>` and `&[T]` of foreign classes, `String` and
+    /// primitive types. Typemaps can be selected for specific mapping
+    /// via `option = "JavaList"` or `option = "NoJavaList"`.
+    /// Default is `JavaVecMapping::Array`
+    pub fn vec_mapping(mut self, vec_mapping: JavaVecMapping) -> JavaConfig {
+        self.vec_mapping = vec_mapping;
+        self
+    }
+    /// How to map `u8`, `u16`, `u32`, `u64` and `usize`, plus `Option`
+    /// and slices/`Vec` of them.
+    /// Default is `JavaUnsignedMapping::Widen`
+    pub fn unsigned_mapping(mut self, unsigned_mapping: JavaUnsignedMapping) -> JavaConfig {
+        self.unsigned_mapping = unsigned_mapping;
+        self
+    }
+}
+
+/// Description of Java exception for Rust error type,
+/// see `JavaConfig::register_exception`
+#[derive(Debug, Clone)]
+pub struct JavaException {
+    rust_error_type: String,
+    class_name: String,
+    base_class: String,
+    variants: Vec<(String, String)>,
+    error_codes: Vec<(String, i32)>,
+}
+
+impl JavaException {
+    /// Create `JavaException`
+    /// # Arguments
+    /// * `rust_error_type` - Rust type that implements `std::error::Error`
+    /// * `class_name` - name of Java exception class to generate
+    pub fn new(rust_error_type: String, class_name: String) -> JavaException {
+        JavaException {
+            rust_error_type,
+            class_name,
+            base_class: "Exception".into(),
+            variants: Vec::new(),
+            error_codes: Vec::new(),
+        }
+    }
+    /// Java class that generated exception extends,
+    /// default is "Exception", so exception is checked
+    pub fn extends(mut self, base_class: String) -> JavaException {
+        self.base_class = base_class;
+        self
+    }
+    /// Generate subclass of exception for enum variant
+    /// # Arguments
+    /// * `rust_variant` - name of variant of Rust error enum, for example `NotFound`
+    /// * `class_name` - name of Java class for this variant
+    pub fn variant(mut self, rust_variant: String, class_name: String) -> JavaException {
+        self.variants.push((rust_variant, class_name));
+        self
+    }
+    /// Alternative to subclasses: exception gets `getErrorCode()`
+    /// and constant with name of variant in upper case, like `NOT_FOUND`.
+    /// Error code of not listed variants is 0
+    /// # Arguments
+    /// * `rust_variant` - name of variant of Rust error enum, for example `NotFound`
+    /// * `code` - value returned by `getErrorCode()` for this variant
+    pub fn error_code(mut self, rust_variant: String, code: i32) -> JavaException {
+        self.error_codes.push((rust_variant, code));
+        self
+    }
+}
+
+/// How to handle exception thrown by Java implementation of callback
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JavaCallbackExceptionPolicy {
+    /// Log exception, print it via `ExceptionDescribe` and clear it
+    Log,
+    /// Clear exception and panic with its class name and message
+    Panic,
+    /// Log exception and abort process
+    Abort,
+}
+
+/// How Java objects free memory allocated by Rust,
+/// if user forgot to call `delete`/`close`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JavaObjectCleanup {
+    /// `delete` method plus overridden `Object.finalize`
+    Finalize,
+    /// Classes implement `AutoCloseable` (`close` is the same as `delete`)
+    /// and register themselves in shared `java.lang.ref.Cleaner`.
+    /// Requires Java 9+ or Android API level 33+
+    Cleaner,
+    /// The same as `Cleaner`, but instead of `java.lang.ref.Cleaner`
+    /// uses generated cleaner based on `PhantomReference` and daemon thread,
+    /// for Java 8 and old Android API levels
+    PhantomReferenceCleaner,
+}
+
+/// Language of code generated for JVM
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JvmLanguage {
+    Java,
+    /// Kotlin classes with nullable types instead of `Optional`,
+    /// static methods in `companion object`, `java.io.Closeable`
+    /// implementation and `enum class` for enums
+    Kotlin,
+}
+
+/// How `Vec<T>` and `&[T]` mapped to Java
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JavaVecMapping {
+    /// Java arrays, `Foo []`, `String []`, `int []` and so on
+    Array,
+    /// `Vec<T>` returned from Rust converted to `java.util.List<T>`,
+    /// `Vec<T>` and `&[T]` arguments accept `java.util.List<? extends T>`.
+    /// Primitive types are boxed
+    List,
+    /// The same as `List`, but `Vec<T>` returned from Rust is not
+    /// copied, instead it is owned by generated `RustList<T>` class,
+    /// read-only `java.util.AbstractList` that converts elements on access.
+    /// Elements are cloned on access, so `Vec<T>` of foreign classes without
+    /// `derive(Clone)` are copied to `java.util.ArrayList` like with `List`
+    LazyList,
+}
+
+/// How unsigned integer types mapped to Java
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JavaUnsignedMapping {
+    /// Wider signed types: `u8` to `short`, `u16` to `int`, `u32` to `long`,
+    /// `u64` and `usize` to `long`. Values that do not fit cause panic
+    Widen,
+    /// The same Java types as `Widen`, but generated Java code throws
+    /// `IllegalArgumentException` for arguments out of range, and
+    /// `ArithmeticException` if `u64`/`usize` result is bigger than `Long.MAX_VALUE`.
+    /// Java callbacks get such `u64` as is, without checks
+    Checked,
+    /// `u64` and `usize` mapped to `java.math.BigInteger`,
+    /// other unsigned types are handled like in `Checked`
+    BigInteger,
+    /// Signed types of the same width: `u8` to `byte`, `u16` to `short`,
+    /// `u32` to `int`, `u64` and `usize` to `long`. Bits passed as is,
+    /// so use `Byte.toUnsignedInt`, `Integer.toUnsignedLong`,
+    /// `Long.toUnsignedString` and so on to interpret values on Java side
+    SameWidth,
 }
 
 /// What reachability fence to use
@@ -198,6 +466,50 @@ pub struct CppConfig {
     /// Create separate *_impl.hpp files with methods implementations.
     /// Can be necessary for the project with circular dependencies between classes.
     separate_impl_headers: bool,
+    /// Throw exception instead of returning variant for `Result`
+    use_exceptions: bool,
+    /// Generate only C headers, without C++ wrappers
+    pure_c_api: bool,
+    /// Generate CMake package config and pkg-config file
+    package: Option<CppPackage>,
+    /// Add typemaps for Qt types, see `CPP_QT_TYPEMAPS`
+    qt_typemaps: bool,
+    /// Prefix for all exported C symbols and C types
+    symbol_prefix: Option<String>,
+    /// Version tag, added to C symbols after `symbol_prefix`
+    symbol_version: Option<String>,
+}
+
+/// Description of native library to generate CMake package config
+/// (`<namespace>Config.cmake`) and pkg-config file (`<namespace>.pc`),
+/// see `CppConfig::generate_package`
+#[derive(Debug, Clone)]
+pub struct CppPackage {
+    lib_name: String,
+    lib_dir: PathBuf,
+    version: String,
+    description: String,
+}
+
+impl CppPackage {
+    /// Create `CppPackage`
+    /// # Arguments
+    /// * `lib_name` - name of cdylib without prefix and suffix, for example "mylib"
+    /// * `lib_dir` - directory where cdylib is placed
+    /// * `version` - version of package
+    pub fn new(lib_name: String, lib_dir: PathBuf, version: String) -> CppPackage {
+        CppPackage {
+            lib_name,
+            lib_dir,
+            version,
+            description: String::new(),
+        }
+    }
+    /// Description for pkg-config file
+    pub fn description(mut self, description: String) -> CppPackage {
+        self.description = description;
+        self
+    }
 }
 
 /// To which `C++` type map `std::option::Option`
@@ -207,6 +519,8 @@ pub enum CppOptional {
     Std17,
     /// `boost::optional`
     Boost,
+    /// `RustOptional` from bundled `rust_optional_impl.hpp`, requires only C++11
+    Bundled,
 }
 
 impl From<CppOptional> for &'static str {
@@ -214,6 +528,7 @@ impl From<CppOptional> for &'static str {
         match x {
             CppOptional::Std17 => "CppOptional::Std17",
             CppOptional::Boost => "CppOptional::Boost",
+            CppOptional::Bundled => "CppOptional::Bundled",
         }
     }
 }
@@ -225,6 +540,8 @@ pub enum CppVariant {
     Std17,
     /// `boost::variant`
     Boost,
+    /// `RustVariant` from bundled `rust_variant_impl.hpp`, requires only C++11
+    Bundled,
 }
 
 impl From<CppVariant> for &'static str {
@@ -232,6 +549,7 @@ impl From<CppVariant> for &'static str {
         match x {
             CppVariant::Std17 => "CppVariant::Std17",
             CppVariant::Boost => "CppVariant::Boost",
+            CppVariant::Bundled => "CppVariant::Bundled",
         }
     }
 }
@@ -243,6 +561,8 @@ pub enum CppStrView {
     Std17,
     /// `boost::string_view`
     Boost,
+    /// `RustStringView` from bundled `rust_string_view_impl.hpp`, requires only C++11
+    Bundled,
 }
 
 impl From<CppStrView> for &'static str {
@@ -250,6 +570,7 @@ impl From<CppStrView> for &'static str {
         match x {
             CppStrView::Std17 => "CppStrView::Std17",
             CppStrView::Boost => "CppStrView::Boost",
+            CppStrView::Bundled => "CppStrView::Bundled",
         }
     }
 }
@@ -267,6 +588,12 @@ impl CppConfig {
             cpp_variant: CppVariant::Std17,
             cpp_str_view: CppStrView::Std17,
             separate_impl_headers: false,
+            use_exceptions: false,
+            pure_c_api: false,
+            package: None,
+            qt_typemaps: false,
+            symbol_prefix: None,
+            symbol_version: None,
         }
     }
     pub fn cpp_optional(self, cpp_optional: CppOptional) -> CppConfig {
@@ -297,6 +624,17 @@ impl CppConfig {
             ..self
         }
     }
+    /// Use header-only implementations generated into output directory
+    /// for that fit: Result -> RustVariant, Option -> RustOptional,
+    /// &str -> RustStringView. The generated code requires only C++11.
+    pub fn use_bundled(self) -> CppConfig {
+        CppConfig {
+            cpp_variant: CppVariant::Bundled,
+            cpp_optional: CppOptional::Bundled,
+            cpp_str_view: CppStrView::Bundled,
+            ..self
+        }
+    }
     /// Create separate *_impl.hpp files with methods' implementations.
     /// Can be necessary for the project with circular dependencies between classes.
     pub fn separate_impl_headers(self, separate_impl_headers: bool) -> CppConfig {
@@ -305,8 +643,71 @@ impl CppConfig {
             ..self
         }
     }
+    /// Methods that return `Result<T, E>` return `T` and throw `RustError<E>`
+    /// (derived from `std::runtime_error`) instead of returning variant,
+    /// such methods are not marked as `noexcept`.
+    /// Use `#[ResultAsVariant]` attribute to keep variant for some method.
+    /// Constructors that return `Result` are not supported in this mode,
+    /// it is reported as error, use static method that returns `Result<Self, E>` instead.
+    pub fn use_exceptions(self, use_exceptions: bool) -> CppConfig {
+        CppConfig {
+            use_exceptions,
+            ..self
+        }
+    }
+    /// Generate only C API: `c_*.h` headers for classes, callbacks and enums,
+    /// without C++ wrappers, so the result can be used with C compiler.
+    /// Methods that return `Result<T, E>` return `uint8_t` (`1` for `Ok`)
+    /// and pass `T` or `E` via out parameters, that can be `NULL`.
+    /// Destructors are named `<Type>_free`, copy functions `<Type>_clone`,
+    /// for example `Foo_free`, `CRustString_free`, `CRustVeci32_free`.
+    pub fn pure_c_api(self, pure_c_api: bool) -> CppConfig {
+        CppConfig { pure_c_api, ..self }
+    }
+    /// Write `<namespace>Config.cmake` with imported target `<namespace>::<namespace>`
+    /// and `<namespace>.pc` into output directory.
+    /// Both of them list all generated headers.
+    pub fn generate_package(self, package: CppPackage) -> CppConfig {
+        CppConfig {
+            package: Some(package),
+            ..self
+        }
+    }
+    /// Add typemaps for `QString`, `QByteArray`, `QVector`, `QList` and `QDateTime`,
+    /// the same as `Generator::merge_type_map("qt-include.rs", CPP_QT_TYPEMAPS)`
+    pub fn qt_typemaps(self, qt_typemaps: bool) -> CppConfig {
+        CppConfig {
+            qt_typemaps,
+            ..self
+        }
+    }
+    /// Add `<symbol_prefix>_` to names of all exported C functions and C types
+    /// (`Foo_new` -> `mylib_Foo_new`, `CRustString` -> `mylib_CRustString`),
+    /// so several libraries generated by flapigen can be linked together.
+    /// Names of C++ classes and methods stay the same.
+    pub fn symbol_prefix(self, symbol_prefix: String) -> CppConfig {
+        CppConfig {
+            symbol_prefix: Some(symbol_prefix),
+            ..self
+        }
+    }
+    /// Add `<symbol_version>_` after `symbol_prefix` to names of C functions and C types
+    /// (`mylib_v2_Foo_new`), so several versions of the same library can be linked together
+    pub fn symbol_version(self, symbol_version: String) -> CppConfig {
+        CppConfig {
+            symbol_version: Some(symbol_version),
+            ..self
+        }
+    }
 }
 
+/// Typemaps for Qt types to use with `Generator::merge_type_map`
+/// or via `CppConfig::qt_typemaps`. They provide Rust aliases:
+/// `QString` (`String`), `QRustStrView` (`&str`), `QByteArray` (`Vec<u8>`),
+/// `QRustByteView` (`&[u8]`), `QVector<T>` and `QList<T>` (`Vec<T>` of foreign classes),
+/// `QDateTime` (`SystemTime`), that mapped to the Qt types with the same names.
+pub static CPP_QT_TYPEMAPS: &str = include_str!("cpp/qt-include.rs");
+
 /// Configuration for Python binding generation
 pub struct PythonConfig {
     module_name: String,
@@ -378,11 +779,17 @@ impl Generator {
                     }),
                 );
             }
-            LanguageConfig::CppConfig(..) => {
+            LanguageConfig::CppConfig(ref cpp_cfg) => {
                 conv_map_source.push(src_reg.register(SourceCode {
                     id_of_code: "cpp-include.rs".into(),
                     code: include_str!("cpp/cpp-include.rs").into(),
                 }));
+                if cpp_cfg.qt_typemaps {
+                    conv_map_source.push(src_reg.register(SourceCode {
+                        id_of_code: "qt-include.rs".into(),
+                        code: CPP_QT_TYPEMAPS.into(),
+                    }));
+                }
                 foreign_lang_helpers.push(SourceCode {
                     id_of_code: "rust_vec_impl.hpp".into(),
                     code: include_str!("cpp/rust_vec_impl.hpp").into(),
@@ -403,6 +810,18 @@ impl Generator {
                     id_of_code: "rust_slice_tmpl.hpp".into(),
                     code: include_str!("cpp/rust_slice_tmpl.hpp").into(),
                 });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_optional_impl.hpp".into(),
+                    code: include_str!("cpp/rust_optional_impl.hpp").into(),
+                });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_variant_impl.hpp".into(),
+                    code: include_str!("cpp/rust_variant_impl.hpp").into(),
+                });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_string_view_impl.hpp".into(),
+                    code: include_str!("cpp/rust_string_view_impl.hpp").into(),
+                });
             }
             LanguageConfig::PythonConfig(..) => {
                 conv_map_source.push(src_reg.register(SourceCode {
@@ -459,7 +878,8 @@ impl Generator {
 
     /// Register callback to extend/modify class, if `foreign_class` has #[derive(attr_name)]
     /// then after foreign code generation `cb` would be called, with full code of module,
-    /// plus class name
+    /// plus class name. Note that if `JavaConfig::object_cleanup` is not `Finalize`
+    /// header of Java class is `class Foo implements AutoCloseable {`
     pub fn register_class_attribute_callback<F>(mut self, attr_name: &str, cb: F) -> Self
     where
         F: Fn(&mut Vec<u8>, &str) + 'static,
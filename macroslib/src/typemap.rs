@@ -35,7 +35,8 @@ use crate::{
 use ast::ConversationResult;
 
 pub(crate) use typemap_macro::{
-    CItem, CItems, ExpandedFType, TypeMapConvRuleInfo, TypeMapConvRuleInfoExpanderHelper,
+    CItem, CItems, ExpandedFType, FTypeConvRule, TypeMapConvRuleInfo,
+    TypeMapConvRuleInfoExpanderHelper,
 };
 
 use self::ast::UniqueName;
@@ -937,6 +938,10 @@ impl TypeMap {
         self.foreign_classes.push(class.clone());
     }
 
+    pub(crate) fn foreign_classes(&self) -> &[ForeignClassInfo] {
+        &self.foreign_classes
+    }
+
     fn add_node<F: FnOnce() -> RustTypeS>(
         &mut self,
         key: SmolStr,
@@ -1284,9 +1289,10 @@ mod tests {
                 self_type: foo_rt.ty.clone(),
                 constructor_ret_type: foo_rt.ty.clone(),
             }),
-            foreign_code: String::new(),
+            foreign_code: vec![],
             doc_comments: vec![],
             derive_list: vec![],
+            namespace: None,
         });
 
         let rc_refcell_foo_ty = types_map
@@ -12,7 +12,7 @@ use crate::{
     file_cache::FileOperationsRegistrator,
     source_registry::SourceId,
     typemap::{
-        ast::check_if_smart_pointer_return_inner_type,
+        ast::{check_if_smart_pointer_return_inner_type, SpannedSmolStr},
         ty::RustType,
         typemap_macro::{FTypeConvRule, TypeMapConvRuleInfo},
         ForeignTypeInfo, RustTypeIdx, TypeMap,
@@ -193,17 +193,7 @@ pub(crate) fn validate_cfg_options(
     let validate_f_type_rules_opts = |rules: &[FTypeConvRule]| -> Result<()> {
         for r in rules {
             if let Some(ref opt) = r.cfg_option {
-                if !avaible_opts.contains(opt.as_str()) {
-                    return Err(DiagnosticError::new(
-                        rule.src_id,
-                        opt.sp,
-                        format!(
-                            "unsupported option {}, avaible options {:?}",
-                            opt.as_str(),
-                            avaible_opts
-                        ),
-                    ));
-                }
+                validate_cfg_option(rule.src_id, opt, avaible_opts)?;
             }
         }
         Ok(())
@@ -213,23 +203,64 @@ pub(crate) fn validate_cfg_options(
 
     for fcode in rule.f_code.iter() {
         if let Some(ref opt) = fcode.cfg_option {
-            if !avaible_opts.contains(opt.as_str()) {
-                return Err(DiagnosticError::new(
-                    rule.src_id,
-                    opt.sp,
-                    format!(
-                        "unsupported option {}, avaible options {:?}",
-                        opt.as_str(),
-                        avaible_opts
-                    ),
-                ));
-            }
+            validate_cfg_option(rule.src_id, opt, avaible_opts)?;
         }
     }
 
     Ok(())
 }
 
+pub(crate) fn validate_cfg_option(
+    src_id: SourceId,
+    opt: &SpannedSmolStr,
+    avaible_opts: &FxHashSet<&'static str>,
+) -> Result<()> {
+    if !cfg_option_parts(opt.as_str()).all(|x| avaible_opts.contains(x)) {
+        return Err(DiagnosticError::new(
+            src_id,
+            opt.sp,
+            format!(
+                "unsupported option {}, avaible options {:?}",
+                opt.as_str(),
+                avaible_opts
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Option of typemap's rule may be list of options separated by comma,
+/// in this case rule is used only if all of them enabled
+pub(crate) fn cfg_option_enabled(opt: &str, options: &FxHashSet<&'static str>) -> bool {
+    cfg_option_parts(opt).all(|x| options.contains(x))
+}
+
+/// Generic rule can be used in this configuration, if it has no `f_type` rules
+/// for such direction, or at least one of them is enabled.
+/// So several generic rules for the same type may coexist, if they have
+/// `f_type` rules for different options
+pub(crate) fn is_generic_rule_enabled(
+    rule: &TypeMapConvRuleInfo,
+    direction: petgraph::Direction,
+    options: &FxHashSet<&'static str>,
+) -> bool {
+    let f_type_rules = match direction {
+        petgraph::Direction::Outgoing => &rule.ftype_left_to_right,
+        petgraph::Direction::Incoming => &rule.ftype_right_to_left,
+    };
+    f_type_rules.is_empty()
+        || f_type_rules.iter().any(|r| {
+            r.cfg_option
+                .as_ref()
+                .map(|opt| cfg_option_enabled(opt.as_str(), options))
+                .unwrap_or(true)
+        })
+}
+
+fn cfg_option_parts(opt: &str) -> impl Iterator<Item = &str> {
+    opt.split(',').map(str::trim)
+}
+
 pub(crate) fn boxed_type(tmap: &mut TypeMap, from: &RustType) -> RustType {
     for smart_pointer in &["Box", "Rc", "Arc"] {
         if let Some(inner_ty) = check_if_smart_pointer_return_inner_type(from, *smart_pointer) {
@@ -323,7 +354,7 @@ pub(crate) fn configure_ftype_rule(
     f_type_rules.retain(|rule| {
         rule.cfg_option
             .as_ref()
-            .map(|opt| options.contains(opt.as_str()))
+            .map(|opt| cfg_option_enabled(opt.as_str(), options))
             .unwrap_or(true)
     });
     if f_type_rules.len() > 1 {
@@ -1,5 +1,6 @@
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
+use rustc_hash::FxHashSet;
 use smol_str::SmolStr;
 use std::fmt;
 use syn::{parse_quote, spanned::Spanned, Type};
@@ -7,7 +8,10 @@ use syn::{parse_quote, spanned::Spanned, Type};
 use crate::{
     error::{DiagnosticError, Result, SourceIdSpan},
     source_registry::SourceId,
-    typemap::ast::DisplayToTokens,
+    typemap::{
+        ast::{DisplayToTokens, SpannedSmolStr},
+        utils::{cfg_option_enabled, validate_cfg_option},
+    },
     SMART_PTR_COPY_TRAIT,
 };
 
@@ -17,9 +21,19 @@ pub(crate) struct ForeignClassInfo {
     pub name: Ident,
     pub methods: Vec<ForeignMethod>,
     pub self_desc: Option<SelfTypeDesc>,
-    pub foreign_code: String,
+    pub foreign_code: Vec<ForeignCode>,
     pub doc_comments: Vec<String>,
     pub derive_list: Vec<String>,
+    /// `#[namespace = "a::b"]`, used only by C++ backend
+    pub namespace: Option<String>,
+}
+
+/// Code from `foreign_code` inside `foreign_class!`,
+/// with `option = "..."` it is used only if option enabled
+#[derive(Debug, Clone)]
+pub(crate) struct ForeignCode {
+    pub cfg_option: Option<SpannedSmolStr>,
+    pub code: String,
 }
 
 /// Two types instead of one, to simplify live to developer
@@ -36,6 +50,24 @@ impl ForeignClassInfo {
     pub(crate) fn span(&self) -> Span {
         self.name.span()
     }
+    /// `foreign_code` for configuration with such `options`
+    pub(crate) fn foreign_code_for(
+        &self,
+        avaible_opts: &FxHashSet<&'static str>,
+        options: &FxHashSet<&'static str>,
+    ) -> Result<String> {
+        let mut ret = String::new();
+        for fcode in &self.foreign_code {
+            if let Some(ref opt) = fcode.cfg_option {
+                validate_cfg_option(self.src_id, opt, avaible_opts)?;
+                if !cfg_option_enabled(opt.as_str(), options) {
+                    continue;
+                }
+            }
+            ret.push_str(&fcode.code);
+        }
+        Ok(ret)
+    }
     pub(crate) fn self_type_as_ty(&self) -> Type {
         self.self_desc
             .as_ref()
@@ -128,6 +160,7 @@ pub(crate) struct NamedArg {
     pub name: SmolStr,
     pub span: Span,
     pub ty: syn::Type,
+    pub unknown_attrs: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -252,6 +285,8 @@ pub(crate) struct ForeignEnumInfo {
     pub(crate) items: Vec<ForeignEnumItem>,
     pub(crate) doc_comments: Vec<String>,
     pub(crate) derive_list: Vec<String>,
+    /// `#[namespace = "a::b"]`, used only by C++ backend
+    pub(crate) namespace: Option<String>,
 }
 
 impl ForeignEnumInfo {
@@ -282,6 +317,19 @@ impl ForeignInterface {
     pub(crate) fn src_id_span(&self) -> SourceIdSpan {
         (self.src_id, self.name.span())
     }
+    /// Can `Arc<dyn Trait>` be wrapped into type that implements
+    /// `Trait` and the same auto traits (`Send`/`Sync`) as `Box<dyn Trait>`
+    pub(crate) fn arc_can_be_boxed(&self) -> bool {
+        let has_bound = |name: &str| {
+            self.self_type.bounds.iter().any(|b| match b {
+                syn::TypeParamBound::Trait(t) => {
+                    t.path.segments.last().map_or(false, |s| s.ident == name)
+                }
+                _ => false,
+            })
+        };
+        has_bound("Send") == has_bound("Sync")
+    }
 }
 
 pub(crate) struct ForeignInterfaceMethod {
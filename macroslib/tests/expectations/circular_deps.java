@@ -1,4 +1,6 @@
 r#"public static void a(@NonNull B b) {
+        if (b == null)
+            throw new NullPointerException("b must not be null");
         long a0 = b.mNativeObj;
         do_a(a0);
 
@@ -6,6 +8,8 @@ r#"public static void a(@NonNull B b) {
     }
     private static native void do_a(long b);"#;
 r#"public static void b(@NonNull A a) {
+        if (a == null)
+            throw new NullPointerException("a must not be null");
         long a0 = a.mNativeObj;
         do_b(a0);
 
@@ -0,0 +1,7 @@
+"#include \"rust_optional_impl.hpp\"";
+"#include \"rust_variant_impl.hpp\"";
+"#include \"rust_string_view_impl.hpp\"";
+"RustVariant<int64_t, RustString> read(int32_t key) const noexcept;";
+"RustOptional<RustString> write(RustStringView key, int64_t val) noexcept;";
+"RustStringView name() const noexcept;";
+"RustOptional<double> opt() const noexcept;";
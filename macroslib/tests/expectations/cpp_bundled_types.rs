@@ -0,0 +1,8 @@
+foreign_class!(class Storage {
+    self_type Storage;
+    constructor Storage::new() -> Storage;
+    fn Storage::read(&self, key: i32) -> Result<i64, String>;
+    fn Storage::write(&mut self, key: &str, val: i64) -> Result<(), String>;
+    fn Storage::name(&self) -> &str;
+    fn Storage::opt(&self) -> Option<f64>;
+});
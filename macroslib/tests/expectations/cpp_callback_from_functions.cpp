@@ -0,0 +1,9 @@
+"#include <functional>";
+"static std::unique_ptr<Observer> from_functions(std::function<bool(int32_t)> on_event, std::function<void(RustString)> on_name);";
+"class ObserverFunctions final : public Observer {";
+r#"    bool on_event(int32_t x) noexcept override
+    {
+        return on_event_(std::move(x));
+    }"#;
+"    std::function<void(RustString)> on_name_;";
+"return std::unique_ptr<Observer>(new ObserverFunctions(std::move(on_event), std::move(on_name)));";
@@ -0,0 +1,10 @@
+foreign_callback!(callback Observer {
+    self_type Observer;
+    on_event = Observer::on_event(&self, x: i32) -> bool;
+    on_name = Observer::on_name(&self, name: String);
+});
+foreign_class!(class Storage {
+    self_type Storage;
+    constructor Storage::new() -> Storage;
+    fn Storage::set_observer(&mut self, o: Box<dyn Observer>);
+});
@@ -0,0 +1,10 @@
+"add_library(org_examples::org_examples SHARED IMPORTED)";
+"INTERFACE_COMPILE_FEATURES cxx_std_17";
+"IMPORTED_LOCATION \"/opt/storage/lib/${CMAKE_SHARED_LIBRARY_PREFIX}storage${CMAKE_SHARED_LIBRARY_SUFFIX}\"";
+"\"${CMAKE_CURRENT_LIST_DIR}/Storage.hpp\"";
+"\"${CMAKE_CURRENT_LIST_DIR}/c_Storage.h\"";
+"\"${CMAKE_CURRENT_LIST_DIR}/rust_str.h\"";
+"Version: 1.2.3";
+"Description: Storage library";
+"Libs: -L${libdir} -lstorage";
+"Cflags: -I${includedir}";
@@ -0,0 +1,5 @@
+foreign_class!(class Storage {
+    self_type Storage;
+    constructor Storage::new() -> Storage;
+    fn Storage::name(&self) -> String;
+});
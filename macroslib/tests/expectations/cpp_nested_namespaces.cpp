@@ -0,0 +1,8 @@
+"net_http_RequestOpaque *net_http_Request_new(uint32_t a0);";
+"namespace org_examples {\nnamespace net {\nnamespace http {";
+"#include \"net/Method.hpp\"";
+"net::Method method() const noexcept;";
+"namespace org_examples {\nnamespace net {\n";
+"#include \"net/http/Request.hpp\"";
+"void send(net::http::Request a0) const noexcept;";
+"#include \"net/http.hpp\"";
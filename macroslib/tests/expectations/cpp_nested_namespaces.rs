@@ -0,0 +1,19 @@
+foreign_enum!(
+#[namespace = "net"]
+enum Method {
+    GET = Method::Get,
+    POST = Method::Post,
+});
+foreign_class!(
+#[namespace = "net::http"]
+class Request {
+    self_type Request;
+    constructor Request::new(_: Method) -> Request;
+    fn Request::method(&self) -> Method;
+});
+foreign_class!(class Client {
+    self_type Client;
+    constructor Client::new() -> Client;
+    fn Client::send(&self, _: Request);
+    fn Client::last(&self) -> Option<&Request>;
+});
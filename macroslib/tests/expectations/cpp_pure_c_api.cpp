@@ -0,0 +1,12 @@
+"#include \"c_Mode.h\"";
+"#include \"c_Observer.h\"";
+"Mode_A = 0,";
+"uint8_t Storage_read(const StorageOpaque * const self, int32_t key, int64_t *ret_out, struct CRustString *err_out);";
+"uint8_t Storage_write(StorageOpaque * const self, int32_t key, int64_t val, struct CRustString *err_out);";
+"void Storage_free(const StorageOpaque *self);";
+"StorageOpaque *Storage_new(void);";
+"struct CRustVeci32 Storage_keys(const StorageOpaque * const self);";
+"SharedOpaque *Shared_clone(const SharedOpaque *);";
+"void Shared_free(const SharedOpaque *self);";
+"void CRustString_free(struct CRustString x);";
+"void CRustVeci32_free(struct CRustVeci32 v);";
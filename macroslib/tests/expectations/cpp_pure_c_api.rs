@@ -0,0 +1,23 @@
+foreign_enum!(enum Mode { A = Mode::A, B = Mode::B, });
+foreign_callback!(callback Observer {
+    self_type Observer;
+    on_event = Observer::on_event(&self, x: i32) -> bool;
+});
+foreign_class!(class Storage {
+    self_type Storage;
+    constructor Storage::new() -> Storage;
+    fn Storage::read(&self, key: i32) -> Result<i64, String>;
+    fn Storage::write(&mut self, key: i32, val: i64) -> Result<(), String>;
+    fn Storage::mode(&self) -> Mode;
+    fn Storage::set_observer(&mut self, o: Box<dyn Observer>);
+    fn Storage::keys(&self) -> Vec<i32>;
+    fn Storage::name(&self) -> String;
+});
+foreign_class!(
+    #[derive(SmartPtrCopy)]
+    class Shared {
+        self_type Shared;
+        constructor Shared::new() -> Arc<Shared>;
+        fn Shared::id(&self) -> i32;
+    }
+);
@@ -1,5 +1,5 @@
 "static QDate f() noexcept;";
-"int64_t Foo_f();";
+"int64_t Foo_f(void);";
 
 r#"template<bool OWN_DATA>
     inline QDate FooWrapper<OWN_DATA>::f() noexcept
@@ -10,7 +10,7 @@ r#"template<bool OWN_DATA>
     }"#;
 
 "static std::optional<QDate> f2() noexcept;";
-"struct CRustOptioni64 Foo_f2();";
+"struct CRustOptioni64 Foo_f2(void);";
 r#"template<bool OWN_DATA>
     inline std::optional<QDate> FooWrapper<OWN_DATA>::f2() noexcept
     {
@@ -0,0 +1,14 @@
+"#include <QString>";
+"#include <QByteArray>";
+"#include <QVector>";
+"#include <QList>";
+"#include <QDateTime>";
+"QString name() const noexcept;";
+"void set_name(QString a0) noexcept;";
+"QByteArray data() const noexcept;";
+"void set_raw(QByteArray a0) noexcept;";
+"QVector<Item> items() const noexcept;";
+"void set_list(QList<Item> a0) noexcept;";
+"return QDateTime::fromMSecsSinceEpoch(ret, Qt::UTC);";
+"#include \"rust_qt.h\"";
+"struct CRustQStringView {";
@@ -0,0 +1,17 @@
+foreign_class!(
+#[derive(SharedPtr)]
+class Item {
+    self_type Item;
+    constructor Item::new() -> Arc<Item>;
+});
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new() -> Foo;
+    fn Foo::name(&self) -> QString;
+    fn Foo::set_name(&mut self, _: QString);
+    fn Foo::data(&self) -> QByteArray;
+    fn Foo::set_raw(&mut self, _: QRustByteView);
+    fn Foo::items(&self) -> QVector<Arc<Item>>;
+    fn Foo::set_list(&mut self, _: QList<Arc<Item>>);
+    fn Foo::time(&self) -> QDateTime;
+});
@@ -0,0 +1,5 @@
+"uintptr_t private_Session_use_count(const SessionOpaque *);";
+"self_ = private_Session_clone(o.self_);";
+"return self_ != nullptr ? static_cast<long>(private_Session_use_count(self_)) : 0;";
+"const SessionWrapper *operator->() const noexcept { return this; }";
+"static int32_t take(Session s) noexcept;";
@@ -0,0 +1,8 @@
+foreign_class!(
+#[derive(SharedPtr)]
+class Session {
+    self_type Session;
+    constructor Session::new() -> Arc<Mutex<Session>>;
+    fn Session::name(&self) -> i32;
+    fn take(s: Arc<Mutex<Session>>) -> i32;
+});
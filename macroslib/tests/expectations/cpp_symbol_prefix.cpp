@@ -0,0 +1,8 @@
+"typedef struct mylib_v2_FooOpaque mylib_v2_FooOpaque;";
+"struct mylib_v2_CRustString mylib_v2_Foo_name(const mylib_v2_FooOpaque * const self);";
+"struct mylib_v2_CRustString {";
+"void mylib_v2_CRustString_free(struct mylib_v2_CRustString x);";
+"class RustString final : private mylib_v2_CRustString {";
+"class FooWrapper {";
+"RustString name() const noexcept;";
+"static mylib_v2_C_Observer to_c_interface(";
@@ -0,0 +1,10 @@
+foreign_callback!(callback Observer {
+    self_type Observer;
+    on_event = Observer::on_event(&self, x: i32);
+});
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: &str) -> Foo;
+    fn Foo::name(&self) -> String;
+    fn Foo::observe(&self, _: Box<dyn Observer>);
+});
@@ -0,0 +1,7 @@
+"#include \"rust_result_exception.hpp\"";
+"template <typename E> class RustError final : public RustException {";
+"int64_t read(int32_t key) const;";
+"void write(int32_t key, int64_t val);";
+"std::variant<int64_t, RustString> try_read(int32_t key) const noexcept;";
+"uintptr_t size() const noexcept;";
+"return rust_result_unwrap(std::move(conv_ret));";
@@ -0,0 +1,9 @@
+foreign_class!(class Storage {
+    self_type Storage;
+    constructor Storage::new() -> Storage;
+    fn Storage::read(&self, key: i32) -> Result<i64, String>;
+    fn Storage::write(&mut self, key: i32, val: i64) -> Result<(), String>;
+    #[ResultAsVariant]
+    fn Storage::try_read(&self, key: i32) -> Result<i64, String>;
+    fn Storage::size(&self) -> usize;
+});
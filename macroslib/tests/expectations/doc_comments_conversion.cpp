@@ -0,0 +1,19 @@
+r#"/// Connection to server, see Request
+template<bool OWN_DATA>
+class SessionWrapper {"#;
+
+r#"    /// Send request to server
+    ///
+    /// \par Example
+    ///
+    /// \code
+    /// session.send(&req, 10);
+    /// \endcode
+    ///
+    /// \param req request, created by Request::Request
+    /// \param timeout timeout in `ms`
+    /// \return Status code
+    int32_t send(const Request & req, uint32_t timeout) const noexcept;"#;
+
+r#"    /// Request with [url](https://example.com) and Session
+    RequestWrapper() noexcept"#;
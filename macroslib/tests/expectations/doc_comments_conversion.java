@@ -0,0 +1,29 @@
+r#"/**
+ * Connection to server, see {@link Request}
+ */
+public final class Session {"#;
+
+r#"    /**
+     * Send request to server
+     *
+     * <p><b>Example</b>
+     *
+     * <pre>
+     * session.send(&amp;req, 10);
+     * </pre>
+     *
+     * @param req request, created by {@link Request#Request}
+     * @param timeout timeout in {@code ms}
+     * @return Status code
+     */
+    public final int send(@NonNull Request req, long timeout) {"#;
+
+r#"    /**
+     * Request with <a href="https://example.com">url</a> and {@link Session}
+     */
+    public Request() {"#;
+
+r#"    /**
+     * Find requests by pattern like {@code log/*&#47;err}, {@code *} matches any text: a/*&#47;b
+     */
+    public final int find(@NonNull String pattern) {"#;
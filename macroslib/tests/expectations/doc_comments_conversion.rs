@@ -0,0 +1,32 @@
+foreign_class!(
+/// Connection to server, see [`Request`]
+class Session {
+    self_type Session;
+    constructor Session::new() -> Session;
+    /// Send request to server
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - request, created by [`Request::new`]
+    /// * `timeout` - timeout in `ms`
+    ///
+    /// # Returns
+    ///
+    /// Status code
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let session = Session::new();
+    /// session.send(&req, 10);
+    /// ```
+    fn Session::send(&self, req: &Request, timeout: u32) -> i32;
+    /// Find requests by pattern like `log/*/err`, `*` matches any text: a/*/b
+    fn Session::find(&self, pattern: &str) -> i32;
+});
+
+foreign_class!(class Request {
+    self_type Request;
+    /// Request with [url](https://example.com) and [`Session`]
+    constructor Request::new() -> Request;
+});
@@ -1,5 +1,5 @@
 r#"enum ControlItem {
-//This is a comment.
+/// This is a comment.
 GNSS = 0
 
 };"#;
\ No newline at end of file
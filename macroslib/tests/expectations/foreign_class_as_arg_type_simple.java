@@ -2,16 +2,20 @@
 "public final int f(int a0, int a1)";
 "public Boo(int a0, long a1)";
 r#"public Boo(@NonNull Foo f) {
-        long a0 = f.mNativeObj;
-        f.mNativeObj = 0;
+        if (f == null)
+            throw new NullPointerException("f must not be null");
+        long a0 = f.releaseNativeObj();
 
         mNativeObj = init(a0);
         JNIReachabilityFence.reachabilityFence1(f);
     }
     private static native long init(long f);"#;
 r#"public final long f(@NonNull Foo foo) {
-        long a0 = foo.mNativeObj;
-        foo.mNativeObj = 0;
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Boo already deleted");
+        if (foo == null)
+            throw new NullPointerException("foo must not be null");
+        long a0 = foo.releaseNativeObj();
 
         long ret = do_f(mNativeObj, a0);
 
@@ -20,8 +24,9 @@ r#"public final long f(@NonNull Foo foo) {
         return ret;
     }"#;
 r#"public static int f2(double a0, @NonNull Foo foo) {
-        long a1 = foo.mNativeObj;
-        foo.mNativeObj = 0;
+        if (foo == null)
+            throw new NullPointerException("foo must not be null");
+        long a1 = foo.releaseNativeObj();
 
         int ret = do_f2(a0, a1);
 
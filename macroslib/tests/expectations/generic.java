@@ -3,6 +3,8 @@ r#"public Foo(int a0) {
     }
     private static native long init(int a0);"#;
 r#"public final int f(int a0, int a1) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Foo already deleted");
         int ret = do_f(mNativeObj, a0, a1);
 
         return ret;
@@ -13,12 +15,16 @@ r#"public Boo(int a0, long a1) throws Exception {
     }
     private static native long init(int a0, long a1) throws Exception;"#;
 r#"public final @NonNull Foo [] get_foo_arr() {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Boo already deleted");
         Foo [] ret = do_get_foo_arr(mNativeObj);
 
         return ret;
     }
     private static native @NonNull Foo [] do_get_foo_arr(long self);"#;
 r#"public final @NonNull Foo get_one_foo() throws Exception {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Boo already deleted");
         long ret = do_get_one_foo(mNativeObj);
         Foo convRet = new Foo(InternalPointerMarker.RAW_PTR, ret);
 
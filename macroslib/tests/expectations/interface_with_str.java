@@ -1,4 +1,8 @@
 r#"public final void f1(@NonNull SomeObserver cb) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("ClassWithCallbacks already deleted");
+        if (cb == null)
+            throw new NullPointerException("cb must not be null");
         do_f1(mNativeObj, cb);
     }
     private static native void do_f1(long self, SomeObserver cb);"#;
@@ -0,0 +1,3 @@
+r##"public interface Storage {"##;
+r##"long load(@NonNull String key) throws Exception;"##;
+r##"void remove(int key) throws Exception;"##;
@@ -0,0 +1,5 @@
+foreign_callback!(callback Storage {
+    self_type Storage;
+    load = Storage::load(&self, key: &str) -> Result<i64, JavaCallbackException>;
+    remove = Storage::remove(&self, key: i32) -> Result<(), String>;
+});
@@ -0,0 +1 @@
+"public interface Observer {";
@@ -0,0 +1,5 @@
+foreign_callback!(callback Observer {
+    self_type Observer;
+    onEvent = Observer::on_event(&self, x: i32);
+    getValue = Observer::value(&self) -> i32;
+});
@@ -0,0 +1,4 @@
+r#"public final int f(int a0) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Foo already deleted");
+        int ret = do_f(mNativeObj, a0);"#;
@@ -0,0 +1,6 @@
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::f(&self, _: i32) -> i32;
+    fn Foo::consume(_: Foo);
+});
@@ -0,0 +1,5 @@
+"public static native java.nio.ByteBuffer encode(java.nio.ByteBuffer data);";
+"public static native void fill(java.nio.ByteBuffer out, short val);";
+"/*package*/ final class RustByteBuffer {";
+"/*package*/ static void own(java.nio.ByteBuffer buffer, long ptr) {";
+"RustCleaner.register(buffer, new Deleter(ptr));";
@@ -0,0 +1,4 @@
+foreign_class!(class Codec {
+    fn encode(data: &[u8]) -> Vec<u8>;
+    fn fill(out: &mut [u8], val: u8);
+});
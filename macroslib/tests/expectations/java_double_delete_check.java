@@ -0,0 +1,9 @@
+"public final class Foo {";
+r#"long a00 = a0.releaseNativeObj();
+
+        do_consume(a00);"#;
+r#"/*package*/ synchronized long releaseNativeObj() {
+        long ptr = mNativeObj;
+        mNativeObj = 0;
+        return ptr;
+    }"#;
@@ -0,0 +1,13 @@
+r#"public final class StorageException extends Exception {
+    /*package*/ StorageException(String message, Throwable cause, int errorCode) {
+        super(message, cause);
+        this.errorCode = errorCode;
+    }
+    public static final int NOT_FOUND = 1;
+    public static final int PERMISSION_DENIED = 2;
+
+    public final int getErrorCode() {
+        return errorCode;
+    }
+    private final int errorCode;
+}"#;
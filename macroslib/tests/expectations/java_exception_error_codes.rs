@@ -0,0 +1,4 @@
+foreign_class!(class Storage {
+    self_type Storage;
+    constructor Storage::open(path: &str) -> Result<Storage, StorageError>;
+});
@@ -0,0 +1,9 @@
+r#"public class StorageException extends Exception {
+    /*package*/ StorageException(String message, Throwable cause) {
+        super(message, cause);
+    }
+}"#;
+"public final class StorageNotFoundException extends StorageException {";
+"public Storage(String path) throws StorageException {";
+"public final long read(int key) throws StorageException {";
+"public final void check() throws Exception {";
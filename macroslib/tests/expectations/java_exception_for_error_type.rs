@@ -0,0 +1,6 @@
+foreign_class!(class Storage {
+    self_type Storage;
+    constructor Storage::open(path: &str) -> Result<Storage, StorageError>;
+    fn Storage::read(&self, key: i32) -> Result<i64, StorageError>;
+    fn Storage::check(&self) -> Result<(), String>;
+});
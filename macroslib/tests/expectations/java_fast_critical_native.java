@@ -0,0 +1,3 @@
+"    @dalvik.annotation.optimization.FastNative\n    private static native String do_f(long self, String a0);";
+"    @dalvik.annotation.optimization.CriticalNative\n    private static native long do_g(long self, int a0);";
+"    @dalvik.annotation.optimization.CriticalNative\n    public static native double sum(int a0, double a1);";
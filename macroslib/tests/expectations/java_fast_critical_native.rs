@@ -0,0 +1,10 @@
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new() -> Foo;
+    #[FastNative]
+    fn Foo::f(&self, _: &str) -> String;
+    #[CriticalNative]
+    fn Foo::g(&self, _: i32) -> i64;
+    #[CriticalNative]
+    fn sum(_: i32, _: f64) -> f64;
+});
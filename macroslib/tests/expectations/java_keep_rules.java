@@ -0,0 +1,18 @@
+r#"-keep class org.example.Foo {
+    long mNativeObj;
+    void registerCleanup();
+    native <methods>;
+}"#;
+r#"-keep class org.example.MyEnum {
+    static org.example.MyEnum ITEM1;
+    static org.example.MyEnum ITEM2;
+}"#;
+r#"-keep interface org.example.Observer {
+    boolean on_event(int, java.lang.String);
+}
+-keepclassmembers class * implements org.example.Observer {
+    boolean on_event(int, java.lang.String);
+}"#;
+r#"-keep class org.example.MyException {
+    <init>(java.lang.String, java.lang.Throwable);
+}"#;
@@ -0,0 +1,15 @@
+foreign_enum!(enum MyEnum {
+    ITEM1 = MyEnum::Item1,
+    ITEM2 = MyEnum::Item2,
+});
+foreign_callback!(callback Observer {
+    self_type Observer;
+    on_event = Observer::on_event(&self, x: i32, s: &str) -> bool;
+});
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::set_observer(&mut self, o: Box<dyn Observer>);
+    fn Foo::f(&self) -> Result<i32, MyError>;
+    fn Foo::g(&self) -> MyEnum;
+});
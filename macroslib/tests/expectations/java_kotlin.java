@@ -0,0 +1,24 @@
+"class Foo : java.io.Closeable {";
+r#"    constructor(a0: Int) {
+        mNativeObj = init(a0)
+    }"#;
+"    fun name(): String? {";
+"    fun set_name(name: String?) {";
+"    fun value(): Double? {";
+"    fun other(o: Foo?): Foo? {";
+"        val a0: Long = o?.mNativeObj ?: 0L";
+"        val convRet: Foo? = if (ret != 0L) Foo(InternalPointerMarker.RAW_PTR, ret) else null";
+"        val a0: Int = k?.value ?: -1";
+"        val a0: Long = f.releaseNativeObj()";
+r#"    companion object {
+        @JvmStatic
+        private external fun init(a0: Int): Long"#;
+r#"        @JvmStatic
+        external fun sum(a: Int, b: Int): Int"#;
+r#"    override fun close() {
+        delete()
+    }"#;
+"    fun kotlinOnly(): Int = 1";
+"enum class MyEnum(val value: Int) {";
+"interface Observer {";
+"    fun on_event(x: Int, s: String): Boolean";
@@ -0,0 +1,22 @@
+foreign_enum!(enum MyEnum {
+    ITEM1 = MyEnum::Item1,
+    ITEM2 = MyEnum::Item2,
+});
+foreign_callback!(callback Observer {
+    self_type Observer;
+    on_event = Observer::on_event(&self, x: i32, s: String) -> bool;
+});
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::name(&self) -> Option<String>;
+    fn Foo::set_name(&mut self, name: Option<&str>);
+    fn Foo::value(&self) -> Option<f64>;
+    fn Foo::other(&self, o: Option<&Foo>) -> Option<Foo>;
+    fn Foo::kind(&self, k: Option<MyEnum>) -> MyEnum;
+    fn Foo::set_observer(&mut self, o: Box<dyn Observer>);
+    fn sum(a: i32, b: i32) -> i32;
+    fn consume(f: Foo);
+    foreign_code(option = "Kotlin") "    fun kotlinOnly(): Int = 1\n";
+    foreign_code(option = "Java") "    public int javaOnly() { return 1; }\n";
+});
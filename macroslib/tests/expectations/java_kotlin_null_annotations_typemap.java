@@ -0,0 +1 @@
+"        external fun id(): String?";
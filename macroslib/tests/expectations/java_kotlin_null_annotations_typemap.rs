@@ -0,0 +1,10 @@
+foreign_typemap!(
+    ($p:r_type) Uuid => internal_aliases::JStringPath {
+        $out = from_std_string_jstring($p.to_string(), env);
+    };
+    ($p:f_type, option = "NoNullAnnotations", unique_prefix = "/*uuid*/") => "/*uuid*/String";
+    ($p:f_type, option = "NullAnnotations", unique_prefix = "/*uuid*/") => "/*uuid*/@Nullable String";
+);
+foreign_class!(class Foo {
+    fn id() -> Uuid;
+});
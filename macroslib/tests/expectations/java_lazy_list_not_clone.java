@@ -0,0 +1,2 @@
+"public static native java.util.List<Foo> make_many(int a0);";
+"public static native java.util.List<Boo> make_many(int a0);";
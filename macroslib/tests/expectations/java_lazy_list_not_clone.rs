@@ -0,0 +1,13 @@
+foreign_class!(
+#[derive(Clone)]
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn make_many(_: i32) -> Vec<Foo>;
+});
+foreign_class!(
+class Boo {
+    self_type Boo;
+    constructor Boo::new(_: i32) -> Boo;
+    fn make_many(_: i32) -> Vec<Boo>;
+});
@@ -0,0 +1 @@
+"public static native int sum(int a0);";
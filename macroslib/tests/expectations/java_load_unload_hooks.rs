@@ -0,0 +1,3 @@
+foreign_class!(class Foo {
+    fn sum(_: i32) -> i32;
+});
@@ -0,0 +1,39 @@
+r#"public Foo(@NonNull String name) {
+        if (name == null)
+            throw new NullPointerException("name must not be null");
+        mNativeObj = init(name);
+    }
+    private static native long init(@NonNull String name);"#;
+r#"public final int f(@NonNull Foo other, @Nullable String name) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Foo already deleted");
+        if (other == null)
+            throw new NullPointerException("other must not be null");
+        long a0 = other.mNativeObj;
+        int ret = do_f(mNativeObj, a0, name);
+
+        JNIReachabilityFence.reachabilityFence1(other);
+
+        return ret;
+    }
+    private static native int do_f(long self, long other, @Nullable String name);"#;
+r#"public final void set_names(@NonNull int [] data, @NonNull String sep) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Foo already deleted");
+        if (data == null)
+            throw new NullPointerException("data must not be null");
+        if (sep == null)
+            throw new NullPointerException("sep must not be null");
+        do_set_names(mNativeObj, data, sep);
+    }
+    private static native void do_set_names(long self, int [] data, @NonNull String sep);"#;
+r#"public final int opt(@Nullable Foo other) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Foo already deleted");
+        long a0 = 0;//TODO: use ptr::null() for corresponding constant
+        if (other != null) {
+            a0 = other.mNativeObj;
+        }
+
+        int ret = do_opt(mNativeObj, a0);"#;
+r#"public static native long parse(@NonNull String text);"#;
@@ -0,0 +1,8 @@
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(name: &str) -> Foo;
+    fn Foo::f(&self, other: &Foo, name: Option<&str>) -> i32;
+    fn Foo::set_names(&mut self, data: &[i32], sep: String);
+    fn Foo::opt(&self, other: Option<&Foo>) -> i32;
+    fn Foo::parse(text: &str) -> i64;
+});
@@ -0,0 +1,12 @@
+"public final class Foo implements AutoCloseable {";
+r#"        mNativeObj = init(a0);
+        registerCleanup();
+    }"#;
+r#"    @Override
+    public void close() {
+        delete();
+    }"#;
+"        long a0 = f.releaseNativeObj();";
+"            a0 = f.releaseNativeObj();";
+"/*package*/ final class RustCleaner {";
+"java.lang.ref.Cleaner.create()";
@@ -0,0 +1,8 @@
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::get(&self) -> i32;
+    fn make_many(_: i32) -> Vec<Foo>;
+    fn consume(f: Foo);
+    fn consume_opt(f: Option<Foo>);
+});
@@ -0,0 +1 @@
+"public static native @NonNull String filter(@NonNull float [] input, @NonNull float [] out, @NonNull String name);";
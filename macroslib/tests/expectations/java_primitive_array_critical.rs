@@ -0,0 +1,4 @@
+foreign_class!(class Dsp {
+    fn filter(#[PrimitiveArrayCritical] input: &[f32], #[PrimitiveArrayCritical] out: &mut [f32], name: &str) -> String;
+    fn scale(data: &mut [i32], k: i32);
+});
@@ -0,0 +1 @@
+"-keep class org.example.Foo {\n    long mNativeObj;\n    native <methods>;\n}";
@@ -0,0 +1,7 @@
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::f(&self, _: i32, _: &str) -> String;
+    fn sum(_: i32) -> i32;
+    fn sum2(_: i32, _: f64) -> f64; alias sum;
+});
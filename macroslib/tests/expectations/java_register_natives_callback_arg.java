@@ -0,0 +1 @@
+"private static native void do_subscribe(long self, Observer a0);";
@@ -0,0 +1,9 @@
+foreign_callback!(callback Observer {
+    self_type Observer;
+    onEvent = Observer::on_event(&self, x: i32);
+});
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new() -> Foo;
+    fn Foo::subscribe(&mut self, _: Box<dyn Observer>);
+});
@@ -0,0 +1 @@
+"        private external fun do_subscribe(self: Long, a0: Observer)";
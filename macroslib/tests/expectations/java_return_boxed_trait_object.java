@@ -0,0 +1,33 @@
+r#"/*package*/ final class ObserverRust implements Observer {
+    /*package*/ ObserverRust(long ptr) {
+        mNativeObj = ptr;
+    }"#;
+
+r#"    @Override
+    public int check(@NonNull String s) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("ObserverRust already deleted");
+        return do_check(mNativeObj, s);
+    }
+    private static native int do_check(long self, @NonNull String s);"#;
+
+r#"    /*package*/ synchronized long releaseNativeObj() {
+        long ptr = mNativeObj;
+        mNativeObj = 0;
+        return ptr;
+    }
+    private static native void do_delete(long me);
+
+    /*package*/ long mNativeObj;"#;
+
+"public static native Observer make_observer();";
+
+"public static native Observer shared_observer();";
+
+"public static native boolean notify(@NonNull Observer a0);";
+
+"public static native Counter make_counter();";
+
+"/*package*/ final class CounterRust implements Counter {";
+
+"private static native int do_inc(long self, int d);";
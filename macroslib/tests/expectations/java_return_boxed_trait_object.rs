@@ -0,0 +1,18 @@
+foreign_callback!(callback Observer {
+    self_type Observer;
+    onEvent = Observer::on_event(&self, x: i32) -> bool;
+    check = Observer::check(&self, s: &str) -> Result<i32, String>;
+});
+
+foreign_callback!(callback Counter {
+    self_type Counter;
+    inc = Counter::inc(&mut self, d: i32) -> i32;
+});
+
+foreign_class!(class Factory {
+    fn make_observer() -> Box<dyn Observer>;
+    fn shared_observer() -> Arc<dyn Observer>;
+    fn notify(_: Box<dyn Observer>) -> bool;
+    fn notify_shared(_: Arc<dyn Observer>) -> bool;
+    fn make_counter() -> Box<dyn Counter>;
+});
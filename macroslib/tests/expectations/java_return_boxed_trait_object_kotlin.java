@@ -0,0 +1,22 @@
+r#"internal class ObserverRust : Observer, java.io.Closeable {
+    internal constructor(ptr: Long) {
+        mNativeObj = ptr
+    }"#;
+
+r#"    override fun check(s: String): Int {
+        check(mNativeObj != 0L) { "ObserverRust already deleted" }
+        return do_check(mNativeObj, s)
+    }"#;
+
+r#"    fun releaseRustImpl(): Long = releaseNativeObj()
+    @JvmField
+    internal var mNativeObj: Long = 0L"#;
+
+r#"        @JvmStatic
+        private external fun do_check(self: Long, s: String): Int"#;
+
+"external fun make_observer(): Observer";
+
+"external fun notify(a0: Observer): Boolean";
+
+"internal class CounterRust : Counter, java.io.Closeable {";
@@ -0,0 +1,3 @@
+r##"public static native byte [] encode(@NonNull byte [] data)"##;
+r##"public static native void fill(@NonNull byte [] out, short val)"##;
+r##"public static native long take(@NonNull byte [] data)"##;
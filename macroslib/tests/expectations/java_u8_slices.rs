@@ -0,0 +1,5 @@
+foreign_class!(class Codec {
+    fn encode(data: &[u8]) -> Vec<u8>;
+    fn fill(out: &mut [u8], val: u8);
+    fn take(data: Vec<u8>) -> usize;
+});
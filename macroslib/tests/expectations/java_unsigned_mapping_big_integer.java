@@ -0,0 +1,5 @@
+"private static native java.math.BigInteger do_f1(short a0, int a1, long a2);";
+r#"        if (a1 != null && (a1.signum() < 0 || a1.bitLength() > 64))
+            throw new IllegalArgumentException("a1 is out of range of u64");"#;
+"private static native long [] do_f4(int [] a0, java.math.BigInteger [] a1);";
+"public static native java.math.BigInteger [] f5();";
@@ -0,0 +1,7 @@
+foreign_class!(class Foo {
+    fn f1(_: u8, _: u16, _: u32) -> u64;
+    fn f2(_: u64, _: usize) -> u32;
+    fn f3(_: Option<u32>, _: Option<u64>) -> Option<u64>;
+    fn f4(_: &[u16], _: Vec<u64>) -> Vec<u32>;
+    fn f5() -> Vec<usize>;
+});
@@ -0,0 +1,11 @@
+r#"        if (a2 < 0 || a2 > 0xFFFFFFFFL)
+            throw new IllegalArgumentException("a2 is out of range of u32");
+        long ret = do_f1(a0, a1, a2);
+        if (ret < 0)
+            throw new ArithmeticException("result of type u64 is out of range of long");"#;
+r#"        if (a1 != null && (a1 < 0))
+            throw new IllegalArgumentException("a1 is out of range of u64");"#;
+r#"        for (int elem : a0) {
+            if (elem < 0 || elem > 0xFFFF)
+                throw new IllegalArgumentException("a0 is out of range of u16");
+        }"#;
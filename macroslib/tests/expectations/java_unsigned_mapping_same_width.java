@@ -0,0 +1,3 @@
+"public static native long f1(byte a0, short a1, int a2);";
+"public static native java.util.OptionalLong f3(Integer a0, Long a1);";
+"public static native int [] f4(short [] a0, long [] a1);";
@@ -0,0 +1,3 @@
+"public static native long f1(short a0, int a1, long a2);";
+"public static native java.util.OptionalLong f3(Long a0, Long a1);";
+"public static native long [] f4(int [] a0, long [] a1);";
@@ -0,0 +1,12 @@
+foreign_class!(
+#[derive(Clone)]
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn make_many(_: i32) -> Vec<Foo>;
+    fn take_many(_: Vec<Foo>);
+    fn take_slice(_: &[Foo]);
+    fn names() -> Vec<String>;
+    fn sum(_: &[i32]) -> i64;
+    fn squares(_: Vec<f64>) -> Vec<f64>;
+});
@@ -0,0 +1,6 @@
+"public static native java.util.List<Foo> make_many(int a0);";
+"public static native void take_many(java.util.List<? extends Foo> a0);";
+"public static native void take_slice(java.util.List<? extends Foo> a0);";
+"public static native java.util.List<String> names();";
+"public static native long sum(java.util.List<? extends Integer> a0);";
+"public static native java.util.List<Double> squares(java.util.List<? extends Double> a0);";
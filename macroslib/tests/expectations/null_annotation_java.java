@@ -6,6 +6,8 @@ public final class TrackInfo {
     private TrackInfo() {}
 
     public final @NonNull java.util.Date start_time() {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("TrackInfo already deleted");
         long ret = do_start_time(mNativeObj);
         java.util.Date convRet = new java.util.Date(ret);
 
@@ -14,6 +16,8 @@ public final class TrackInfo {
     private static native long do_start_time(long self);
 
     public final @NonNull java.util.OptionalLong end_time() {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("TrackInfo already deleted");
         java.util.OptionalLong ret = do_end_time(mNativeObj);
 
         return ret;
@@ -31,6 +35,8 @@ public final class Boo {
     private static native long init();
 
     public final @NonNull String latDirection() {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Boo already deleted");
         String ret = do_latDirection(mNativeObj);
 
         return ret;
@@ -48,6 +54,8 @@ public final class Foo {
     private static native long init(double a0, double a1, double a2, double a3, double a4);
 
     public final @NonNull Boo dropPoint() {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Foo already deleted");
         long ret = do_dropPoint(mNativeObj);
         Boo convRet = new Boo(InternalPointerMarker.RAW_PTR, ret);
 
@@ -63,6 +71,8 @@ public final class BooList {
     private BooList() {}
 
     public final @NonNull Boo [] getBooList() {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("BooList already deleted");
         Boo [] ret = do_getBooList(mNativeObj);
 
         return ret;
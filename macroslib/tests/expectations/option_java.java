@@ -1,6 +1,8 @@
 "public final @NonNull java.util.OptionalDouble f1(@Nullable Double a0)";
 "public final @NonNull java.util.OptionalLong f2(@Nullable Long a0)";
 r#"public final @NonNull java.util.Optional<Boo> f3() {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Foo already deleted");
         long ret = do_f3(mNativeObj);
         java.util.Optional<Boo> convRet;
         if (ret != 0) {
@@ -13,10 +15,11 @@ r#"public final @NonNull java.util.Optional<Boo> f3() {
     }
     private static native long do_f3(long self);"#;
 r#"public final void f4(@Nullable Boo boo) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Foo already deleted");
         long a0 = 0;//TODO: use ptr::null() for corresponding constant
         if (boo != null) {
-            a0 = boo.mNativeObj;
-            boo.mNativeObj = 0;
+            a0 = boo.releaseNativeObj();
         }
 
         do_f4(mNativeObj, a0);
@@ -27,6 +30,8 @@ r#"public final void f4(@Nullable Boo boo) {
 "#;
 
 r#"public final @NonNull java.util.Optional<String> f5() {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Foo already deleted");
         String ret = do_f5(mNativeObj);
         java.util.Optional<String> convRet = java.util.Optional.ofNullable(ret);
 
@@ -35,6 +40,8 @@ r#"public final @NonNull java.util.Optional<String> f5() {
     private static native @Nullable String do_f5(long self);"#;
 
 r#"public final void f6(@Nullable Boo boo) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Foo already deleted");
         long a0 = 0;//TODO: use ptr::null() for corresponding constant
         if (boo != null) {
             a0 = boo.mNativeObj;
@@ -47,11 +54,15 @@ r#"public final void f6(@Nullable Boo boo) {
     private static native void do_f6(long self, long boo);"#;
 
 r#"public final void f7(@Nullable String a0) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Foo already deleted");
         do_f7(mNativeObj, a0);
     }
     private static native void do_f7(long self, @Nullable String a0);"#;
 
 r#"public final @NonNull java.util.OptionalInt f8(@Nullable Integer a0) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Foo already deleted");
         java.util.OptionalInt ret = do_f8(mNativeObj, a0);
 
         return ret;
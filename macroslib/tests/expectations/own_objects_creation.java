@@ -10,8 +10,11 @@ r#"public static @NonNull Boo factory_method() throws Exception {
     }
     private static native long do_factory_method() throws Exception;"#;
 r#"public final int boo_as_arg(@NonNull Boo a0) {
-        long a00 = a0.mNativeObj;
-        a0.mNativeObj = 0;
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Boo already deleted");
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
+        long a00 = a0.releaseNativeObj();
 
         int ret = do_boo_as_arg(mNativeObj, a00);
 
@@ -21,6 +24,8 @@ r#"public final int boo_as_arg(@NonNull Boo a0) {
     }
     private static native int do_boo_as_arg(long self, long a0);"#;
 r#"public final @NonNull Foo get_one_foo() {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Boo already deleted");
         long ret = do_get_one_foo(mNativeObj);
         Foo convRet = new Foo(InternalPointerMarker.RAW_PTR, ret);
 
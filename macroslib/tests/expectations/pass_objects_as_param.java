@@ -1,4 +1,8 @@
 r#"public final void f1(@NonNull Foo a0) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("TestPassObjectsAsParams already deleted");
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
         long a00 = a0.mNativeObj;
         do_f1(mNativeObj, a00);
 
@@ -7,8 +11,11 @@ r#"public final void f1(@NonNull Foo a0) {
     private static native void do_f1(long self, long a0);"#;
 
 r#"public final void f2(@NonNull Foo a0) {
-        long a00 = a0.mNativeObj;
-        a0.mNativeObj = 0;
+        if (mNativeObj == 0)
+            throw new IllegalStateException("TestPassObjectsAsParams already deleted");
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
+        long a00 = a0.releaseNativeObj();
 
         do_f2(mNativeObj, a00);
 
@@ -17,6 +24,10 @@ r#"public final void f2(@NonNull Foo a0) {
     private static native void do_f2(long self, long a0);"#;
 
 r#"public final void f3(@NonNull Foo a0) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("TestPassObjectsAsParams already deleted");
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
         long a00 = a0.mNativeObj;
         do_f3(mNativeObj, a00);
 
@@ -25,6 +36,10 @@ r#"public final void f3(@NonNull Foo a0) {
     private static native void do_f3(long self, long a0);"#;
 
 r#"public final void f4(@NonNull Foo a0) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("TestPassObjectsAsParams already deleted");
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
         long a00 = a0.mNativeObj;
         do_f4(mNativeObj, a00);
 
@@ -33,6 +48,10 @@ r#"public final void f4(@NonNull Foo a0) {
     private static native void do_f4(long self, long a0);"#;
 
 r#"public final void f5(@NonNull Foo a0) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("TestPassObjectsAsParams already deleted");
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
         long a00 = a0.mNativeObj;
         do_f5(mNativeObj, a00);
 
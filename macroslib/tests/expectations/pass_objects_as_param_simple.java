@@ -1,4 +1,8 @@
 r#"public final void f1(@NonNull Foo a0) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("TestPassObjectsAsParams already deleted");
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
         long a00 = a0.mNativeObj;
         do_f1(mNativeObj, a00);
 
@@ -6,8 +10,11 @@ r#"public final void f1(@NonNull Foo a0) {
     }
     private static native void do_f1(long self, long a0);"#;
 r#"public final void f2(@NonNull Foo a0) {
-        long a00 = a0.mNativeObj;
-        a0.mNativeObj = 0;
+        if (mNativeObj == 0)
+            throw new IllegalStateException("TestPassObjectsAsParams already deleted");
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
+        long a00 = a0.releaseNativeObj();
 
         do_f2(mNativeObj, a00);
 
@@ -15,6 +22,10 @@ r#"public final void f2(@NonNull Foo a0) {
     }
     private static native void do_f2(long self, long a0);"#;
 r#"public final void f3(@NonNull Foo a0) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("TestPassObjectsAsParams already deleted");
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
         long a00 = a0.mNativeObj;
         do_f3(mNativeObj, a00);
 
@@ -22,6 +33,10 @@ r#"public final void f3(@NonNull Foo a0) {
     }
     private static native void do_f3(long self, long a0);"#;
 r#"public final void f3_a(@NonNull Boo a0) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("TestPassObjectsAsParams already deleted");
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
         long a00 = a0.mNativeObj;
         do_f3_a(mNativeObj, a00);
 
@@ -30,6 +45,8 @@ r#"public final void f3_a(@NonNull Boo a0) {
     private static native void do_f3_a(long self, long a0);
 "#;
 r#"public static void f4(@NonNull Foo a0) {
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
         long a00 = a0.mNativeObj;
         do_f4(a00);
 
@@ -37,8 +54,9 @@ r#"public static void f4(@NonNull Foo a0) {
     }
     private static native void do_f4(long a0);"#;
 r#"public static void f5(@NonNull Foo a0) {
-        long a00 = a0.mNativeObj;
-        a0.mNativeObj = 0;
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
+        long a00 = a0.releaseNativeObj();
 
         do_f5(a00);
 
@@ -1,6 +1,8 @@
 r#"public final class LongOperation {
 
     public LongOperation(@NonNull DropCounter drop_counter) {
+        if (drop_counter == null)
+            throw new NullPointerException("drop_counter must not be null");
         long a0 = drop_counter.mNativeObj;
         mNativeObj = init(a0);
         JNIReachabilityFence.reachabilityFence1(drop_counter);
@@ -8,6 +10,10 @@ r#"public final class LongOperation {
     private static native long init(long drop_counter);
 
     public final void mf(@NonNull DropCounter drop_counter) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("LongOperation already deleted");
+        if (drop_counter == null)
+            throw new NullPointerException("drop_counter must not be null");
         long a0 = drop_counter.mNativeObj;
         do_mf(mNativeObj, a0);
 
@@ -16,6 +22,8 @@ r#"public final class LongOperation {
     private static native void do_mf(long self, long drop_counter);
 
     public static void f(@NonNull DropCounter drop_counter) {
+        if (drop_counter == null)
+            throw new NullPointerException("drop_counter must not be null");
         long a0 = drop_counter.mNativeObj;
         do_f(a0);
 
@@ -24,6 +32,10 @@ r#"public final class LongOperation {
     private static native void do_f(long drop_counter);
 
     public final int mf2(@NonNull DropCounter drop_counter) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("LongOperation already deleted");
+        if (drop_counter == null)
+            throw new NullPointerException("drop_counter must not be null");
         long a0 = drop_counter.mNativeObj;
         int ret = do_mf2(mNativeObj, a0);
 
@@ -34,6 +46,8 @@ r#"public final class LongOperation {
     private static native int do_mf2(long self, long drop_counter);
 
     public static int f2(@NonNull DropCounter drop_counter) {
+        if (drop_counter == null)
+            throw new NullPointerException("drop_counter must not be null");
         long a0 = drop_counter.mNativeObj;
         int ret = do_f2(a0);
 
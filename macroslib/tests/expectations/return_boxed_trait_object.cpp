@@ -0,0 +1,95 @@
+r#"    static C_Observer to_c_interface(std::unique_ptr<Observer> p)
+    {
+        assert(p != nullptr);
+        C_Observer ret;
+        if (p->into_c_interface(ret)) {
+            return ret;
+        }
+        ret.opaque = p.release();"#;
+
+r#"    static uint32_t c_count(void *opaque)
+    {
+        assert(opaque != nullptr);
+        auto pi = static_cast<Observer *>(opaque);
+
+        auto ret = pi->count();
+        return ret;
+    }"#;
+
+r#"/// Implementation of Observer by Rust object
+class ObserverRust final : public Observer {
+public:
+    explicit ObserverRust(C_Observer c) noexcept
+        : c_(c)
+    {
+    }
+    ObserverRust(const ObserverRust &) = delete;
+    ObserverRust &operator=(const ObserverRust &) = delete;
+    ~ObserverRust() noexcept override
+    {
+        if (c_.opaque != nullptr) {
+            c_.C_Observer_deref(c_.opaque);
+        }
+    }
+    bool onEvent(int32_t x) noexcept override
+    {
+        auto ret = c_.onEvent(x, c_.opaque);
+        return (ret != 0);
+    }
+    uint32_t count() noexcept override
+    {
+        return c_.count(c_.opaque);
+    }
+
+private:
+    bool into_c_interface(C_Observer &out) noexcept override
+    {
+        out = c_;
+        c_.opaque = nullptr;
+        return true;
+    }
+    bool share_c_interface(C_Observer &out) const noexcept override
+    {
+        return C_Observer_share(&c_, &out) != 0;
+    }
+    C_Observer c_;
+};"#;
+
+r#"        struct C_Observer ret = Factory_make_observer();
+        return Observer::from_c_interface(ret);"#;
+
+r#"        struct C_Observer ret = Factory_shared_observer();
+        return Observer::shared_from_c_interface(ret);"#;
+
+"struct C_Observer Factory_make_observer(void);";
+
+r#"inline C_Observer Observer::to_c_interface(std::shared_ptr<Observer> p)
+{
+    assert(p != nullptr);
+    C_Observer ret;
+    if (p->share_c_interface(ret)) {
+        return ret;
+    }
+    return to_c_interface(std::unique_ptr<Observer>(new ObserverShared(std::move(p))));
+}"#;
+
+r#"    bool onEvent(int32_t x) noexcept override
+    {
+        return p_->onEvent(std::move(x));
+    }"#;
+
+"char C_Observer_share(const struct C_Observer *self, struct C_Observer *out);";
+
+"char C_Observer_foreign(const struct C_Observer *self, struct C_Observer *out);";
+
+r#"inline std::unique_ptr<Observer> Observer::from_c_interface(C_Observer c)
+{
+    if (c.C_Observer_deref == c_Observer_deref) {
+        return std::unique_ptr<Observer>(static_cast<Observer *>(c.opaque));
+    }
+    return std::unique_ptr<Observer>(new ObserverRust(c));
+}"#;
+
+r#"    std::shared_ptr<Observer> shared_origin() const noexcept override { return p_; }"#;
+
+r#"        C_Observer tmp = Observer::to_c_interface(std::move(a0));"#;
@@ -0,0 +1,12 @@
+foreign_callback!(callback Observer {
+    self_type Observer;
+    onEvent = Observer::on_event(&self, x: i32) -> bool;
+    count = Observer::count(&self) -> u32;
+});
+
+foreign_class!(class Factory {
+    fn make_observer() -> Box<dyn Observer>;
+    fn shared_observer() -> Arc<dyn Observer>;
+    fn notify(_: Box<dyn Observer>) -> bool;
+    fn notify_shared(_: Arc<dyn Observer>) -> bool;
+});
@@ -1,4 +1,6 @@
 r#"public final @NonNull Boo getBoo() {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Moo already deleted");
         long ret = do_getBoo(mNativeObj);
         Boo convRet = new Boo(InternalPointerMarker.RAW_PTR, ret);
 
@@ -16,6 +16,8 @@ r#"public static @NonNull LocationService create() throws Exception {
     private static native long do_create() throws Exception;"#;
 
 r#"public static @NonNull Foo from_string(@NonNull String a0) throws Exception {
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
         long ret = do_from_string(a0);
         Foo convRet = new Foo(InternalPointerMarker.RAW_PTR, ret);
 
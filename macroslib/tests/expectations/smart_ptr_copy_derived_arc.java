@@ -1,4 +1,8 @@
 r#"public final void subscribeOnUpdates(@NonNull Session session) {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("NavigationService already deleted");
+        if (session == null)
+            throw new NullPointerException("session must not be null");
         long a0 = session.mNativeObj;
 
         do_subscribeOnUpdates(mNativeObj, a0);
@@ -1,4 +1,6 @@
 r#"public static void static_foo(@NonNull Boo a0) {
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
         long a00 = a0.mNativeObj;
         do_static_foo(a00);
 
@@ -1,4 +1,6 @@
 r#"public static void f1(@NonNull Boo a0) {
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
         long a00 = a0.mNativeObj;
         do_f1(a00);
 
@@ -6,6 +8,8 @@ r#"public static void f1(@NonNull Boo a0) {
     }"#;
 
 r#"public static void f2(@NonNull Boo a0) {
+        if (a0 == null)
+            throw new NullPointerException("a0 must not be null");
         long a00 = a0.mNativeObj;
         do_f2(a00);
 
@@ -9,6 +9,8 @@ public final class Foo {
     private static native long init();
 
     public final @NonNull java.lang.String [] list() {
+        if (mNativeObj == 0)
+            throw new IllegalStateException("Foo already deleted");
         java.lang.String [] ret = do_list(mNativeObj);
 
         return ret;
@@ -30,6 +32,11 @@ public final class Foo {
              super.finalize();
         }
     }
+    /*package*/ synchronized long releaseNativeObj() {
+        long ptr = mNativeObj;
+        mNativeObj = 0;
+        return ptr;
+    }
     private static native void do_delete(long me);
     /*package*/ Foo(InternalPointerMarker marker, long ptr) {
         assert marker == InternalPointerMarker.RAW_PTR;
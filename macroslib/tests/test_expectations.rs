@@ -4,7 +4,11 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use flapigen::{rustfmt_cnt, CppConfig, Generator, JavaConfig, LanguageConfig, RustEdition};
+use flapigen::{
+    rustfmt_cnt, CppConfig, CppPackage, Generator, JavaCallbackExceptionPolicy, JavaConfig,
+    JavaException, JavaObjectCleanup, JavaUnsignedMapping, JavaVecMapping, JvmLanguage,
+    LanguageConfig, RustEdition,
+};
 use log::warn;
 use syn::Token;
 use tempfile::tempdir;
@@ -451,7 +455,7 @@ class MyObjWrapper : public QObject {
     ));
     assert!(foreign_code.contains("Q_INVOKABLE static void f()"));
     assert!(foreign_code.contains(
-        r#"//enum comment
+        r#"/// enum comment
 enum class MyEnum {
 A = 0,
 B = 1"#
@@ -459,6 +463,292 @@ B = 1"#
     tmp_dir.close().unwrap();
 }
 
+#[test]
+fn test_java_callback_exception_policy() {
+    let _ = env_logger::try_init();
+    let code_pair = parse_test_case("java_callback_exception_policy", ForeignLang::Java);
+    assert!(!code_pair.rust_code.contains("ExceptionDescribe"));
+}
+
+#[test]
+fn test_java_register_natives() {
+    let _ = env_logger::try_init();
+    let code_pair = parse_test_case("java_register_natives", ForeignLang::Java);
+    assert!(!code_pair
+        .rust_code
+        .contains("#[no_mangle]\npub extern \"C\" fn Java_"));
+}
+
+#[test]
+fn test_java_critical_native_not_primitive() {
+    let _ = env_logger::try_init();
+    let rust_src = r#"
+foreign_class!(class Foo {
+    #[CriticalNative]
+    fn f(_: &str) -> i32;
+});
+"#;
+    let result = panic::catch_unwind(|| {
+        parse_code(
+            "java_critical_native_not_primitive",
+            Source::Str(rust_src),
+            ForeignLang::Java,
+        )
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_java_primitive_array_critical_invalid() {
+    let _ = env_logger::try_init();
+    let rust_src = r#"
+foreign_class!(class Dsp {
+    fn f(#[PrimitiveArrayCritical] _: Vec<f32>);
+});
+"#;
+    let result = panic::catch_unwind(|| {
+        parse_code(
+            "java_primitive_array_critical_invalid",
+            Source::Str(rust_src),
+            ForeignLang::Java,
+        )
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_java_keep_rules_disabled() {
+    let _ = env_logger::try_init();
+    let code_pair = parse_test_case("java_direct_byte_buffer", ForeignLang::Java);
+    assert!(!code_pair.foreign_code.contains("-keep class"));
+}
+
+#[test]
+fn test_java_vec_mapping() {
+    let _ = env_logger::try_init();
+    let code_pair = parse_test_case("java_vec_mapping_list", ForeignLang::Java);
+    assert!(!code_pair.foreign_code.contains("class RustList<T>"));
+    assert!(!code_pair
+        .rust_code
+        .contains("Java_org_example_RustList_do_1get"));
+}
+
+#[test]
+fn test_java_object_cleanup() {
+    let _ = env_logger::try_init();
+    for test_name in &[
+        "java_object_cleanup_cleaner",
+        "java_object_cleanup_phantom_reference",
+    ] {
+        let code_pair = parse_test_case(test_name, ForeignLang::Java);
+        assert!(!code_pair.foreign_code.contains("finalize()"));
+    }
+}
+
+#[test]
+fn test_java_kotlin() {
+    let _ = env_logger::try_init();
+    let code_pair = parse_test_case("java_kotlin", ForeignLang::Java);
+    assert!(!code_pair.foreign_code.contains("public final class"));
+    assert!(!code_pair.foreign_code.contains("javaOnly"));
+}
+
+#[test]
+fn test_java_kotlin_typemap_with_java_code() {
+    let _ = env_logger::try_init();
+    let rust_src = r#"
+foreign_typemap!(
+    ($p:r_type) Uuid => jstring {
+        $out = from_std_string_jstring($p.to_string(), env);
+    };
+    ($p:f_type, option = "NoNullAnnotations", unique_prefix = "/*uuid*/")
+        => "/*uuid*/java.util.UUID" "$out = java.util.UUID.fromString($p);";
+    ($p:f_type, option = "NullAnnotations", unique_prefix = "/*uuid*/")
+        => "/*uuid*/@NonNull java.util.UUID" "$out = java.util.UUID.fromString($p);";
+);
+foreign_class!(class Foo {
+    fn id() -> Uuid;
+});
+"#;
+    let result = panic::catch_unwind(|| {
+        parse_code(
+            "java_kotlin_typemap_with_java_code",
+            Source::Str(rust_src),
+            ForeignLang::Java,
+        )
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_cpp_symbol_prefix_all_symbols() {
+    let _ = env_logger::try_init();
+    let rust_src = r#"
+foreign_enum!(enum Mode { A = Mode::A, B = Mode::B, });
+foreign_callback!(callback Observer {
+    self_type Observer;
+    on_event = Observer::on_event(&self, x: i32, s: &str) -> bool;
+});
+foreign_class!(
+#[derive(SharedPtr)]
+class Session {
+    self_type Session;
+    constructor Session::new() -> Arc<Mutex<Session>>;
+    fn Session::id(&self) -> i32;
+});
+foreign_class!(
+#[namespace = "net"]
+#[derive(Clone)]
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: &str) -> Foo;
+    fn Foo::name(&self) -> String;
+    fn Foo::read(&self) -> Result<Vec<i32>, String>;
+    fn Foo::opt(&self) -> Option<f64>;
+    fn Foo::sum(&self, _: &[i32]) -> i64;
+    fn Foo::mode(&self, _: Mode) -> Mode;
+    fn Foo::observe(&self, _: Box<dyn Observer>);
+    fn Foo::make_many() -> Vec<Foo>;
+    private fn Foo::secret(&self) -> i32;
+    fn Foo::session(&self) -> Arc<Mutex<Session>>;
+});
+"#;
+    let code_pair = parse_code(
+        "cpp_symbol_prefix_all_symbols",
+        Source::Str(rust_src),
+        ForeignLang::Cpp,
+    )
+    .unwrap();
+    let rust_code = syn::parse_file(&code_pair.rust_code).unwrap();
+
+    struct ExportedSymbols(Vec<String>);
+    impl<'ast> syn::visit::Visit<'ast> for ExportedSymbols {
+        fn visit_item_fn(&mut self, f: &'ast syn::ItemFn) {
+            let export_name = f.attrs.iter().find_map(|a| match a.parse_meta() {
+                Ok(syn::Meta::NameValue(ref nv)) if nv.path.is_ident("export_name") => {
+                    match nv.lit {
+                        syn::Lit::Str(ref name) => Some(name.value()),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            });
+            let no_mangle = f.attrs.iter().any(|a| a.path.is_ident("no_mangle"));
+            if let Some(name) = export_name {
+                self.0.push(name);
+            } else if no_mangle {
+                self.0.push(f.sig.ident.to_string());
+            }
+            syn::visit::visit_item_fn(self, f);
+        }
+    }
+    let mut symbols = ExportedSymbols(vec![]);
+    syn::visit::visit_file(&mut symbols, &rust_code);
+    assert!(symbols.0.len() > 20);
+    for name in &symbols.0 {
+        assert!(
+            name.starts_with("mylib_v2_"),
+            "symbol without prefix: {}",
+            name
+        );
+    }
+    assert!(symbols
+        .0
+        .iter()
+        .any(|x| x == "mylib_v2_private_Session_clone"));
+    assert!(symbols
+        .0
+        .iter()
+        .any(|x| x == "mylib_v2_private_net_Foo_secret"));
+}
+
+#[test]
+fn test_cpp_use_exceptions_not_supported_result() {
+    let _ = env_logger::try_init();
+    for rust_src in &[
+        r#"
+foreign_class!(class Connection {
+    self_type Connection;
+    constructor Connection::open(path: &str) -> Result<Connection, String>;
+    fn Connection::is_open(&self) -> bool;
+});
+"#,
+        r#"
+foreign_typemap!(
+    ($p:r_type) Result<u64, String> => u64 {
+        $out = $p.unwrap_or(0);
+    };
+    ($p:f_type) => "uint64_t";
+);
+foreign_class!(class Storage {
+    fn Storage::size() -> Result<u64, String>;
+});
+"#,
+    ] {
+        let result = panic::catch_unwind(|| {
+            parse_code(
+                "cpp_use_exceptions",
+                Source::Str(rust_src),
+                ForeignLang::Cpp,
+            )
+            .expect("cpp_use_exceptions");
+        });
+        assert!(result.is_err());
+    }
+
+    let code_pair = parse_code(
+        "cpp_use_exceptions",
+        Source::Str(
+            r#"
+foreign_class!(class Connection {
+    self_type Connection;
+    private constructor = empty;
+    fn Connection::open(path: &str) -> Result<Connection, String>;
+    fn Connection::is_open(&self) -> bool;
+});
+"#,
+        ),
+        ForeignLang::Cpp,
+    )
+    .unwrap();
+    assert!(code_pair
+        .foreign_code
+        .contains("static Connection open(std::string_view path);"));
+}
+
+#[test]
+fn test_cpp_bundled_types() {
+    let _ = env_logger::try_init();
+    let code_pair = parse_test_case("cpp_bundled_types", ForeignLang::Cpp);
+    assert!(!code_pair.foreign_code.contains("#include <optional>"));
+    assert!(!code_pair.foreign_code.contains("#include <variant>"));
+    assert!(!code_pair.foreign_code.contains("#include <string_view>"));
+
+    let test_case = Path::new("tests")
+        .join("expectations")
+        .join("cpp_bundled_types.rs");
+    let code_pair = parse_code(
+        "cpp_bundled_types_not_used",
+        Source::Path(&test_case),
+        ForeignLang::Cpp,
+    )
+    .unwrap();
+    assert!(!code_pair.foreign_code.contains("class RustOptional"));
+}
+
+#[test]
+fn test_cpp_pure_c_api() {
+    let _ = env_logger::try_init();
+    let code_pair = parse_test_case("cpp_pure_c_api", ForeignLang::Cpp);
+    assert!(!code_pair.foreign_code.contains("class "));
+    assert!(!code_pair.foreign_code.contains("namespace "));
+    assert!(!code_pair.foreign_code.contains(".hpp"));
+    assert!(!code_pair.foreign_code.contains("<string"));
+    assert!(!code_pair.foreign_code.contains("_delete"));
+    assert!(!code_pair.foreign_code.contains("private_"));
+    assert!(!code_pair.foreign_code.contains("();"));
+}
+
 fn find_subsequence<T>(haystack: &[T], needle: &[T]) -> Option<usize>
 where
     for<'a> &'a [T]: PartialEq,
@@ -569,7 +859,9 @@ fn collect_code_in_dir(dir_with_code: &Path, exts: &[&str]) -> Result<String, Er
     let mut code = String::new();
     for path in fs::read_dir(dir_with_code)? {
         let path = path?;
-        if path.file_type()?.is_file()
+        if path.file_type()?.is_dir() {
+            code.push_str(&collect_code_in_dir(&path.path(), exts)?);
+        } else if path.file_type()?.is_file()
             && exts
                 .iter()
                 .any(|ext| path.path().to_str().map_or(false, |x| x.ends_with(ext)))
@@ -581,6 +873,104 @@ fn collect_code_in_dir(dir_with_code: &Path, exts: &[&str]) -> Result<String, Er
     Ok(code)
 }
 
+/// Generate code for `tests/expectations/{test_name}.rs`
+fn parse_test_case(test_name: &str, lang: ForeignLang) -> CodePair {
+    let test_case = Path::new("tests")
+        .join("expectations")
+        .join(format!("{}.rs", test_name));
+    parse_code(test_name, Source::Path(&test_case), lang).expect("parse_code failed")
+}
+
+/// `LanguageConfig` for test, tests of not default options are listed here
+fn test_config(test_name: &str, lang: ForeignLang, out_dir: PathBuf) -> LanguageConfig {
+    match lang {
+        ForeignLang::Java => {
+            let java_cfg = JavaConfig::new(out_dir, "org.example".into());
+            LanguageConfig::JavaConfig(match test_name {
+                "java_exception_for_error_type" => java_cfg.register_exception(
+                    JavaException::new("StorageError".into(), "StorageException".into())
+                        .variant("NotFound".into(), "StorageNotFoundException".into()),
+                ),
+                "java_exception_error_codes" => java_cfg.register_exception(
+                    JavaException::new("StorageError".into(), "StorageException".into())
+                        .error_code("NotFound".into(), 1)
+                        .error_code("PermissionDenied".into(), 2),
+                ),
+                "java_callback_exception_policy" => {
+                    java_cfg.callback_exception_policy(JavaCallbackExceptionPolicy::Panic)
+                }
+                "java_direct_byte_buffer" => java_cfg.use_direct_byte_buffer(true),
+                "java_register_natives" => {
+                    java_cfg.register_natives(true).generate_keep_rules(true)
+                }
+                "java_register_natives_callback_arg" => java_cfg.register_natives(true),
+                "java_register_natives_callback_arg_kotlin" => java_cfg
+                    .register_natives(true)
+                    .jvm_language(JvmLanguage::Kotlin),
+                "java_object_cleanup_cleaner" => {
+                    java_cfg.object_cleanup(JavaObjectCleanup::Cleaner)
+                }
+                "java_object_cleanup_phantom_reference" => {
+                    java_cfg.object_cleanup(JavaObjectCleanup::PhantomReferenceCleaner)
+                }
+                "java_kotlin"
+                | "java_kotlin_null_annotations_typemap"
+                | "java_kotlin_typemap_with_java_code"
+                | "java_return_boxed_trait_object_kotlin" => {
+                    java_cfg.jvm_language(JvmLanguage::Kotlin)
+                }
+                "java_keep_rules" => java_cfg
+                    .generate_keep_rules(true)
+                    .object_cleanup(JavaObjectCleanup::Cleaner)
+                    .register_exception(JavaException::new("MyError".into(), "MyException".into())),
+                "java_check_deleted_objects" => java_cfg.check_deleted_objects(true),
+                "java_vec_mapping_list" => java_cfg.vec_mapping(JavaVecMapping::List),
+                "java_vec_mapping_lazy_list" | "java_lazy_list_not_clone" => {
+                    java_cfg.vec_mapping(JavaVecMapping::LazyList)
+                }
+                "java_unsigned_mapping_widen" => {
+                    java_cfg.unsigned_mapping(JavaUnsignedMapping::Widen)
+                }
+                "java_unsigned_mapping_checked" => {
+                    java_cfg.unsigned_mapping(JavaUnsignedMapping::Checked)
+                }
+                "java_unsigned_mapping_big_integer" => {
+                    java_cfg.unsigned_mapping(JavaUnsignedMapping::BigInteger)
+                }
+                "java_unsigned_mapping_same_width" => {
+                    java_cfg.unsigned_mapping(JavaUnsignedMapping::SameWidth)
+                }
+                "java_load_unload_hooks" => java_cfg
+                    .on_load_hook("crate::init_logger".into())
+                    .on_load_hook("init_runtime".into())
+                    .on_unload_hook("crate::stop_runtime".into())
+                    .on_unload_hook("flush_logger".into()),
+                "java_fast_critical_native" | "java_critical_native_not_primitive" => {
+                    java_cfg.register_natives(true)
+                }
+                _ => java_cfg.use_null_annotation_from_package("android.support.annotation".into()),
+            })
+        }
+        ForeignLang::Cpp => {
+            let cpp_cfg = CppConfig::new(out_dir, "org_examples".into());
+            LanguageConfig::CppConfig(match test_name {
+                "cpp_use_exceptions" => cpp_cfg.use_exceptions(true),
+                "cpp_pure_c_api" => cpp_cfg.pure_c_api(true),
+                "cpp_generate_package" => cpp_cfg.generate_package(
+                    CppPackage::new("storage".into(), "/opt/storage/lib".into(), "1.2.3".into())
+                        .description("Storage library".into()),
+                ),
+                "cpp_bundled_types" => cpp_cfg.use_bundled(),
+                "cpp_qt_typemaps" => cpp_cfg.qt_typemaps(true),
+                "cpp_symbol_prefix" | "cpp_symbol_prefix_all_symbols" => cpp_cfg
+                    .symbol_prefix("mylib".into())
+                    .symbol_version("v2".into()),
+                _ => cpp_cfg,
+            })
+        }
+    }
+}
+
 enum Source<'a> {
     Str(&'a str),
     Path(&'a Path),
@@ -588,25 +978,12 @@ enum Source<'a> {
 
 fn parse_code(test_name: &str, rust_src: Source, lang: ForeignLang) -> Result<CodePair, Error> {
     let tmp_dir = tempdir().expect("Can not create tmp directory");
-    let (swig_gen, ext_list): (Generator, &[&'static str]) = match lang {
-        ForeignLang::Java => {
-            let swig_gen = Generator::new(LanguageConfig::JavaConfig(
-                JavaConfig::new(tmp_dir.path().into(), "org.example".into())
-                    .use_null_annotation_from_package("android.support.annotation".into()),
-            ))
-            .with_pointer_target_width(64);
-
-            (swig_gen, &[".java"])
-        }
-        ForeignLang::Cpp => {
-            let swig_gen = Generator::new(LanguageConfig::CppConfig(CppConfig::new(
-                tmp_dir.path().into(),
-                "org_examples".into(),
-            )))
-            .with_pointer_target_width(64);
-            (swig_gen, &[".h", ".hpp"])
-        }
+    let ext_list: &[&'static str] = match lang {
+        ForeignLang::Java => &[".java", ".kt", ".pro"],
+        ForeignLang::Cpp => &[".h", ".hpp", ".cmake", ".pc"],
     };
+    let swig_gen = Generator::new(test_config(test_name, lang, tmp_dir.path().into()))
+        .with_pointer_target_width(64);
 
     let rust_code_path = tmp_dir.path().join("test.rs");
     match rust_src {